@@ -1,9 +1,11 @@
 //! Example demonstrating HTTP request helpers
 //!
 //! This example shows how to use the high-level async helpers for HTTP requests.
+//! The requests go to a small JSON API the example serves on a local port, so
+//! it works offline. It needs the `http` feature of `hojicha-core`.
 
 use hojicha_core::{
-    async_helpers::{http_get, http_post, HttpError},
+    async_helpers::{http_get, http_post},
     commands,
     core::{Cmd, Model},
     event::{Event, Key},
@@ -16,11 +18,15 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
-use std::time::Duration;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
 
 /// HTTP demo application
 #[derive(Clone)]
 struct HttpDemo {
+    /// Address of the local API
+    base_url: String,
     /// Current API endpoint
     endpoint: String,
     /// Request status
@@ -127,17 +133,17 @@ impl Model for HttpDemo {
                     self.update(Event::User(Msg::Clear))
                 }
                 Key::Char('1') => {
-                    self.endpoint = "https://api.example.com/users".to_string();
+                    self.endpoint = format!("{}/users", self.base_url);
                     self.log("Endpoint changed to /users");
                     Cmd::none()
                 }
                 Key::Char('2') => {
-                    self.endpoint = "https://api.example.com/posts".to_string();
+                    self.endpoint = format!("{}/posts", self.base_url);
                     self.log("Endpoint changed to /posts");
                     Cmd::none()
                 }
                 Key::Char('3') => {
-                    self.endpoint = "https://api.example.com/comments".to_string();
+                    self.endpoint = format!("{}/comments", self.base_url);
                     self.log("Endpoint changed to /comments");
                     Cmd::none()
                 }
@@ -252,9 +258,10 @@ impl Model for HttpDemo {
 }
 
 impl HttpDemo {
-    fn new() -> Self {
+    fn new(base_url: String) -> Self {
         Self {
-            endpoint: "https://api.example.com/data".to_string(),
+            endpoint: format!("{}/data", base_url),
+            base_url,
             status: RequestStatus::Idle,
             response: None,
             error: None,
@@ -272,12 +279,68 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("HTTP Request Demo");
     println!("=================");
     println!();
+    let base_url = serve_locally()?;
     println!("This demo shows how to use the high-level HTTP helpers.");
-    println!("Note: Requests return mock data for demonstration.");
+    println!("Requests go to a small API served on {}.", base_url);
     println!();
 
-    let model = HttpDemo::new();
+    let model = HttpDemo::new(base_url);
     let program = Program::with_options(model, ProgramOptions::default())?;
     program.run()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Serve a tiny JSON API on a free local port and return its address
+///
+/// Every request is answered with the method and path it used and the size
+/// of its body.
+fn serve_locally() -> std::io::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = answer(stream) {
+                eprintln!("Local API failed to answer: {}", e);
+            }
+        }
+    });
+    Ok(url)
+}
+
+/// Read one request from `stream` and answer it
+fn answer(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET");
+    let path = parts.next().unwrap_or("/");
+    let json = format!(
+        r#"{{"method": "{}", "path": "{}", "received_bytes": {}}}"#,
+        method,
+        path,
+        body.len()
+    );
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        json.len(),
+        json
+    )
+}
@@ -1,8 +1,15 @@
 //! File I/O helper commands
 
+use super::watch::FileWatcher;
 use crate::core::{Cmd, Message};
 use crate::commands;
+use crate::subscriptions::Sub;
+use futures::future;
+use futures::stream::{BoxStream, StreamExt};
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
 
 /// File operation errors
 #[derive(Debug, Clone)]
@@ -40,7 +47,12 @@ pub enum FileEvent {
     /// File was deleted
     Deleted(PathBuf),
     /// File was renamed
-    Renamed { from: PathBuf, to: PathBuf },
+    Renamed {
+        /// Path before the rename
+        from: PathBuf,
+        /// Path after the rename
+        to: PathBuf,
+    },
 }
 
 /// Read a file asynchronously
@@ -111,6 +123,390 @@ where
     })
 }
 
+/// An event from reading a file line by line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineEvent {
+    /// Lines read since the last event, without their line endings
+    Lines(Vec<String>),
+    /// How far reading has got
+    Progress {
+        /// Offset in the file after the last line read
+        bytes_read: u64,
+        /// Size of the file
+        total: u64,
+    },
+    /// The file got shorter and is read again from the start (when following)
+    Truncated,
+    /// The path now names a new file, which is read from the start (when
+    /// following)
+    Rotated,
+    /// The end of the file was reached (when not following)
+    Finished,
+}
+
+/// Where a [`LineReader`] starts
+#[derive(Debug, Clone, Copy)]
+enum ReadStart {
+    At(SeekFrom),
+    LastLines(usize),
+}
+
+/// Streams a file in batches of lines, optionally following it like `tail -F`
+///
+/// Only a batch of lines is held in memory at a time, so this suits files of
+/// any size. After each batch a [`LineEvent::Progress`] says how far reading
+/// has got. Invalid UTF-8 is replaced.
+///
+/// # Example
+/// ```no_run
+/// # use hojicha_core::async_helpers::file_io::{LineEvent, LineReader};
+/// # use hojicha_core::Cmd;
+/// # enum Msg { Lines(Vec<String>), Failed(String) }
+/// // Show the last 100 lines of the log and everything appended to it
+/// let cmd: Cmd<Msg> = LineReader::new("/var/log/app.log")
+///     .last_lines(100)
+///     .follow()
+///     .read(|event| match event {
+///         Ok(LineEvent::Lines(lines)) => Some(Msg::Lines(lines)),
+///         Ok(_) => None,
+///         Err(e) => Some(Msg::Failed(e.to_string())),
+///     })
+///     .keyed("tail");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineReader {
+    path: PathBuf,
+    start: ReadStart,
+    batch_size: usize,
+    follow: bool,
+    poll_interval: Duration,
+}
+
+impl LineReader {
+    /// Create a reader for `path` that reads it once from the start
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            start: ReadStart::At(SeekFrom::Start(0)),
+            batch_size: 1000,
+            follow: false,
+            poll_interval: Duration::from_millis(250),
+        }
+    }
+
+    /// Start reading at `position`, e.g. `SeekFrom::End(-4096)`
+    ///
+    /// Unless the position is at the start of a line, reading begins with the
+    /// next line. An offset from the end that reaches past the start of the
+    /// file reads it all.
+    pub fn start_at(mut self, position: SeekFrom) -> Self {
+        self.start = ReadStart::At(position);
+        self
+    }
+
+    /// Start reading with the last `lines` lines of the file
+    pub fn last_lines(mut self, lines: usize) -> Self {
+        self.start = ReadStart::LastLines(lines);
+        self
+    }
+
+    /// Set the most lines sent in one [`LineEvent::Lines`] (1000 by default)
+    pub fn batch_size(mut self, lines: usize) -> Self {
+        self.batch_size = lines.max(1);
+        self
+    }
+
+    /// Keep reading what is appended after reaching the end, like `tail -F`
+    ///
+    /// If the file is truncated it is read again from the start. If the path
+    /// is replaced, e.g. by log rotation, the rest of the old file is read
+    /// (including a last line without a newline) and then the new file from
+    /// the start. Otherwise a line is only sent once its newline has been
+    /// written. The file doesn't have to exist yet.
+    ///
+    /// Replaced files are recognized by their device and inode, so rotation
+    /// is only detected on Unix. Elsewhere a new file that is shorter than the
+    /// old one is read from the start as a truncation, and a longer one is
+    /// missed.
+    pub fn follow(mut self) -> Self {
+        self.follow = true;
+        self
+    }
+
+    /// Set how often a followed file is checked for new data (250ms by default)
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Create a command that passes every event to `handler`
+    pub fn read<M, F>(&self, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnMut(Result<LineEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        commands::stream(self.messages(handler))
+    }
+
+    /// Create a subscription that reads while it is listed
+    pub fn subscribe<M, F>(&self, key: impl Into<String>, handler: F) -> Sub<M>
+    where
+        M: Message,
+        F: FnMut(Result<LineEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        let reader = self.clone();
+        Sub::stream(key, move || reader.messages(handler))
+    }
+
+    fn messages<M, F>(&self, mut handler: F) -> BoxStream<'static, M>
+    where
+        M: Message,
+        F: FnMut(Result<LineEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        self.events()
+            .filter_map(move |event| future::ready(handler(event)))
+            .boxed()
+    }
+
+    /// Stream the events
+    ///
+    /// The stream ends after [`LineEvent::Finished`] or the first error. When
+    /// following it only ends on an error.
+    pub fn events(&self) -> BoxStream<'static, Result<LineEvent, FileError>> {
+        let reader = self.clone();
+        let events = async_stream::stream! {
+            let path = reader.path.clone();
+            let file = loop {
+                match tokio::fs::File::open(&path).await {
+                    Ok(file) => break file,
+                    Err(e) if reader.follow && e.kind() == std::io::ErrorKind::NotFound => {
+                        tokio::time::sleep(reader.poll_interval).await;
+                    }
+                    Err(e) => {
+                        yield Err(file_error(e, &path));
+                        return;
+                    }
+                }
+            };
+            let mut identity = match file.metadata().await {
+                Ok(metadata) => file_identity(&metadata),
+                Err(e) => {
+                    yield Err(file_error(e, &path));
+                    return;
+                }
+            };
+            let mut file = BufReader::new(file);
+            let mut position = match seek_start(&mut file, reader.start).await {
+                Ok(position) => position,
+                Err(e) => {
+                    yield Err(file_error(e, &path));
+                    return;
+                }
+            };
+
+            let mut line = Vec::new();
+            // Set once the path names a new file and the old one is being finished
+            let mut rotated = false;
+            loop {
+                let mut lines = Vec::new();
+                let mut at_end = false;
+                while lines.len() < reader.batch_size {
+                    match file.read_until(b'\n', &mut line).await {
+                        Ok(0) => {
+                            at_end = true;
+                            break;
+                        }
+                        Ok(n) => {
+                            position += n as u64;
+                            if line.ends_with(b"\n") {
+                                lines.push(decode_line(&line));
+                                line.clear();
+                            }
+                        }
+                        Err(e) => {
+                            yield Err(file_error(e, &path));
+                            return;
+                        }
+                    }
+                }
+                // Without following, or once the file has been replaced, the last
+                // line needn't end with a newline
+                if at_end && (!reader.follow || rotated) && !line.is_empty() {
+                    lines.push(decode_line(&line));
+                    line.clear();
+                }
+
+                if !lines.is_empty() {
+                    yield Ok(LineEvent::Lines(lines));
+                    let total = match file.get_ref().metadata().await {
+                        Ok(metadata) => metadata.len(),
+                        Err(e) => {
+                            yield Err(file_error(e, &path));
+                            return;
+                        }
+                    };
+                    yield Ok(LineEvent::Progress { bytes_read: position, total });
+                }
+                if !at_end {
+                    continue;
+                }
+                if !reader.follow {
+                    yield Ok(LineEvent::Finished);
+                    return;
+                }
+
+                if rotated {
+                    // The old file is exhausted, so switch to the new one
+                    rotated = false;
+                    let Ok(new_file) = tokio::fs::File::open(&path).await else {
+                        continue;
+                    };
+                    let Ok(metadata) = new_file.metadata().await else {
+                        continue;
+                    };
+                    file = BufReader::new(new_file);
+                    identity = file_identity(&metadata);
+                    position = 0;
+                    yield Ok(LineEvent::Rotated);
+                    continue;
+                }
+
+                tokio::time::sleep(reader.poll_interval).await;
+                // A missing path is waited out; the old file may still grow
+                let Ok(metadata) = tokio::fs::metadata(&path).await else {
+                    continue;
+                };
+                if file_identity(&metadata) != identity {
+                    // Lines written just before the rename are still to be read
+                    rotated = true;
+                } else if metadata.len() < position {
+                    if let Err(e) = file.seek(SeekFrom::Start(0)).await {
+                        yield Err(file_error(e, &path));
+                        return;
+                    }
+                    position = 0;
+                    line.clear();
+                    yield Ok(LineEvent::Truncated);
+                }
+            }
+        };
+        events.boxed()
+    }
+}
+
+/// Read a file in batches of lines
+///
+/// See [`LineReader`] for following the file and starting elsewhere.
+pub fn read_lines<M, F, P>(path: P, handler: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnMut(Result<LineEvent, FileError>) -> Option<M> + Send + 'static,
+    P: AsRef<Path> + Send + 'static,
+{
+    LineReader::new(path.as_ref()).read(handler)
+}
+
+/// Send the last `lines` lines of a file, then everything appended to it
+///
+/// Like `tail -F`, this survives truncation and rotation. Run it under a key
+/// with [`Cmd::keyed`] to be able to [`cancel`](commands::cancel) it.
+pub fn tail_file<M, F, P>(path: P, lines: usize, handler: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnMut(Result<LineEvent, FileError>) -> Option<M> + Send + 'static,
+    P: AsRef<Path> + Send + 'static,
+{
+    LineReader::new(path.as_ref())
+        .last_lines(lines)
+        .follow()
+        .read(handler)
+}
+
+/// Convert an I/O error about `path`
+fn file_error(error: std::io::Error, path: &Path) -> FileError {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => FileError::NotFound(path.to_path_buf()),
+        std::io::ErrorKind::PermissionDenied => FileError::PermissionDenied(path.to_path_buf()),
+        _ => FileError::IoError(error.to_string()),
+    }
+}
+
+/// Tells files apart when a path is replaced
+///
+/// Always `None` outside Unix, where replaced files aren't recognized.
+#[cfg(unix)]
+fn file_identity(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Strip the line ending and decode a line
+fn decode_line(line: &[u8]) -> String {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8_lossy(line).into_owned()
+}
+
+/// Move to where reading starts, at the beginning of a line
+async fn seek_start(file: &mut BufReader<tokio::fs::File>, start: ReadStart) -> std::io::Result<u64> {
+    let position = match start {
+        // A file shorter than the offset is read from its start
+        ReadStart::At(SeekFrom::End(offset)) if offset < 0 => {
+            let len = file.get_ref().metadata().await?.len();
+            let position = len.saturating_sub(offset.unsigned_abs());
+            file.seek(SeekFrom::Start(position)).await?
+        }
+        ReadStart::At(position) => file.seek(position).await?,
+        ReadStart::LastLines(lines) => {
+            let offset = last_lines_offset(file.get_mut(), lines).await?;
+            return file.seek(SeekFrom::Start(offset)).await;
+        }
+    };
+    if position == 0 {
+        return Ok(0);
+    }
+
+    // Skip the rest of the line the position falls into, unless it starts one
+    file.seek(SeekFrom::Start(position - 1)).await?;
+    let skipped = file.read_until(b'\n', &mut Vec::new()).await?;
+    Ok(position - 1 + skipped as u64)
+}
+
+/// Find where the last `lines` lines of a file start, reading backwards
+async fn last_lines_offset(file: &mut tokio::fs::File, lines: usize) -> std::io::Result<u64> {
+    let len = file.metadata().await?.len();
+    if lines == 0 {
+        return Ok(len);
+    }
+
+    let mut chunk = vec![0; 8192];
+    let mut end = len;
+    let mut newlines = 0;
+    while end > 0 {
+        let start = end.saturating_sub(chunk.len() as u64);
+        let chunk = &mut chunk[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start)).await?;
+        file.read_exact(chunk).await?;
+        for (i, byte) in chunk.iter().enumerate().rev() {
+            let offset = start + i as u64;
+            // The newline ending the file ends the last line
+            if *byte == b'\n' && offset != len - 1 {
+                newlines += 1;
+                if newlines == lines {
+                    return Ok(offset + 1);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
 /// Write to a file asynchronously
 ///
 /// # Example
@@ -156,9 +552,10 @@ where
 
 /// Watch a file for changes
 ///
-/// This creates a file watcher that will send events when the file changes.
-/// Note: This is a simplified implementation. A real implementation would use
-/// notify or similar crate for actual file system watching.
+/// This creates a file watcher that sends events while the command runs. A
+/// directory is watched with its subdirectories. Errors are logged; use a
+/// [`FileWatcher`](super::watch::FileWatcher) to receive them, filter paths
+/// or watch as a subscription.
 ///
 /// # Example
 /// ```no_run
@@ -185,12 +582,12 @@ where
     P: AsRef<Path> + Send + 'static,
 {
     let path = path.as_ref().to_path_buf();
-    
-    commands::spawn(async move {
-        // In a real implementation, this would use notify or similar
-        // For now, we'll simulate a file change after a delay
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-        handler(FileEvent::Modified(path))
+    FileWatcher::new(&path).watch(move |result| match result {
+        Ok(event) => handler(event),
+        Err(e) => {
+            log::error!("Watching {} failed: {}", path.display(), e);
+            None
+        }
     })
 }
 
@@ -279,4 +676,190 @@ where
         
         Some(handler(result))
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Collect events until the stream ends
+    async fn collect(reader: LineReader) -> Vec<LineEvent> {
+        reader.events().map(Result::unwrap).collect().await
+    }
+
+    fn lines(events: &[LineEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                LineEvent::Lines(lines) => Some(lines.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Wait for the next event from a followed file
+    async fn next(events: &mut BoxStream<'static, Result<LineEvent, FileError>>) -> LineEvent {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("no line event")
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Skip progress events and return the next lines
+    async fn next_lines(events: &mut BoxStream<'static, Result<LineEvent, FileError>>) -> LineEvent {
+        loop {
+            match next(events).await {
+                LineEvent::Progress { .. } => {}
+                event => return event,
+            }
+        }
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new().append(true).create(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_lines_in_batches_with_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.log");
+        let content: String = (0..2500).map(|i| format!("line {}\r\n", i)).collect();
+        std::fs::write(&path, format!("{}last", content)).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+
+        let events = collect(LineReader::new(&path)).await;
+        let batches: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                LineEvent::Lines(lines) => Some(lines.len()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(batches, vec![1000, 1000, 501]);
+        assert_eq!(lines(&events)[1234], "line 1234");
+        assert_eq!(lines(&events).last().unwrap(), "last");
+        assert_eq!(
+            events[events.len() - 2],
+            LineEvent::Progress { bytes_read: size, total: size }
+        );
+        assert_eq!(events.last(), Some(&LineEvent::Finished));
+    }
+
+    #[tokio::test]
+    async fn test_starts_from_the_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+
+        let events = collect(LineReader::new(&path).last_lines(2)).await;
+        assert_eq!(lines(&events), vec!["three", "four"]);
+
+        let events = collect(LineReader::new(&path).last_lines(10)).await;
+        assert_eq!(lines(&events).len(), 4);
+
+        // Lands in the middle of "three", so reading starts at "four"
+        let events = collect(LineReader::new(&path).start_at(SeekFrom::End(-8))).await;
+        assert_eq!(lines(&events), vec!["four"]);
+
+        // Lands at the start of "four"
+        let events = collect(LineReader::new(&path).start_at(SeekFrom::End(-5))).await;
+        assert_eq!(lines(&events), vec!["four"]);
+    }
+
+    #[tokio::test]
+    async fn test_start_before_a_short_file_reads_all_of_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short.log");
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+
+        let events = collect(LineReader::new(&path).start_at(SeekFrom::End(-4096))).await;
+        assert_eq!(lines(&events), vec!["one", "two"]);
+        assert_eq!(events.last(), Some(&LineEvent::Finished));
+    }
+
+    #[tokio::test]
+    async fn test_follows_appends_truncation_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old\nnew\n").unwrap();
+
+        let mut events = LineReader::new(&path)
+            .last_lines(1)
+            .follow()
+            .poll_interval(Duration::from_millis(10))
+            .events();
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["new".into()]));
+
+        // Partial lines wait for their newline
+        append(&path, "appe");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        append(&path, "nded\n");
+        assert_eq!(
+            next_lines(&mut events).await,
+            LineEvent::Lines(vec!["appended".into()])
+        );
+
+        std::fs::write(&path, "x\n").unwrap();
+        assert_eq!(next_lines(&mut events).await, LineEvent::Truncated);
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["x".into()]));
+
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        std::fs::write(&path, "fresh\n").unwrap();
+        assert_eq!(next_lines(&mut events).await, LineEvent::Rotated);
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["fresh".into()]));
+    }
+
+    #[tokio::test]
+    async fn test_rotation_reads_the_rest_of_the_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "first\n").unwrap();
+
+        let mut events = LineReader::new(&path)
+            .follow()
+            .poll_interval(Duration::from_millis(100))
+            .events();
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["first".into()]));
+
+        // Written while the reader sleeps, then rotated away before it wakes
+        append(&path, "last words\npartial");
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        std::fs::write(&path, "fresh\n").unwrap();
+
+        let mut old = Vec::new();
+        loop {
+            match next_lines(&mut events).await {
+                LineEvent::Lines(lines) => old.extend(lines),
+                event => {
+                    assert_eq!(event, LineEvent::Rotated);
+                    break;
+                }
+            }
+        }
+        assert_eq!(old, vec!["last words", "partial"]);
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["fresh".into()]));
+    }
+
+    #[tokio::test]
+    async fn test_missing_files_are_errors_unless_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("later.log");
+
+        let mut events = LineReader::new(&path).events();
+        assert!(matches!(events.next().await, Some(Err(FileError::NotFound(_)))));
+        assert!(events.next().await.is_none());
+
+        let mut events = LineReader::new(&path)
+            .follow()
+            .poll_interval(Duration::from_millis(10))
+            .events();
+        let waiting = tokio::time::timeout(Duration::from_millis(50), events.next()).await;
+        assert!(waiting.is_err());
+        std::fs::write(&path, "hello\n").unwrap();
+        assert_eq!(next_lines(&mut events).await, LineEvent::Lines(vec!["hello".into()]));
+    }
+}
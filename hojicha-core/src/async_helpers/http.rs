@@ -1,12 +1,26 @@
 //! HTTP request helper commands
+//!
+//! Requests are sent through an [`HttpTransport`]. With the `http` cargo
+//! feature the default transport is [`HyperTransport`], an HTTP/1.1 client;
+//! without it every request fails with [`HttpError::Unsupported`]. Use an
+//! [`HttpClient`] to pick another transport, such as an in-process fake in
+//! tests or one that speaks TLS.
+//!
+//! Responses are returned for every status code; only transport failures
+//! and timeouts are errors. [`http_with_retry`] also retries on `408`, `429`
+//! and `5xx` responses, but only requests that are safe to send twice: those
+//! with an idempotent method, or marked with [`HttpRequest::idempotent`].
 
-use crate::core::{Cmd, Message};
+use super::AsyncConfig;
 use crate::commands;
-use super::{AsyncConfig, AsyncResult};
+use crate::core::{Cmd, Message};
+use futures::future::BoxFuture;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
 /// HTTP methods
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
     /// GET request
     Get,
@@ -20,6 +34,29 @@ pub enum HttpMethod {
     Patch,
     /// HEAD request
     Head,
+    /// OPTIONS request
+    Options,
+}
+
+impl HttpMethod {
+    /// Get the method name as sent on the wire
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Delete => "DELETE",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Options => "OPTIONS",
+        }
+    }
+
+    /// Whether sending a request with this method twice has the same effect
+    /// as sending it once (GET, HEAD, PUT, DELETE and OPTIONS)
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, HttpMethod::Post | HttpMethod::Patch)
+    }
 }
 
 /// HTTP request error
@@ -35,22 +72,82 @@ pub enum HttpError {
     ServerError(u16, String),
     /// Parse error
     ParseError(String),
+    /// The request can't be built, e.g. because of an invalid header
+    InvalidRequest(String),
+    /// The transport can't send this request, e.g. an `https` URL without TLS
+    Unsupported(String),
+}
+
+impl HttpError {
+    /// Whether sending the same request again might succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, HttpError::NetworkError(_) | HttpError::Timeout)
+    }
 }
 
-impl std::fmt::Display for HttpError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             HttpError::NetworkError(e) => write!(f, "Network error: {}", e),
             HttpError::Timeout => write!(f, "Request timed out"),
             HttpError::InvalidUrl(url) => write!(f, "Invalid URL: {}", url),
             HttpError::ServerError(code, msg) => write!(f, "Server error {}: {}", code, msg),
             HttpError::ParseError(e) => write!(f, "Parse error: {}", e),
+            HttpError::InvalidRequest(e) => write!(f, "Invalid request: {}", e),
+            HttpError::Unsupported(e) => write!(f, "Unsupported request: {}", e),
         }
     }
 }
 
 impl std::error::Error for HttpError {}
 
+/// An HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// Request method
+    pub method: HttpMethod,
+    /// Absolute URL
+    pub url: String,
+    /// Request headers
+    pub headers: HashMap<String, String>,
+    /// Request body
+    pub body: Option<Vec<u8>>,
+    /// Whether the request may be retried; true for idempotent methods
+    pub idempotent: bool,
+}
+
+impl HttpRequest {
+    /// Create a request without headers or body
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: HashMap::new(),
+            body: None,
+            idempotent: method.is_idempotent(),
+        }
+    }
+
+    /// Allow retrying the request although its method isn't idempotent,
+    /// e.g. a POST the server deduplicates by an idempotency key
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
+    }
+
+    /// Add a header
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set the body
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
 /// HTTP response
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
@@ -64,6 +161,323 @@ pub struct HttpResponse {
     pub bytes: Vec<u8>,
 }
 
+impl HttpResponse {
+    /// Create a response, decoding the body as UTF-8 for `body`
+    ///
+    /// Invalid UTF-8 is replaced in `body`; `bytes` keeps the body as received.
+    pub fn new(status: u16, headers: HashMap<String, String>, bytes: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers,
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+            bytes,
+        }
+    }
+
+    /// Whether the status is in the 2xx range
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Whether the status says the request may succeed if sent again
+    /// (`408`, `429` or `5xx`)
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.status, 408 | 429 | 500..=599)
+    }
+}
+
+/// Sends HTTP requests for an [`HttpClient`]
+///
+/// Implement this to route requests somewhere else, for example to an
+/// in-process fake in tests.
+pub trait HttpTransport: Send + Sync {
+    /// Send a request and wait for the whole response
+    fn send(&self, request: HttpRequest) -> BoxFuture<'static, Result<HttpResponse, HttpError>>;
+}
+
+/// Sends requests through a transport, with the timeout and retries of an
+/// [`AsyncConfig`]
+///
+/// `HttpClient::default()` uses the default transport and config. Keep a
+/// client around to reuse its connections.
+///
+/// # Example
+/// ```no_run
+/// # use hojicha_core::async_helpers::{AsyncConfig, BackoffStrategy};
+/// # use hojicha_core::async_helpers::http::{HttpClient, HttpMethod, HttpRequest};
+/// # use hojicha_core::Cmd;
+/// # use std::time::Duration;
+/// # enum Msg { Saved(u16), Failed(String) }
+/// let client = HttpClient::default().with_config(
+///     AsyncConfig::default()
+///         .with_timeout(Duration::from_secs(5))
+///         .with_retries(3, BackoffStrategy::Exponential(Duration::from_millis(100))),
+/// );
+/// let request = HttpRequest::new(HttpMethod::Put, "http://localhost:8080/notes/1")
+///     .header("Content-Type", "text/plain")
+///     .body("hello");
+/// let cmd: Cmd<Msg> = client.request(request, |result| match result {
+///     Ok(response) => Msg::Saved(response.status),
+///     Err(e) => Msg::Failed(e.to_string()),
+/// });
+/// ```
+#[derive(Clone)]
+pub struct HttpClient {
+    transport: Arc<dyn HttpTransport>,
+    config: AsyncConfig,
+}
+
+impl HttpClient {
+    /// Create a client that sends requests through `transport`
+    pub fn new(transport: impl HttpTransport + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+            config: AsyncConfig::default(),
+        }
+    }
+
+    /// Set the timeout and retries
+    ///
+    /// The timeout applies to each attempt separately.
+    pub fn with_config(mut self, config: AsyncConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Send a request, retrying as configured
+    ///
+    /// Network errors, timeouts and `408`, `429` and `5xx` responses are
+    /// retried if the request is [`idempotent`](HttpRequest::idempotent).
+    /// Once the retries are used up, the last response or error is returned.
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let mut attempts = 0;
+        loop {
+            let result = self.send_once(request.clone()).await;
+            let retryable = request.idempotent
+                && match &result {
+                    Ok(response) => response.is_retryable(),
+                    Err(error) => error.is_retryable(),
+                };
+            if !retryable || attempts >= self.config.retries {
+                return result;
+            }
+
+            attempts += 1;
+            if let Some(delay) = self.config.backoff.delay(attempts) {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    /// Send a request once, within the configured timeout
+    async fn send_once(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let response = self.transport.send(request);
+        match self.config.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, response)
+                .await
+                .unwrap_or(Err(HttpError::Timeout)),
+            None => response.await,
+        }
+    }
+
+    /// Create a command that sends a request and passes the result to `handler`
+    pub fn request<M, F>(&self, request: HttpRequest, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
+    {
+        let client = self.clone();
+        commands::spawn(async move { Some(handler(client.send(request).await)) })
+    }
+
+    /// Create a GET request command
+    pub fn get<M, F>(&self, url: impl Into<String>, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
+    {
+        self.request(HttpRequest::new(HttpMethod::Get, url), handler)
+    }
+
+    /// Create a POST request command with a JSON body
+    pub fn post<M, F>(&self, url: impl Into<String>, body: impl Into<String>, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
+    {
+        let request = HttpRequest::new(HttpMethod::Post, url)
+            .header("Content-Type", "application/json")
+            .body(body.into());
+        self.request(request, handler)
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self {
+            transport: default_transport(),
+            config: AsyncConfig::default(),
+        }
+    }
+}
+
+impl fmt::Debug for HttpClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpClient")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "http")]
+fn default_transport() -> Arc<dyn HttpTransport> {
+    Arc::new(HyperTransport::new())
+}
+
+#[cfg(not(feature = "http"))]
+fn default_transport() -> Arc<dyn HttpTransport> {
+    Arc::new(Unavailable)
+}
+
+/// The default transport when the `http` feature is off
+#[cfg(not(feature = "http"))]
+struct Unavailable;
+
+#[cfg(not(feature = "http"))]
+impl HttpTransport for Unavailable {
+    fn send(&self, _request: HttpRequest) -> BoxFuture<'static, Result<HttpResponse, HttpError>> {
+        Box::pin(async {
+            Err(HttpError::Unsupported(
+                "HTTP support is disabled, enable the `http` feature".to_string(),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "http")]
+pub use self::hyper_transport::HyperTransport;
+
+#[cfg(feature = "http")]
+mod hyper_transport {
+    use super::{HttpError, HttpRequest, HttpResponse, HttpTransport};
+    use futures::future::BoxFuture;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Bytes;
+    use hyper_util::client::legacy::{connect::HttpConnector, Client};
+    use hyper_util::rt::TokioExecutor;
+    use std::collections::HashMap;
+
+    /// An HTTP/1.1 client over plain TCP, built on hyper
+    ///
+    /// Connections are pooled and reused by clones of the transport.
+    /// Redirects are not followed, and `https` URLs are rejected with
+    /// [`HttpError::Unsupported`]; use a TLS-capable [`HttpTransport`] for
+    /// those.
+    #[derive(Clone)]
+    pub struct HyperTransport {
+        client: Client<HttpConnector, Full<Bytes>>,
+    }
+
+    impl HyperTransport {
+        /// Create a transport with an empty connection pool
+        pub fn new() -> Self {
+            Self {
+                client: Client::builder(TokioExecutor::new()).build_http(),
+            }
+        }
+    }
+
+    impl Default for HyperTransport {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl std::fmt::Debug for HyperTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("HyperTransport").finish_non_exhaustive()
+        }
+    }
+
+    impl HttpTransport for HyperTransport {
+        fn send(
+            &self,
+            request: HttpRequest,
+        ) -> BoxFuture<'static, Result<HttpResponse, HttpError>> {
+            let client = self.client.clone();
+            Box::pin(async move {
+                let request = build_request(request)?;
+                let response = client
+                    .request(request)
+                    .await
+                    .map_err(|e| HttpError::NetworkError(error_chain(&e)))?;
+
+                let status = response.status().as_u16();
+                let mut headers: HashMap<String, String> = HashMap::new();
+                for (name, value) in response.headers() {
+                    let value = String::from_utf8_lossy(value.as_bytes());
+                    headers
+                        .entry(name.as_str().to_string())
+                        .and_modify(|joined| {
+                            joined.push_str(", ");
+                            joined.push_str(&value);
+                        })
+                        .or_insert_with(|| value.into_owned());
+                }
+                let bytes = response
+                    .into_body()
+                    .collect()
+                    .await
+                    .map_err(|e| HttpError::NetworkError(error_chain(&e)))?
+                    .to_bytes();
+
+                Ok(HttpResponse::new(status, headers, bytes.to_vec()))
+            })
+        }
+    }
+
+    /// Turn a request into a hyper request, checking the URL and headers
+    fn build_request(request: HttpRequest) -> Result<hyper::Request<Full<Bytes>>, HttpError> {
+        let uri: hyper::Uri = request
+            .url
+            .parse()
+            .map_err(|_| HttpError::InvalidUrl(request.url.clone()))?;
+        match uri.scheme_str() {
+            Some("http") => {}
+            Some("https") => {
+                return Err(HttpError::Unsupported(format!(
+                    "{} needs a TLS transport",
+                    request.url
+                )))
+            }
+            _ => return Err(HttpError::InvalidUrl(request.url)),
+        }
+
+        let mut builder = hyper::Request::builder()
+            .method(request.method.as_str())
+            .uri(uri);
+        for (name, value) in &request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let body = Full::new(Bytes::from(request.body.unwrap_or_default()));
+        builder
+            .body(body)
+            .map_err(|e| HttpError::InvalidRequest(e.to_string()))
+    }
+
+    /// Format an error with its sources, which hold the useful detail
+    fn error_chain(error: &dyn std::error::Error) -> String {
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        message
+    }
+}
+
 /// Create a GET request command
 ///
 /// # Example
@@ -74,7 +488,7 @@ pub struct HttpResponse {
 /// #     DataLoaded(String),
 /// #     Error(String),
 /// # }
-/// 
+///
 /// http_get("https://api.example.com/data", |result| {
 ///     match result {
 ///         Ok(response) => Msg::DataLoaded(response.body),
@@ -88,7 +502,7 @@ where
     M: Message,
     F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
 {
-    http_request(HttpMethod::Get, url, None::<String>, None, handler)
+    HttpClient::default().get(url, handler)
 }
 
 /// Create a POST request command with JSON body
@@ -101,7 +515,7 @@ where
 /// #     Posted,
 /// #     Error(String),
 /// # }
-/// 
+///
 /// let json_body = r#"{"name": "test"}"#;
 /// http_post("https://api.example.com/data", json_body, |result| {
 ///     match result {
@@ -117,9 +531,7 @@ where
     F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
     B: Into<String>,
 {
-    let mut headers = HashMap::new();
-    headers.insert("Content-Type".to_string(), "application/json".to_string());
-    http_request(HttpMethod::Post, url, Some(body), Some(headers), handler)
+    HttpClient::default().post(url, body, handler)
 }
 
 /// Create a custom HTTP request command
@@ -138,84 +550,330 @@ where
     F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
     B: Into<String>,
 {
-    let url = url.into();
-    let body = body.map(|b| b.into());
-    
-    commands::spawn(async move {
-        // In a real implementation, we would use reqwest or similar
-        // For now, we'll create a mock implementation
-        let result = perform_http_request(method, url, body, headers).await;
-        Some(handler(result))
-    })
-}
-
-/// Internal function to perform HTTP request
-/// In a real implementation, this would use reqwest or similar
-async fn perform_http_request(
-    method: HttpMethod,
-    url: String,
-    body: Option<String>,
-    headers: Option<HashMap<String, String>>,
-) -> Result<HttpResponse, HttpError> {
-    // This is a placeholder implementation
-    // In production, you would use reqwest or another HTTP client
-    
-    // Simulate network delay
-    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-    
-    // For demonstration, return a mock response
-    Ok(HttpResponse {
-        status: 200,
+    let request = HttpRequest {
         headers: headers.unwrap_or_default(),
-        body: body.unwrap_or_else(|| format!("Mock response for {} {}", 
-            match method {
-                HttpMethod::Get => "GET",
-                HttpMethod::Post => "POST",
-                HttpMethod::Put => "PUT",
-                HttpMethod::Delete => "DELETE",
-                HttpMethod::Patch => "PATCH",
-                HttpMethod::Head => "HEAD",
-            },
-            url
-        )),
-        bytes: Vec::new(),
-    })
+        body: body.map(|b| b.into().into_bytes()),
+        ..HttpRequest::new(method, url)
+    };
+    HttpClient::default().request(request, handler)
 }
 
 /// Create an HTTP request with retry logic
-pub fn http_with_retry<M, F>(
-    method: HttpMethod,
-    url: impl Into<String>,
-    config: AsyncConfig,
-    handler: F,
-) -> Cmd<M>
+///
+/// The request is sent with the timeout of `config` and retried on network
+/// errors, timeouts and `408`, `429` and `5xx` responses, waiting between
+/// attempts as its backoff says. Requests that aren't idempotent are sent
+/// once unless marked with [`HttpRequest::idempotent`].
+pub fn http_with_retry<M, F>(request: HttpRequest, config: AsyncConfig, handler: F) -> Cmd<M>
 where
     M: Message,
     F: FnOnce(Result<HttpResponse, HttpError>) -> M + Send + 'static,
 {
-    let url = url.into();
-    
-    commands::spawn(async move {
-        let mut attempts = 0;
-        loop {
-            let result = perform_http_request(method, url.clone(), None, None).await;
-            
-            if result.is_ok() || attempts >= config.retries {
-                return Some(handler(result));
+    HttpClient::default()
+        .with_config(config)
+        .request(request, handler)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_helpers::BackoffStrategy;
+    use std::time::Duration;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Answers requests from a script and records what it was sent
+    #[derive(Clone, Default)]
+    struct FakeTransport {
+        replies: Arc<Mutex<VecDeque<Result<HttpResponse, HttpError>>>>,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+        delay: Option<Duration>,
+    }
+
+    impl FakeTransport {
+        fn replying(replies: Vec<Result<HttpResponse, HttpError>>) -> Self {
+            Self {
+                replies: Arc::new(Mutex::new(replies.into())),
+                ..Self::default()
             }
-            
-            attempts += 1;
-            
-            // Apply backoff strategy
-            match config.backoff {
-                super::BackoffStrategy::None => {},
-                super::BackoffStrategy::Linear(duration) => {
-                    tokio::time::sleep(duration * attempts).await;
-                },
-                super::BackoffStrategy::Exponential(duration) => {
-                    tokio::time::sleep(duration * 2u32.pow(attempts)).await;
-                },
+        }
+
+        fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl HttpTransport for FakeTransport {
+        fn send(
+            &self,
+            request: HttpRequest,
+        ) -> BoxFuture<'static, Result<HttpResponse, HttpError>> {
+            self.requests.lock().unwrap().push(request);
+            let reply = self.replies.lock().unwrap().pop_front();
+            let delay = self.delay;
+            Box::pin(async move {
+                if let Some(delay) = delay {
+                    tokio::time::sleep(delay).await;
+                }
+                reply.unwrap_or_else(|| Err(HttpError::NetworkError("no reply".into())))
+            })
+        }
+    }
+
+    fn status(code: u16) -> Result<HttpResponse, HttpError> {
+        Ok(HttpResponse::new(
+            code,
+            HashMap::new(),
+            code.to_string().into_bytes(),
+        ))
+    }
+
+    fn retrying(retries: u32) -> AsyncConfig {
+        AsyncConfig::default()
+            .with_retries(retries, BackoffStrategy::Linear(Duration::from_millis(1)))
+    }
+
+    #[tokio::test]
+    async fn test_retries_on_server_errors_and_network_errors() {
+        let transport = FakeTransport::replying(vec![
+            status(503),
+            Err(HttpError::NetworkError("reset".into())),
+            status(200),
+        ]);
+        let client = HttpClient::new(transport.clone()).with_config(retrying(3));
+
+        let response = client
+            .send(HttpRequest::new(HttpMethod::Get, "http://x/"))
+            .await;
+        assert_eq!(response.unwrap().status, 200);
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let transport = FakeTransport::replying(vec![status(404), status(200)]);
+        let client = HttpClient::new(transport.clone()).with_config(retrying(3));
+
+        let response = client
+            .send(HttpRequest::new(HttpMethod::Get, "http://x/"))
+            .await;
+        assert_eq!(response.unwrap().status, 404);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_idempotent_requests_are_retried() {
+        let transport = FakeTransport::replying(vec![status(503), status(200)]);
+        let client = HttpClient::new(transport.clone()).with_config(retrying(3));
+
+        let post = HttpRequest::new(HttpMethod::Post, "http://x/");
+        assert!(!post.idempotent);
+        let response = client.send(post.clone()).await;
+        assert_eq!(response.unwrap().status, 503);
+        assert_eq!(transport.requests().len(), 1);
+
+        let response = client.send(post.idempotent()).await;
+        assert_eq!(response.unwrap().status, 200);
+        assert_eq!(transport.requests().len(), 2);
+
+        assert!(HttpRequest::new(HttpMethod::Options, "http://x/").idempotent);
+        assert!(!HttpRequest::new(HttpMethod::Patch, "http://x/").idempotent);
+    }
+
+    #[tokio::test]
+    async fn test_last_response_is_returned_when_retries_run_out() {
+        let transport = FakeTransport::replying(vec![status(500), status(502), status(503)]);
+        let client = HttpClient::new(transport.clone()).with_config(retrying(1));
+
+        let response = client
+            .send(HttpRequest::new(HttpMethod::Get, "http://x/"))
+            .await;
+        assert_eq!(response.unwrap().status, 502);
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_each_attempt_times_out() {
+        let transport = FakeTransport {
+            delay: Some(Duration::from_secs(5)),
+            ..FakeTransport::replying(vec![status(200), status(200)])
+        };
+        let config = retrying(1).with_timeout(Duration::from_millis(20));
+        let client = HttpClient::new(transport.clone()).with_config(config);
+
+        let response = client
+            .send(HttpRequest::new(HttpMethod::Get, "http://x/"))
+            .await;
+        assert!(matches!(response, Err(HttpError::Timeout)));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_commands_send_method_headers_and_body() {
+        let transport = FakeTransport::replying(vec![status(201), status(200)]);
+        let client = HttpClient::new(transport.clone());
+
+        let cmd = client.post("http://x/items", "{}", |result| {
+            result.map(|r| r.status).ok()
+        });
+        let future = cmd.take_async().unwrap();
+        assert_eq!(Box::into_pin(future).await, Some(Some(201)));
+
+        let request = HttpRequest::new(HttpMethod::Put, "http://x/items/1")
+            .header("X-Token", "secret")
+            .body(vec![1, 2, 3]);
+        let cmd = client.request(request.clone(), |result| result.is_ok());
+        assert_eq!(Box::into_pin(cmd.take_async().unwrap()).await, Some(true));
+
+        let sent = transport.requests();
+        assert_eq!(sent[0].method, HttpMethod::Post);
+        assert_eq!(sent[0].body.as_deref(), Some(&b"{}"[..]));
+        assert_eq!(sent[0].headers["Content-Type"], "application/json");
+        assert_eq!(sent[1], request);
+    }
+
+    #[test]
+    fn test_response_decodes_body() {
+        let response = HttpResponse::new(200, HashMap::new(), vec![b'o', b'k', 0xff]);
+        assert_eq!(response.body, "ok\u{fffd}");
+        assert_eq!(response.bytes, vec![b'o', b'k', 0xff]);
+        assert!(response.is_success());
+        assert!(!response.is_retryable());
+    }
+
+    #[cfg(feature = "http")]
+    mod hyper {
+        use super::*;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        /// Serve the given raw responses, one connection each, and return the
+        /// address and the raw requests received
+        async fn serve(
+            responses: Vec<&'static str>,
+        ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let server = tokio::spawn(async move {
+                let mut requests = Vec::new();
+                for response in responses {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    requests.push(read_request(&mut socket).await);
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                    socket.shutdown().await.unwrap();
+                }
+                requests
+            });
+            (url, server)
+        }
+
+        /// Read a request head and its Content-Length body
+        async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+            let mut data = Vec::new();
+            let mut buf = [0; 1024];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                data.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&data).to_string();
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(str::to_string)
+                        })
+                        .map_or(0, |n| n.trim().parse::<usize>().unwrap());
+                    if data.len() >= head_end + 4 + length || n == 0 {
+                        return text;
+                    }
+                }
             }
         }
-    })
-}
\ No newline at end of file
+
+        #[tokio::test]
+        async fn test_hyper_transport_talks_to_a_server() {
+            let (url, server) = serve(vec![
+                "HTTP/1.1 201 Created\r\nContent-Length: 5\r\nX-Id: 7\r\nConnection: close\r\n\r\nhello",
+            ])
+            .await;
+
+            let request = HttpRequest::new(HttpMethod::Post, format!("{}/items", url))
+                .header("X-Token", "secret")
+                .body("ping");
+            let response = HttpClient::default().send(request).await.unwrap();
+
+            assert_eq!(response.status, 201);
+            assert_eq!(response.body, "hello");
+            assert_eq!(response.bytes, b"hello");
+            assert_eq!(response.headers["x-id"], "7");
+
+            let requests = server.await.unwrap();
+            assert!(
+                requests[0].starts_with("POST /items HTTP/1.1\r\n"),
+                "{}",
+                requests[0]
+            );
+            assert!(requests[0].to_ascii_lowercase().contains("x-token: secret"));
+            assert!(requests[0].ends_with("\r\n\r\nping"));
+        }
+
+        #[tokio::test]
+        async fn test_hyper_transport_retries_server_errors() {
+            let (url, server) = serve(vec![
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n2\r\nok\r\n0\r\n\r\n",
+            ])
+            .await;
+
+            let client = HttpClient::default().with_config(retrying(2));
+            let response = client
+                .send(HttpRequest::new(HttpMethod::Get, url))
+                .await
+                .unwrap();
+
+            assert_eq!(response.status, 200);
+            assert_eq!(response.body, "ok");
+            assert_eq!(server.await.unwrap().len(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_hyper_transport_reports_errors() {
+            let client = HttpClient::default();
+            let send = |url: &str| client.send(HttpRequest::new(HttpMethod::Get, url));
+
+            assert!(matches!(
+                send("https://example.com/").await,
+                Err(HttpError::Unsupported(_))
+            ));
+            assert!(matches!(
+                send("not a url").await,
+                Err(HttpError::InvalidUrl(_))
+            ));
+
+            // Nothing listens on a port that was just released
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            drop(listener);
+            assert!(matches!(send(&url).await, Err(HttpError::NetworkError(_))));
+        }
+
+        #[tokio::test]
+        async fn test_hyper_transport_times_out() {
+            // Accepts the connection but never answers
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                drop(socket);
+            });
+
+            let client = HttpClient::default()
+                .with_config(AsyncConfig::default().with_timeout(Duration::from_millis(50)));
+            let response = client.send(HttpRequest::new(HttpMethod::Get, url)).await;
+            assert!(matches!(response, Err(HttpError::Timeout)));
+            server.abort();
+        }
+    }
+}
@@ -6,7 +6,9 @@
 //! ## Available Helpers
 //! 
 //! ### HTTP Operations
-//! Simple HTTP requests with automatic JSON handling:
+//! Simple HTTP requests with automatic JSON handling. Requests are sent over
+//! HTTP/1.1 when the `http` feature is enabled; see [`HttpClient`] for
+//! timeouts, retries and custom transports:
 //! ```no_run
 //! # use hojicha_core::async_helpers::{http_get, http_post, HttpResponse, HttpError};
 //! # use hojicha_core::Cmd;
@@ -32,12 +34,14 @@
 //! ```
 //! 
 //! ### WebSocket Connections
-//! Real-time bidirectional communication:
+//! Real-time bidirectional communication over RFC 6455 when the `websocket`
+//! feature is enabled; see [`WebSocketHandle`] for sending, reconnects and
+//! heartbeats:
 //! ```no_run
 //! # use hojicha_core::async_helpers::{websocket, WebSocketEvent};
 //! # use hojicha_core::Cmd;
 //! # enum Msg { WsConnected, WsMessage(String), WsError(String), WsDisconnected, WsBinary(Vec<u8>) }
-//! let cmd: Cmd<Msg> = websocket("wss://echo.websocket.org", |event| {
+//! let cmd: Cmd<Msg> = websocket("ws://localhost:8080/feed", |event| {
 //!     Some(match event {
 //!         WebSocketEvent::Connected => Msg::WsConnected,
 //!         WebSocketEvent::Message(text) => Msg::WsMessage(text),
@@ -49,11 +53,12 @@
 //! ```
 //! 
 //! ### File Operations
-//! Async file I/O and watching:
+//! Async file I/O, and watching when the `watch` feature is enabled (see
+//! [`FileWatcher`] for filters and debouncing):
 //! ```no_run
-//! # use hojicha_core::async_helpers::{read_file, write_file, watch_file};
+//! # use hojicha_core::async_helpers::{read_file, tail_file, write_file, watch_file, LineEvent};
 //! # use hojicha_core::Cmd;
-//! # enum Msg { ConfigLoaded(String), Error(String), FileChanged }
+//! # enum Msg { ConfigLoaded(String), Error(String), FileChanged, LogLines(Vec<String>) }
 //! // Read file
 //! let cmd: Cmd<Msg> = read_file("config.json", |result| {
 //!     result.map(Msg::ConfigLoaded)
@@ -62,6 +67,31 @@
 //! 
 //! // Watch for changes
 //! let cmd = watch_file("data.csv", |_| Some(Msg::FileChanged));
+//!
+//! // Stream a large log in batches of lines and follow it like `tail -F`
+//! let cmd = tail_file("app.log", 100, |event| match event {
+//!     Ok(LineEvent::Lines(lines)) => Some(Msg::LogLines(lines)),
+//!     _ => None,
+//! });
+//! ```
+//! 
+//! ### Background Processes
+//! Child processes with streamed output and a handle to write to their stdin,
+//! signal or kill them:
+//! ```no_run
+//! # use hojicha_core::async_helpers::{spawn_process, ProcessEvent};
+//! # use hojicha_core::Cmd;
+//! # enum Msg { Output(String), Exited(Option<i32>) }
+//! let (handle, cmd): (_, Cmd<Msg>) = spawn_process("ping", ["-c", "3", "localhost"], |event| {
+//!     match event {
+//!         Ok(ProcessEvent::Stdout(line)) => Some(Msg::Output(line)),
+//!         Ok(ProcessEvent::Exited(status)) => Some(Msg::Exited(status.code())),
+//!         _ => None,
+//!     }
+//! });
+//!
+//! // Later, e.g. when the user presses Ctrl+C
+//! handle.kill();
 //! ```
 //! 
 //! ### Timers
@@ -82,11 +112,26 @@ pub mod http;
 pub mod websocket;
 pub mod file_io;
 pub mod timer;
+pub mod watch;
+pub mod process;
 
-pub use http::{http_get, http_post, http_request, HttpMethod, HttpError, HttpResponse};
-pub use websocket::{websocket, WebSocketEvent, WebSocketError};
-pub use file_io::{read_file, write_file, watch_file, FileError, FileEvent};
+pub use http::{
+    http_get, http_post, http_request, http_with_retry, HttpClient, HttpError, HttpMethod,
+    HttpRequest, HttpResponse, HttpTransport,
+};
+#[cfg(feature = "http")]
+pub use http::HyperTransport;
+pub use websocket::{
+    websocket, websocket_with_heartbeat, ws_close, ws_send, WebSocketError, WebSocketEvent,
+    WebSocketHandle,
+};
+pub use file_io::{
+    read_file, read_lines, tail_file, watch_file, write_file, FileError, FileEvent, LineEvent,
+    LineReader,
+};
 pub use timer::{delay, interval, with_timeout, debounce, throttle};
+pub use watch::FileWatcher;
+pub use process::{spawn_process, Process, ProcessError, ProcessEvent, ProcessHandle};
 
 use crate::core::{Cmd, Message};
 
@@ -115,6 +160,25 @@ pub enum BackoffStrategy {
     Exponential(std::time::Duration),
 }
 
+impl BackoffStrategy {
+    /// How long to wait before the given retry, counting from 1
+    ///
+    /// Saturates at `Duration::MAX` instead of overflowing.
+    pub fn delay(&self, attempt: u32) -> Option<std::time::Duration> {
+        match self {
+            BackoffStrategy::None => None,
+            BackoffStrategy::Linear(duration) => {
+                Some(duration.checked_mul(attempt).unwrap_or(std::time::Duration::MAX))
+            }
+            BackoffStrategy::Exponential(duration) => Some(
+                duration
+                    .checked_mul(2u32.saturating_pow(attempt))
+                    .unwrap_or(std::time::Duration::MAX),
+            ),
+        }
+    }
+}
+
 impl Default for AsyncConfig {
     fn default() -> Self {
         Self {
@@ -0,0 +1,741 @@
+//! Background process helper commands
+//!
+//! Unlike [`commands::exec`], which hands the terminal to the child, a
+//! [`Process`] runs in the background with piped stdio. Its output arrives
+//! line by line (or chunk by chunk) while it runs, followed by its exit
+//! status, and the [`ProcessHandle`] returned with the command writes to its
+//! stdin, signals or kills it.
+//!
+//! The command is a streaming command, so it counts as one task against the
+//! program's `ResourceLimits` until the process has exited. If the command is
+//! cancelled or the program quits, the process is killed.
+//!
+//! On Unix the process leads a process group of its own, and signals go to
+//! the whole group, so killing `sh -c "make"` also stops `make` and whatever
+//! it started.
+
+use crate::commands;
+use crate::core::{Cmd, Message};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, Notify};
+
+/// How many stdin writes can wait for the process
+const STDIN_BUFFER: usize = 32;
+
+/// After the process exits, how long to wait for more output before giving up
+/// on pipes kept open by its own children
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Size of the reads in chunk mode
+const CHUNK_SIZE: usize = 4096;
+
+/// An event from a background process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessEvent {
+    /// The process was started
+    Started {
+        /// Its process ID
+        pid: u32,
+    },
+    /// A line (without its line ending) or chunk written to stdout
+    Stdout(String),
+    /// A line (without its line ending) or chunk written to stderr
+    Stderr(String),
+    /// The process exited; this is the last event
+    Exited(ExitStatus),
+}
+
+/// Background process errors
+#[derive(Debug, Clone)]
+pub enum ProcessError {
+    /// The process couldn't be started
+    SpawnFailed(String),
+    /// Reading its output or waiting for it failed
+    IoError(String),
+    /// The process isn't running
+    NotRunning,
+}
+
+impl std::fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessError::SpawnFailed(e) => write!(f, "Failed to start process: {}", e),
+            ProcessError::IoError(e) => write!(f, "I/O error: {}", e),
+            ProcessError::NotRunning => write!(f, "Process is not running"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+/// What the handle sends to the process's stdin
+#[derive(Debug)]
+enum Input {
+    Data(Vec<u8>),
+    Close,
+}
+
+/// Controls a process started by [`Process::spawn`]
+///
+/// Clones control the same process.
+#[derive(Debug, Clone)]
+pub struct ProcessHandle {
+    stdin: mpsc::Sender<Input>,
+    kill: Arc<Notify>,
+    /// The running process's ID, or 0. It is locked while the process is
+    /// signalled and while it is reaped, so a signal can't reach another
+    /// process that was given the same ID
+    pid: Arc<Mutex<u32>>,
+}
+
+impl ProcessHandle {
+    /// Get the process ID while the process is running
+    pub fn pid(&self) -> Option<u32> {
+        Some(*self.pid.lock().unwrap()).filter(|pid| *pid != 0)
+    }
+
+    /// Whether the process is running
+    pub fn is_running(&self) -> bool {
+        self.pid().is_some()
+    }
+
+    /// Write to the process's stdin
+    ///
+    /// Data written before the process has started is delivered once it
+    /// runs. Fails once the process has exited or its stdin was closed.
+    pub async fn write(&self, data: impl Into<Vec<u8>>) -> Result<(), ProcessError> {
+        self.stdin
+            .send(Input::Data(data.into()))
+            .await
+            .map_err(|_| ProcessError::NotRunning)
+    }
+
+    /// Close the process's stdin, so it reads end of file
+    pub async fn close_stdin(&self) {
+        let _ = self.stdin.send(Input::Close).await;
+    }
+
+    /// Kill the process (on Unix, its whole process group)
+    ///
+    /// A process that hasn't started yet is killed as soon as it starts.
+    pub fn kill(&self) {
+        self.kill.notify_one();
+    }
+
+    /// Send a signal to the process's group, e.g. `libc::SIGTERM`
+    #[cfg(unix)]
+    pub fn signal(&self, signal: i32) -> Result<(), ProcessError> {
+        let pid = self.pid.lock().unwrap();
+        if *pid == 0 {
+            return Err(ProcessError::NotRunning);
+        }
+        signal_group(*pid, signal).map_err(|e| ProcessError::IoError(e.to_string()))
+    }
+}
+
+/// Send `signal` to the process group led by `pid`
+///
+/// The caller holds the lock on the ID, so the leader hasn't been reaped and
+/// the group's ID can't have been reused.
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: i32) -> std::io::Result<()> {
+    // SAFETY: killpg has no memory safety requirements
+    if unsafe { libc::killpg(pid as libc::pid_t, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Kills the process group when the command is dropped before the process
+/// has been reaped, e.g. when it is cancelled
+#[cfg(unix)]
+struct GroupGuard(Arc<Mutex<u32>>);
+
+#[cfg(unix)]
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        let pid = self.0.lock().unwrap();
+        if *pid != 0 {
+            let _ = signal_group(*pid, libc::SIGKILL);
+        }
+    }
+}
+
+/// How output is split into events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputMode {
+    Lines,
+    Chunks,
+}
+
+/// A background process to start
+///
+/// # Example
+/// ```no_run
+/// # use hojicha_core::async_helpers::process::{Process, ProcessEvent, ProcessHandle};
+/// # use hojicha_core::Cmd;
+/// # enum Msg { Output(String), Finished(bool), Failed(String) }
+/// let (build, cmd): (ProcessHandle, Cmd<Msg>) = Process::new("cargo")
+///     .args(["build", "--color=never"])
+///     .current_dir("/path/to/project")
+///     .spawn(|event| match event {
+///         Ok(ProcessEvent::Stdout(line) | ProcessEvent::Stderr(line)) => Some(Msg::Output(line)),
+///         Ok(ProcessEvent::Exited(status)) => Some(Msg::Finished(status.success())),
+///         Ok(ProcessEvent::Started { .. }) => None,
+///         Err(e) => Some(Msg::Failed(e.to_string())),
+///     });
+/// // Keep `build` in the model to kill the build later
+/// ```
+#[derive(Debug, Clone)]
+pub struct Process {
+    program: OsString,
+    args: Vec<OsString>,
+    current_dir: Option<PathBuf>,
+    env: Vec<(OsString, OsString)>,
+    output: OutputMode,
+}
+
+impl Process {
+    /// Create a process running `program`, found on `PATH` unless it is a path
+    pub fn new(program: impl Into<OsString>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            current_dir: None,
+            env: Vec::new(),
+            output: OutputMode::Lines,
+        }
+    }
+
+    /// Add an argument
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add arguments
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the working directory
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set an environment variable
+    pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Send output as it arrives instead of line by line
+    ///
+    /// Useful for progress output that redraws a line with `\r`. Chunks are
+    /// split between characters, never inside one.
+    pub fn chunks(mut self) -> Self {
+        self.output = OutputMode::Chunks;
+        self
+    }
+
+    /// Create a command that starts the process and a handle to control it
+    ///
+    /// Every event is passed to `handler`. The command ends after
+    /// [`ProcessEvent::Exited`], or with the error if the process can't be
+    /// started.
+    pub fn spawn<M, F>(self, mut handler: F) -> (ProcessHandle, Cmd<M>)
+    where
+        M: Message,
+        F: FnMut(Result<ProcessEvent, ProcessError>) -> Option<M> + Send + 'static,
+    {
+        let (stdin, input) = mpsc::channel(STDIN_BUFFER);
+        let handle = ProcessHandle {
+            stdin,
+            kill: Arc::new(Notify::new()),
+            pid: Arc::new(Mutex::new(0)),
+        };
+        let messages = self
+            .events(input, handle.clone())
+            .filter_map(move |event| futures::future::ready(handler(event)));
+        (handle, commands::stream(messages))
+    }
+
+    fn events(
+        self,
+        mut input: mpsc::Receiver<Input>,
+        handle: ProcessHandle,
+    ) -> BoxStream<'static, Result<ProcessEvent, ProcessError>> {
+        let events = async_stream::stream! {
+            let mut command = tokio::process::Command::new(&self.program);
+            command
+                .args(&self.args)
+                .envs(self.env.iter().map(|(key, value)| (key, value)))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            if let Some(dir) = &self.current_dir {
+                command.current_dir(dir);
+            }
+            #[cfg(unix)]
+            command.process_group(0);
+            // Listen before spawning so the child's exit can't be missed
+            let mut exits = match ChildExits::new() {
+                Ok(exits) => exits,
+                Err(e) => {
+                    yield Err(ProcessError::SpawnFailed(e.to_string()));
+                    return;
+                }
+            };
+            let mut child = match command.spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    yield Err(ProcessError::SpawnFailed(e.to_string()));
+                    return;
+                }
+            };
+
+            let pid = child.id().unwrap_or_default();
+            *handle.pid.lock().unwrap() = pid;
+            #[cfg(unix)]
+            let _group = GroupGuard(handle.pid.clone());
+            yield Ok(ProcessEvent::Started { pid });
+
+            let mut outputs = match (child.stdout.take(), child.stderr.take()) {
+                (Some(stdout), Some(stderr)) => stream::select(
+                    output(stdout, self.output, ProcessEvent::Stdout),
+                    output(stderr, self.output, ProcessEvent::Stderr),
+                )
+                .boxed(),
+                _ => stream::empty().boxed(),
+            };
+            // Writing runs alongside reading, so a child that only reads stdin
+            // after writing a lot of output can't deadlock with us
+            let mut stdin = child.stdin.take();
+            let mut writer = Box::pin(async move {
+                while let Some(Input::Data(data)) = input.recv().await {
+                    let Some(pipe) = stdin.as_mut() else { break };
+                    if pipe.write_all(&data).await.is_err() || pipe.flush().await.is_err() {
+                        break;
+                    }
+                }
+                // Dropping stdin closes it
+                drop(stdin);
+                input.close();
+            });
+
+            let mut outputs_done = false;
+            let mut writer_done = false;
+            let mut status = None;
+            while status.is_none() || !outputs_done {
+                let step = tokio::select! {
+                    event = outputs.next(), if !outputs_done => Step::Output(event),
+                    _ = &mut writer, if !writer_done => Step::WriterDone,
+                    _ = handle.kill.notified(), if status.is_none() => Step::Kill,
+                    exit = exits.wait(&mut child, &handle.pid), if status.is_none() => {
+                        Step::Exited(exit)
+                    }
+                    _ = tokio::time::sleep(OUTPUT_DRAIN_TIMEOUT), if status.is_some() => {
+                        Step::DrainTimeout
+                    }
+                };
+                match step {
+                    Step::Output(Some(event)) => yield event,
+                    Step::Output(None) | Step::DrainTimeout => outputs_done = true,
+                    Step::WriterDone => writer_done = true,
+                    Step::Kill => {
+                        if let Err(e) = kill(&mut child, &handle.pid) {
+                            log::warn!("Failed to kill process {}: {}", pid, e);
+                        }
+                    }
+                    Step::Exited(Ok(exit)) => status = Some(exit),
+                    Step::Exited(Err(e)) => {
+                        yield Err(ProcessError::IoError(e.to_string()));
+                        return;
+                    }
+                }
+            }
+            if let Some(status) = status {
+                yield Ok(ProcessEvent::Exited(status));
+            }
+        };
+        events.boxed()
+    }
+}
+
+/// Kill the child (on Unix, its process group) unless it has been reaped
+#[cfg(unix)]
+fn kill(_child: &mut tokio::process::Child, pid: &Mutex<u32>) -> std::io::Result<()> {
+    let pid = pid.lock().unwrap();
+    if *pid == 0 {
+        return Ok(());
+    }
+    signal_group(*pid, libc::SIGKILL)
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut tokio::process::Child, _pid: &Mutex<u32>) -> std::io::Result<()> {
+    child.start_kill()
+}
+
+/// Waits for the child to exit, clearing the handle's ID as it is reaped
+///
+/// On Unix the child is reaped while the ID is locked, so
+/// [`ProcessHandle::signal`] either signals it before it is reaped or sees
+/// that it isn't running.
+#[cfg(unix)]
+struct ChildExits(tokio::signal::unix::Signal);
+
+#[cfg(unix)]
+impl ChildExits {
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        signal(SignalKind::child()).map(Self)
+    }
+
+    async fn wait(
+        &mut self,
+        child: &mut tokio::process::Child,
+        pid: &Mutex<u32>,
+    ) -> std::io::Result<ExitStatus> {
+        loop {
+            {
+                let mut pid = pid.lock().unwrap();
+                let exited = child.try_wait();
+                if !matches!(exited, Ok(None)) {
+                    *pid = 0;
+                }
+                if let Some(status) = exited? {
+                    return Ok(status);
+                }
+            }
+            // Any child's exit wakes us up, so check again
+            if self.0.recv().await.is_none() {
+                return Err(std::io::Error::other("child exits are no longer reported"));
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct ChildExits;
+
+#[cfg(not(unix))]
+impl ChildExits {
+    fn new() -> std::io::Result<Self> {
+        Ok(Self)
+    }
+
+    async fn wait(
+        &mut self,
+        child: &mut tokio::process::Child,
+        pid: &Mutex<u32>,
+    ) -> std::io::Result<ExitStatus> {
+        let exited = child.wait().await;
+        *pid.lock().unwrap() = 0;
+        exited
+    }
+}
+
+/// What woke up the process loop
+enum Step {
+    Output(Option<Result<ProcessEvent, ProcessError>>),
+    WriterDone,
+    Kill,
+    Exited(std::io::Result<ExitStatus>),
+    DrainTimeout,
+}
+
+/// Stream the output of a pipe as events
+fn output<R>(
+    pipe: R,
+    mode: OutputMode,
+    event: fn(String) -> ProcessEvent,
+) -> BoxStream<'static, Result<ProcessEvent, ProcessError>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    match mode {
+        OutputMode::Lines => stream::unfold(Some(BufReader::new(pipe)), move |reader| async move {
+            let mut reader = reader?;
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line).await {
+                Ok(0) => None,
+                Ok(_) => {
+                    let text = line.strip_suffix(b"\n").unwrap_or(&line);
+                    let text = text.strip_suffix(b"\r").unwrap_or(text);
+                    let text = String::from_utf8_lossy(text).into_owned();
+                    Some((Ok(event(text)), Some(reader)))
+                }
+                Err(e) => Some((Err(ProcessError::IoError(e.to_string())), None)),
+            }
+        })
+        .boxed(),
+        OutputMode::Chunks => stream::unfold(Some((pipe, Vec::new())), move |state| async move {
+            let (mut pipe, mut pending) = state?;
+            let mut chunk = [0; CHUNK_SIZE];
+            loop {
+                match pipe.read(&mut chunk).await {
+                    Ok(0) if pending.is_empty() => return None,
+                    Ok(0) => {
+                        let text = String::from_utf8_lossy(&pending).into_owned();
+                        return Some((Ok(event(text)), None));
+                    }
+                    Ok(n) => {
+                        pending.extend_from_slice(&chunk[..n]);
+                        let text = take_complete_chars(&mut pending);
+                        if !text.is_empty() {
+                            return Some((Ok(event(text)), Some((pipe, pending))));
+                        }
+                    }
+                    Err(e) => return Some((Err(ProcessError::IoError(e.to_string())), None)),
+                }
+            }
+        })
+        .boxed(),
+    }
+}
+
+/// Decode the bytes up to an incomplete character at the end, which stays in
+/// `pending`
+fn take_complete_chars(pending: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        // Only an incomplete sequence at the very end is kept back
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+    let rest = pending.split_off(complete);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+/// Start a background process with piped stdio
+///
+/// See [`Process`] for the working directory, environment and chunked
+/// output.
+pub fn spawn_process<M, F, I, S>(
+    program: impl Into<OsString>,
+    args: I,
+    handler: F,
+) -> (ProcessHandle, Cmd<M>)
+where
+    M: Message,
+    F: FnMut(Result<ProcessEvent, ProcessError>) -> Option<M> + Send + 'static,
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    Process::new(program).args(args).spawn(handler)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    /// Start a shell script and stream its events
+    fn sh(
+        script: &str,
+    ) -> (
+        ProcessHandle,
+        BoxStream<'static, Result<ProcessEvent, ProcessError>>,
+    ) {
+        let (handle, cmd) = Process::new("sh").args(["-c", script]).spawn(Some);
+        (handle, cmd.take_stream().unwrap())
+    }
+
+    async fn next(
+        events: &mut BoxStream<'static, Result<ProcessEvent, ProcessError>>,
+    ) -> Option<ProcessEvent> {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("no process event")
+            .map(Result::unwrap)
+    }
+
+    async fn rest(
+        events: &mut BoxStream<'static, Result<ProcessEvent, ProcessError>>,
+    ) -> Vec<ProcessEvent> {
+        let mut rest = Vec::new();
+        while let Some(event) = next(events).await {
+            rest.push(event);
+        }
+        rest
+    }
+
+    #[tokio::test]
+    async fn test_streams_output_and_exit_status() {
+        let (handle, mut events) = sh("echo one; echo oops >&2; printf 'two\\r\\nthree'; exit 3");
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Started { .. })
+        ));
+        assert!(handle.is_running());
+
+        let rest = rest(&mut events).await;
+        let stdout: Vec<_> = rest
+            .iter()
+            .filter_map(|event| match event {
+                ProcessEvent::Stdout(line) => Some(line.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stdout, vec!["one", "two", "three"]);
+        assert!(rest.contains(&ProcessEvent::Stderr("oops".into())));
+        match rest.last() {
+            Some(ProcessEvent::Exited(status)) => assert_eq!(status.code(), Some(3)),
+            other => panic!("expected the exit status, got {:?}", other),
+        }
+        assert!(!handle.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_writes_to_stdin() {
+        let (handle, mut events) = sh("cat");
+        handle.write("early\n").await.unwrap();
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Started { .. })
+        ));
+        assert_eq!(
+            next(&mut events).await,
+            Some(ProcessEvent::Stdout("early".into()))
+        );
+
+        handle.write(b"late\n".to_vec()).await.unwrap();
+        assert_eq!(
+            next(&mut events).await,
+            Some(ProcessEvent::Stdout("late".into()))
+        );
+
+        handle.close_stdin().await;
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Exited(status)) if status.success()
+        ));
+        assert!(matches!(
+            handle.write("gone").await,
+            Err(ProcessError::NotRunning)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_kill_and_signal() {
+        let (handle, mut events) = sh("sleep 10");
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Started { .. })
+        ));
+        handle.kill();
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Exited(status)) if status.signal() == Some(libc::SIGKILL)
+        ));
+        assert!(matches!(
+            handle.signal(libc::SIGTERM),
+            Err(ProcessError::NotRunning)
+        ));
+
+        let (handle, mut events) =
+            sh("trap 'echo stopping; exit 0' TERM; echo ready; while true; do sleep 0.02; done");
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Started { .. })
+        ));
+        assert_eq!(
+            next(&mut events).await,
+            Some(ProcessEvent::Stdout("ready".into()))
+        );
+        handle.signal(libc::SIGTERM).unwrap();
+        // The signal also reaches `sleep`, which the shell reports on stderr
+        let rest = rest(&mut events).await;
+        assert!(rest.contains(&ProcessEvent::Stdout("stopping".into())));
+        assert!(matches!(
+            rest.last(),
+            Some(ProcessEvent::Exited(status)) if status.success()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_kill_stops_the_whole_group() {
+        // Without the group the subshell would still print while the pipes drain
+        let (handle, mut events) = sh("(sleep 0.2; echo orphan) & echo ready; wait");
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ProcessEvent::Started { .. })
+        ));
+        assert_eq!(
+            next(&mut events).await,
+            Some(ProcessEvent::Stdout("ready".into()))
+        );
+        handle.kill();
+        let rest = rest(&mut events).await;
+        assert!(!rest.contains(&ProcessEvent::Stdout("orphan".into())));
+        assert!(matches!(
+            rest.last(),
+            Some(ProcessEvent::Exited(status)) if status.signal() == Some(libc::SIGKILL)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chunks_keep_characters_whole() {
+        let (_handle, cmd) = Process::new("sh")
+            .args([
+                "-c",
+                "printf 'a\\rb'; printf '\\303'; sleep 0.05; printf '\\251'",
+            ])
+            .chunks()
+            .spawn(Some);
+        let events: Vec<_> = cmd
+            .take_stream()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let output: String = events
+            .iter()
+            .filter_map(|event| match event {
+                ProcessEvent::Stdout(chunk) => Some(chunk.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(output, "a\rbé");
+        assert!(!events.contains(&ProcessEvent::Stdout("\u{fffd}".into())));
+    }
+
+    #[test]
+    fn test_incomplete_characters_wait_for_the_rest() {
+        let mut pending = vec![b'a', 0xc3];
+        assert_eq!(take_complete_chars(&mut pending), "a");
+        assert_eq!(pending, vec![0xc3]);
+
+        pending.push(0xa9);
+        assert_eq!(take_complete_chars(&mut pending), "é");
+        assert!(pending.is_empty());
+
+        let mut invalid = vec![0xff, b'b'];
+        assert_eq!(take_complete_chars(&mut invalid), "\u{fffd}b");
+    }
+
+    #[tokio::test]
+    async fn test_missing_programs_fail_to_spawn() {
+        let (handle, cmd) = spawn_process("definitely-not-a-program", ["x"], Some);
+        let events: Vec<_> = cmd.take_stream().unwrap().collect().await;
+        assert!(matches!(&events[..], [Err(ProcessError::SpawnFailed(_))]));
+        assert!(!handle.is_running());
+    }
+}
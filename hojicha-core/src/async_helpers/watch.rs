@@ -0,0 +1,766 @@
+//! File system watching
+//!
+//! A [`FileWatcher`] reports changes below one or more paths as
+//! [`FileEvent`]s, as a command or a subscription. With the `watch` cargo
+//! feature it uses the platform's notification API (inotify on Linux);
+//! without it watching fails with [`FileError::IoError`].
+//!
+//! Notifications are collected until the paths have been quiet for the
+//! debounce interval and then coalesced per path, so an editor save that
+//! writes a temporary file and renames it into place arrives as a single
+//! [`FileEvent::Modified`].
+
+use super::file_io::{FileError, FileEvent};
+use crate::commands;
+use crate::core::{Cmd, Message};
+use crate::subscriptions::Sub;
+use futures::future;
+use futures::stream::{BoxStream, StreamExt};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long the paths must be quiet before changes are reported by default
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches files and directories for changes
+///
+/// Directories are watched recursively unless [`recursive`](Self::recursive)
+/// says otherwise. A file is watched through its directory, so the watch
+/// survives the file being replaced, and it doesn't have to exist yet.
+/// Paths in events are absolute.
+///
+/// # Example
+/// ```no_run
+/// # use hojicha_core::async_helpers::watch::FileWatcher;
+/// # use hojicha_core::async_helpers::FileEvent;
+/// # use hojicha_core::Sub;
+/// # enum Msg { SourceChanged(std::path::PathBuf), WatchFailed(String) }
+/// let sub: Sub<Msg> = FileWatcher::new("src")
+///     .include("*.rs")
+///     .exclude("generated/**")
+///     .subscribe("sources", |result| match result {
+///         Ok(FileEvent::Modified(path)) => Some(Msg::SourceChanged(path)),
+///         Ok(_) => None,
+///         Err(e) => Some(Msg::WatchFailed(e.to_string())),
+///     });
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    recursive: bool,
+    debounce: Duration,
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl FileWatcher {
+    /// Create a watcher for `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            paths: vec![path.into()],
+            recursive: true,
+            debounce: DEFAULT_DEBOUNCE,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Also watch `path`
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Set whether directories are watched with their subdirectories
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Set how long the paths must be quiet before changes are reported
+    ///
+    /// Changes are reported after ten intervals at the latest. A zero
+    /// interval reports every batch of notifications as it arrives.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Only report paths matching `glob`
+    ///
+    /// Globs are matched against the path relative to the watched directory
+    /// and against the file name, so `*.toml` matches at any depth while
+    /// `config/*.toml` only matches directly in `config`. With several
+    /// includes a path has to match one of them.
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Don't report paths matching `glob`, even if they are included
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Create a command that passes every change to `handler`
+    ///
+    /// The command runs until the watch fails to start; run it under a key
+    /// with [`Cmd::keyed`] to be able to [`cancel`](commands::cancel) it.
+    pub fn watch<M, F>(&self, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnMut(Result<FileEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        commands::stream(self.messages(handler))
+    }
+
+    /// Create a subscription that watches while it is listed
+    pub fn subscribe<M, F>(&self, key: impl Into<String>, handler: F) -> Sub<M>
+    where
+        M: Message,
+        F: FnMut(Result<FileEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        let watcher = self.clone();
+        Sub::stream(key, move || watcher.messages(handler))
+    }
+
+    fn messages<M, F>(&self, mut handler: F) -> BoxStream<'static, M>
+    where
+        M: Message,
+        F: FnMut(Result<FileEvent, FileError>) -> Option<M> + Send + 'static,
+    {
+        self.events()
+            .filter_map(move |event| future::ready(handler(event)))
+            .boxed()
+    }
+
+    /// Stream the changes
+    ///
+    /// The watch starts when the stream is first polled and stops when it is
+    /// dropped. If it can't start, e.g. because a path doesn't exist or a glob
+    /// is invalid, the stream yields the error and ends.
+    pub fn events(&self) -> BoxStream<'static, Result<FileEvent, FileError>> {
+        backend::events(self.clone())
+    }
+}
+
+#[cfg(feature = "watch")]
+mod backend {
+    use super::{coalesce::Coalescer, coalesce::RawChange, FileWatcher};
+    use crate::async_helpers::file_io::{FileError, FileEvent};
+    use futures::stream::{BoxStream, StreamExt};
+    use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+    use notify::event::{AccessKind, AccessMode, MetadataKind, ModifyKind, RenameMode};
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use std::path::{Path, PathBuf};
+    use tokio::sync::mpsc;
+
+    /// Changes are reported after at most this many debounce intervals, even
+    /// if the paths never go quiet
+    const MAX_DEBOUNCE_INTERVALS: u32 = 10;
+
+    /// A watched path
+    struct Root {
+        /// The directory registered with the platform watcher
+        dir: PathBuf,
+        /// Only this file in `dir` is reported, when a file is watched
+        file: Option<PathBuf>,
+    }
+
+    impl Root {
+        fn resolve(path: &Path) -> Result<Self, FileError> {
+            let not_found = || FileError::NotFound(path.to_path_buf());
+            if path.is_dir() {
+                let dir = path.canonicalize().map_err(|_| not_found())?;
+                return Ok(Self { dir, file: None });
+            }
+
+            let name = path.file_name().ok_or_else(not_found)?;
+            let parent = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            let dir = parent.canonicalize().map_err(|_| not_found())?;
+            let file = dir.join(name);
+            Ok(Self {
+                dir,
+                file: Some(file),
+            })
+        }
+
+        fn contains(&self, path: &Path) -> bool {
+            match &self.file {
+                Some(file) => path == file,
+                None => path.starts_with(&self.dir),
+            }
+        }
+    }
+
+    /// Decides which paths a watcher reports: those in one of its roots that
+    /// pass the include and exclude globs
+    struct PathFilter {
+        include: Option<GlobSet>,
+        exclude: Option<GlobSet>,
+    }
+
+    impl PathFilter {
+        fn new(include: &[String], exclude: &[String]) -> Result<Self, FileError> {
+            Ok(Self {
+                include: glob_set(include)?,
+                exclude: glob_set(exclude)?,
+            })
+        }
+
+        fn matches(&self, roots: &[Root], path: &Path) -> bool {
+            if !roots.iter().any(|root| root.contains(path)) {
+                return false;
+            }
+            let relative = roots
+                .iter()
+                .find_map(|root| path.strip_prefix(&root.dir).ok())
+                .unwrap_or(path);
+            let name = path.file_name().map(Path::new).unwrap_or(relative);
+            let hit = |set: &GlobSet| set.is_match(relative) || set.is_match(name);
+
+            self.include.as_ref().map_or(true, hit) && !self.exclude.as_ref().is_some_and(hit)
+        }
+
+        fn keeps(&self, roots: &[Root], event: &FileEvent) -> bool {
+            match event {
+                FileEvent::Created(path) | FileEvent::Modified(path) | FileEvent::Deleted(path) => {
+                    self.matches(roots, path)
+                }
+                FileEvent::Renamed { from, to } => {
+                    self.matches(roots, from) || self.matches(roots, to)
+                }
+            }
+        }
+    }
+
+    fn glob_set(globs: &[String]) -> Result<Option<GlobSet>, FileError> {
+        if globs.is_empty() {
+            return Ok(None);
+        }
+        let mut set = GlobSetBuilder::new();
+        for glob in globs {
+            let glob = GlobBuilder::new(glob)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| FileError::IoError(format!("Invalid glob: {}", e)))?;
+            set.add(glob);
+        }
+        set.build()
+            .map(Some)
+            .map_err(|e| FileError::IoError(format!("Invalid glob: {}", e)))
+    }
+
+    fn watch_error(error: notify::Error, path: &Path) -> FileError {
+        match error.kind {
+            notify::ErrorKind::PathNotFound => FileError::NotFound(path.to_path_buf()),
+            notify::ErrorKind::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                FileError::PermissionDenied(path.to_path_buf())
+            }
+            _ => FileError::IoError(error.to_string()),
+        }
+    }
+
+    /// Translate a notification into the changes it describes
+    fn raw_changes(event: notify::Event) -> Vec<RawChange> {
+        let tracker = event.tracker();
+        let mut paths = event.paths.into_iter();
+        let each = |paths: std::vec::IntoIter<PathBuf>, change: fn(PathBuf) -> RawChange| {
+            paths.map(change).collect()
+        };
+        match event.kind {
+            EventKind::Create(_) => each(paths, RawChange::Created),
+            EventKind::Remove(_) => each(paths, RawChange::Deleted),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                match (paths.next(), paths.next()) {
+                    (Some(from), Some(to)) => vec![RawChange::Renamed(from, to)],
+                    _ => Vec::new(),
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => paths
+                .map(|path| RawChange::MovedFrom(path, tracker))
+                .collect(),
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => paths
+                .map(|path| RawChange::MovedTo(path, tracker))
+                .collect(),
+            // Without details, whether the path still exists tells which side it was
+            EventKind::Modify(ModifyKind::Name(_)) => paths
+                .map(|path| {
+                    if path.exists() {
+                        RawChange::Created(path)
+                    } else {
+                        RawChange::Deleted(path)
+                    }
+                })
+                .collect(),
+            EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime)) => Vec::new(),
+            EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+                each(paths, RawChange::Modified)
+            }
+            EventKind::Access(_) | EventKind::Any | EventKind::Other => Vec::new(),
+        }
+    }
+
+    pub(super) fn events(watcher: FileWatcher) -> BoxStream<'static, Result<FileEvent, FileError>> {
+        let events = async_stream::stream! {
+            let filter = match PathFilter::new(&watcher.include, &watcher.exclude) {
+                Ok(filter) => filter,
+                Err(error) => {
+                    yield Err(error);
+                    return;
+                }
+            };
+            let mut roots = Vec::new();
+            for path in &watcher.paths {
+                match Root::resolve(path) {
+                    Ok(root) => roots.push(root),
+                    Err(error) => {
+                        yield Err(error);
+                        return;
+                    }
+                }
+            }
+
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            let mut platform = match notify::recommended_watcher(move |result| {
+                let _ = tx.send(result);
+            }) {
+                Ok(platform) => platform,
+                Err(error) => {
+                    yield Err(FileError::IoError(error.to_string()));
+                    return;
+                }
+            };
+            for (root, path) in roots.iter().zip(&watcher.paths) {
+                let mode = if root.file.is_none() && watcher.recursive {
+                    RecursiveMode::Recursive
+                } else {
+                    RecursiveMode::NonRecursive
+                };
+                if let Err(error) = platform.watch(&root.dir, mode) {
+                    yield Err(watch_error(error, path));
+                    return;
+                }
+            }
+
+            let mut coalescer = Coalescer::default();
+            while let Some(first) = rx.recv().await {
+                // Collect until the paths are quiet, but not forever
+                let latest = tokio::time::Instant::now() + watcher.debounce * MAX_DEBOUNCE_INTERVALS;
+                let mut next = Some(first);
+                while let Some(result) = next.take() {
+                    match result {
+                        Ok(event) => {
+                            for change in raw_changes(event) {
+                                coalescer.push(change);
+                            }
+                        }
+                        Err(error) => yield Err(FileError::IoError(error.to_string())),
+                    }
+                    let quiet = (tokio::time::Instant::now() + watcher.debounce).min(latest);
+                    next = tokio::time::timeout_at(quiet, rx.recv()).await.ok().flatten();
+                }
+
+                for event in coalescer.finish() {
+                    if filter.keeps(&roots, &event) {
+                        yield Ok(event);
+                    }
+                }
+            }
+            drop(platform);
+        };
+        events.boxed()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+            let owned = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+            PathFilter::new(&owned(include), &owned(exclude)).unwrap()
+        }
+
+        #[test]
+        fn test_filters_match_relative_paths_and_names() {
+            let roots = [Root {
+                dir: PathBuf::from("/project"),
+                file: None,
+            }];
+            let filter = filter(&["*.toml", "config/*.json"], &["target/**"]);
+            let matches = |path: &str| filter.matches(&roots, Path::new(path));
+
+            assert!(matches("/project/Cargo.toml"));
+            assert!(matches("/project/crates/core/Cargo.toml"));
+            assert!(matches("/project/config/app.json"));
+            assert!(!matches("/project/other/app.json"));
+            assert!(!matches("/project/target/debug/Cargo.toml"));
+            assert!(!matches("/project/src/main.rs"));
+            assert!(!matches("/elsewhere/Cargo.toml"));
+        }
+
+        #[test]
+        fn test_invalid_globs_are_errors() {
+            assert!(PathFilter::new(&["a[".to_string()], &[]).is_err());
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+mod backend {
+    use super::FileWatcher;
+    use crate::async_helpers::file_io::{FileError, FileEvent};
+    use futures::stream::{self, BoxStream, StreamExt};
+
+    pub(super) fn events(_watcher: FileWatcher) -> BoxStream<'static, Result<FileEvent, FileError>> {
+        stream::once(async {
+            Err(FileError::IoError(
+                "File watching is disabled, enable the `watch` feature".to_string(),
+            ))
+        })
+        .boxed()
+    }
+}
+
+#[cfg(feature = "watch")]
+mod coalesce {
+    use crate::async_helpers::file_io::FileEvent;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// A change reported by the platform, before coalescing
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(super) enum RawChange {
+        Created(PathBuf),
+        Modified(PathBuf),
+        Deleted(PathBuf),
+        /// The first half of a rename, which may be matched by its tracker
+        MovedFrom(PathBuf, Option<usize>),
+        /// The second half of a rename
+        MovedTo(PathBuf, Option<usize>),
+        /// A rename with both halves
+        Renamed(PathBuf, PathBuf),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Change {
+        Created,
+        Modified,
+        Deleted,
+    }
+
+    #[derive(Debug)]
+    enum Pending {
+        Change(PathBuf, Change),
+        Renamed(PathBuf, PathBuf),
+    }
+
+    /// Folds a batch of changes into one event per path
+    ///
+    /// A file created and deleted in the same batch isn't reported, one
+    /// deleted and created again is `Modified`, and one written under another
+    /// name and renamed into place is `Modified` under its final name.
+    /// Events come out in the order their paths first changed.
+    #[derive(Debug, Default)]
+    pub(super) struct Coalescer {
+        pending: Vec<Option<Pending>>,
+        /// Where each path's `Pending::Change` is
+        index: HashMap<PathBuf, usize>,
+        /// First halves of renames waiting for the second
+        moved_from: HashMap<usize, PathBuf>,
+    }
+
+    impl Coalescer {
+        pub(super) fn push(&mut self, change: RawChange) {
+            match change {
+                RawChange::Created(path) => self.apply(path, Change::Created),
+                RawChange::Modified(path) => self.apply(path, Change::Modified),
+                RawChange::Deleted(path) | RawChange::MovedFrom(path, None) => {
+                    self.apply(path, Change::Deleted)
+                }
+                RawChange::MovedFrom(path, Some(tracker)) => {
+                    self.moved_from.insert(tracker, path);
+                }
+                RawChange::MovedTo(path, tracker) => {
+                    // A matched rename is completed by `Renamed`
+                    if !tracker.is_some_and(|t| self.moved_from.contains_key(&t)) {
+                        self.apply(path, Change::Created);
+                    }
+                }
+                RawChange::Renamed(from, to) => {
+                    self.moved_from.retain(|_, path| *path != from);
+                    self.rename(from, to);
+                }
+            }
+        }
+
+        fn apply(&mut self, path: PathBuf, change: Change) {
+            let Some(&i) = self.index.get(&path) else {
+                self.index.insert(path.clone(), self.pending.len());
+                self.pending.push(Some(Pending::Change(path, change)));
+                return;
+            };
+            let Some(Pending::Change(_, current)) = &mut self.pending[i] else {
+                return;
+            };
+            match (*current, change) {
+                (Change::Created, Change::Deleted) => {
+                    self.pending[i] = None;
+                    self.index.remove(&path);
+                }
+                (Change::Created, _) | (Change::Modified, Change::Created) => {}
+                (Change::Deleted, Change::Created) => *current = Change::Modified,
+                (_, change) => *current = change,
+            }
+        }
+
+        fn rename(&mut self, from: PathBuf, to: PathBuf) {
+            if let Some(&i) = self.index.get(&from) {
+                if let Some(Pending::Change(_, Change::Created)) = self.pending[i] {
+                    self.pending[i] = None;
+                    self.index.remove(&from);
+                    self.apply(to, Change::Modified);
+                    return;
+                }
+            }
+            self.pending.push(Some(Pending::Renamed(from, to)));
+        }
+
+        /// Take the events of the batch
+        pub(super) fn finish(&mut self) -> Vec<FileEvent> {
+            // Renames whose second half never came moved out of the watch
+            let moved_out: Vec<_> = self.moved_from.drain().map(|(_, path)| path).collect();
+            for path in moved_out {
+                self.apply(path, Change::Deleted);
+            }
+
+            self.index.clear();
+            self.pending
+                .drain(..)
+                .flatten()
+                .map(|pending| match pending {
+                    Pending::Change(path, Change::Created) => FileEvent::Created(path),
+                    Pending::Change(path, Change::Modified) => FileEvent::Modified(path),
+                    Pending::Change(path, Change::Deleted) => FileEvent::Deleted(path),
+                    Pending::Renamed(from, to) => FileEvent::Renamed { from, to },
+                })
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn coalesce(changes: Vec<RawChange>) -> Vec<String> {
+            let mut coalescer = Coalescer::default();
+            for change in changes {
+                coalescer.push(change);
+            }
+            coalescer
+                .finish()
+                .into_iter()
+                .map(|event| format!("{:?}", event))
+                .collect()
+        }
+
+        fn p(path: &str) -> PathBuf {
+            PathBuf::from(path)
+        }
+
+        #[test]
+        fn test_storms_collapse_to_one_event_per_path() {
+            use RawChange::*;
+            let events = coalesce(vec![
+                Modified(p("a")),
+                Created(p("b")),
+                Modified(p("a")),
+                Modified(p("b")),
+                Deleted(p("c")),
+                Modified(p("a")),
+            ]);
+            assert_eq!(
+                events,
+                vec![r#"Modified("a")"#, r#"Created("b")"#, r#"Deleted("c")"#]
+            );
+        }
+
+        #[test]
+        fn test_short_lived_and_recreated_files() {
+            use RawChange::*;
+            let events = coalesce(vec![
+                Created(p("tmp")),
+                Modified(p("tmp")),
+                Deleted(p("tmp")),
+                Deleted(p("config")),
+                Created(p("config")),
+            ]);
+            assert_eq!(events, vec![r#"Modified("config")"#]);
+        }
+
+        #[test]
+        fn test_atomic_save_is_a_modification() {
+            use RawChange::*;
+            let events = coalesce(vec![
+                Created(p("config.tmp")),
+                Modified(p("config.tmp")),
+                MovedFrom(p("config.tmp"), Some(1)),
+                MovedTo(p("config"), Some(1)),
+                Renamed(p("config.tmp"), p("config")),
+            ]);
+            assert_eq!(events, vec![r#"Modified("config")"#]);
+        }
+
+        #[test]
+        fn test_renames() {
+            use RawChange::*;
+            let events = coalesce(vec![
+                MovedFrom(p("old"), Some(1)),
+                MovedTo(p("new"), Some(1)),
+                Renamed(p("old"), p("new")),
+                MovedFrom(p("gone"), Some(2)),
+                MovedTo(p("arrived"), Some(3)),
+            ]);
+            assert_eq!(
+                events,
+                vec![
+                    r#"Renamed { from: "old", to: "new" }"#,
+                    r#"Created("arrived")"#,
+                    r#"Deleted("gone")"#,
+                ]
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "watch"))]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    /// Start watching and give the platform watcher time to register
+    async fn start(watcher: FileWatcher) -> mpsc::UnboundedReceiver<Result<FileEvent, FileError>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut events = watcher.debounce(Duration::from_millis(50)).events();
+        tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        rx
+    }
+
+    /// Take the events that arrive until things are quiet
+    async fn settle(rx: &mut mpsc::UnboundedReceiver<Result<FileEvent, FileError>>) -> Vec<FileEvent> {
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(400), rx.recv()).await {
+            events.push(event.unwrap());
+        }
+        events
+    }
+
+    fn temp_dir() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().canonicalize().unwrap();
+        (dir, path)
+    }
+
+    #[tokio::test]
+    async fn test_reports_created_modified_and_deleted_files() {
+        let (_guard, dir) = temp_dir();
+        let file = dir.join("notes.txt");
+        let mut rx = start(FileWatcher::new(&dir)).await;
+
+        std::fs::write(&file, "one").unwrap();
+        assert!(matches!(&settle(&mut rx).await[..], [FileEvent::Created(p)] if *p == file));
+
+        for i in 0..10 {
+            std::fs::write(&file, i.to_string()).unwrap();
+        }
+        assert!(matches!(&settle(&mut rx).await[..], [FileEvent::Modified(p)] if *p == file));
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(matches!(&settle(&mut rx).await[..], [FileEvent::Deleted(p)] if *p == file));
+    }
+
+    #[tokio::test]
+    async fn test_watches_subdirectories_unless_told_otherwise() {
+        let (_guard, dir) = temp_dir();
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        let nested = dir.join("nested").join("deep.txt");
+
+        let mut rx = start(FileWatcher::new(&dir)).await;
+        std::fs::write(&nested, "x").unwrap();
+        assert!(matches!(&settle(&mut rx).await[..], [FileEvent::Created(p)] if *p == nested));
+
+        let mut rx = start(FileWatcher::new(&dir).recursive(false)).await;
+        std::fs::write(&nested, "y").unwrap();
+        std::fs::write(dir.join("top.txt"), "z").unwrap();
+        assert!(matches!(
+            &settle(&mut rx).await[..],
+            [FileEvent::Created(p)] if *p == dir.join("top.txt")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_watched_file_survives_atomic_saves() {
+        let (_guard, dir) = temp_dir();
+        let config = dir.join("config.toml");
+        std::fs::write(&config, "a = 1").unwrap();
+        let mut rx = start(FileWatcher::new(&config)).await;
+
+        // What editors do: write a temporary file, then rename it over the original
+        for value in 2..4 {
+            let temp = dir.join(".config.toml.swp");
+            std::fs::write(&temp, format!("a = {}", value)).unwrap();
+            std::fs::rename(&temp, &config).unwrap();
+            assert!(matches!(&settle(&mut rx).await[..], [FileEvent::Modified(p)] if *p == config));
+        }
+
+        std::fs::write(dir.join("other.toml"), "").unwrap();
+        assert!(settle(&mut rx).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filters_paths() {
+        let (_guard, dir) = temp_dir();
+        std::fs::create_dir(dir.join("target")).unwrap();
+        let mut rx = start(FileWatcher::new(&dir).include("*.toml").exclude("target/**")).await;
+
+        std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.join("main.rs"), "").unwrap();
+        std::fs::write(dir.join("target").join("Cargo.toml"), "").unwrap();
+        assert!(matches!(
+            &settle(&mut rx).await[..],
+            [FileEvent::Created(p)] if *p == dir.join("Cargo.toml")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_paths_are_errors() {
+        let (_guard, dir) = temp_dir();
+        let mut events = FileWatcher::new(dir.join("missing").join("file")).events();
+        assert!(matches!(events.next().await, Some(Err(FileError::NotFound(_)))));
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_command_delivers_events() {
+        let (_guard, dir) = temp_dir();
+        let cmd: Cmd<FileEvent> = FileWatcher::new(&dir).watch(|event| event.ok());
+        let mut stream = cmd.take_stream().unwrap();
+        let first = tokio::spawn(async move { stream.next().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        std::fs::write(dir.join("a"), "").unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), first).await.unwrap().unwrap();
+        assert!(matches!(event, Some(FileEvent::Created(_))));
+    }
+}
@@ -1,9 +1,38 @@
 //! WebSocket connection helper commands
+//!
+//! A [`WebSocketHandle`] owns one logical connection. Its events arrive
+//! through a command ([`WebSocketHandle::connect`]) or a subscription
+//! ([`WebSocketHandle::subscribe`]), and the model keeps the handle to send
+//! frames and close the connection.
+//!
+//! With the `websocket` cargo feature connections speak RFC 6455 over plain
+//! TCP (`ws://` URLs); without it connecting fails with
+//! [`WebSocketError::ConnectionFailed`]. Lost connections are reconnected
+//! with the handle's [`AsyncConfig`], and an optional heartbeat pings the
+//! server and drops connections that stop answering.
 
-use crate::core::{Cmd, Message};
+use super::{AsyncConfig, BackoffStrategy};
 use crate::commands;
+use crate::core::{Cmd, Message};
+use crate::subscriptions::Sub;
+use futures::future;
+use futures::stream::{BoxStream, StreamExt};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// How many outgoing frames can wait for the connection
+const OUTGOING_BUFFER: usize = 32;
+
+/// Longest wait between reconnect attempts, whatever the backoff says
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Reconnect attempts in a row before a handle gives up by default
+const DEFAULT_RECONNECTS: u32 = 10;
+
+/// Source of unique handle IDs
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
 /// WebSocket events
 #[derive(Debug, Clone)]
@@ -14,7 +43,7 @@ pub enum WebSocketEvent {
     Message(String),
     /// Received binary data
     Binary(Vec<u8>),
-    /// Connection closed
+    /// Connection closed, with the reason the server gave
     Closed(Option<String>),
     /// Error occurred
     Error(WebSocketError),
@@ -46,47 +75,468 @@ impl std::fmt::Display for WebSocketError {
 
 impl std::error::Error for WebSocketError {}
 
+/// A frame queued for the connection
+#[derive(Debug)]
+#[cfg_attr(not(feature = "websocket"), allow(dead_code))]
+enum Outgoing {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping,
+    Close,
+}
+
+/// A frame read from the connection
+#[derive(Debug)]
+#[cfg_attr(not(feature = "websocket"), allow(dead_code))]
+enum Incoming {
+    Text(String),
+    Binary(Vec<u8>),
+    /// A ping or pong, which only shows the server is alive
+    Heartbeat,
+    Close(Option<String>),
+}
+
+/// Why a connection attempt failed
+#[derive(Debug)]
+enum ConnectError {
+    /// Trying again may work
+    Retry(WebSocketError),
+    /// Trying again won't help, e.g. the URL is invalid
+    Fatal(WebSocketError),
+}
+
 /// WebSocket connection handle
+///
+/// Clones share the connection. Only one connection runs per handle at a
+/// time; starting its events again while they are running waits for the
+/// first connection to end.
+///
+/// # Example
+/// ```no_run
+/// # use hojicha_core::async_helpers::websocket::{WebSocketEvent, WebSocketHandle};
+/// # use hojicha_core::{Cmd, Subs};
+/// # use std::time::Duration;
+/// # enum Msg { Received(String) }
+/// struct Chat {
+///     socket: WebSocketHandle,
+/// }
+///
+/// impl Chat {
+///     fn new() -> Self {
+///         let socket = WebSocketHandle::new("ws://localhost:8080/chat")
+///             .with_heartbeat(Duration::from_secs(15));
+///         Self { socket }
+///     }
+///
+///     // Stays connected as long as the subscription is listed
+///     fn subscriptions(&self) -> Subs<Msg> {
+///         self.socket
+///             .subscribe(|event| match event {
+///                 WebSocketEvent::Message(text) => Some(Msg::Received(text)),
+///                 _ => None,
+///             })
+///             .into()
+///     }
+///
+///     fn say(&self, text: String) -> Cmd<Msg> {
+///         hojicha_core::async_helpers::websocket::ws_send(self.socket.clone(), text)
+///     }
+/// }
+/// ```
 #[derive(Debug, Clone)]
 pub struct WebSocketHandle {
-    /// Unique connection ID
+    /// Unique connection ID, also the key of [`subscribe`](Self::subscribe)
     pub id: String,
     /// URL of the WebSocket server
     pub url: String,
-    /// Whether the connection is active
-    pub connected: Arc<Mutex<bool>>,
+    config: AsyncConfig,
+    ping_interval: Option<Duration>,
+    sender: mpsc::Sender<Outgoing>,
+    receiver: Arc<Mutex<mpsc::Receiver<Outgoing>>>,
+    connected: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
 }
 
 impl WebSocketHandle {
+    /// Create a handle for `url` without connecting yet
+    ///
+    /// By default the handshake times out after 30 seconds and a lost
+    /// connection is retried up to 10 times in a row with exponential
+    /// backoff from 250ms.
+    pub fn new(url: impl Into<String>) -> Self {
+        let (sender, receiver) = mpsc::channel(OUTGOING_BUFFER);
+        Self {
+            id: format!("ws-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+            url: url.into(),
+            config: AsyncConfig::default().with_retries(
+                DEFAULT_RECONNECTS,
+                BackoffStrategy::Exponential(Duration::from_millis(250)),
+            ),
+            ping_interval: None,
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            connected: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Set the handshake timeout and how to reconnect
+    ///
+    /// `retries` is how many reconnect attempts in a row are made before
+    /// giving up, and `backoff` how long to wait before each one (at most 30
+    /// seconds). The count starts over once a connection succeeds.
+    pub fn with_config(mut self, config: AsyncConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Ping the server every `interval`
+    ///
+    /// If nothing arrives from the server between two pings, the connection
+    /// is reported as timed out and reconnected.
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval).filter(|interval| !interval.is_zero());
+        self
+    }
+
+    /// Whether the connection is currently open
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
     /// Send a text message through the WebSocket
+    ///
+    /// Waits while too many frames are queued. Fails if the connection isn't
+    /// open.
     pub async fn send_text(&self, message: String) -> Result<(), WebSocketError> {
-        // In a real implementation, this would send through the actual WebSocket
-        if *self.connected.lock().await {
-            Ok(())
-        } else {
-            Err(WebSocketError::SendFailed("Not connected".to_string()))
-        }
+        self.send(Outgoing::Text(message)).await
     }
 
     /// Send binary data through the WebSocket
     pub async fn send_binary(&self, data: Vec<u8>) -> Result<(), WebSocketError> {
-        if *self.connected.lock().await {
-            Ok(())
-        } else {
-            Err(WebSocketError::SendFailed("Not connected".to_string()))
+        self.send(Outgoing::Binary(data)).await
+    }
+
+    async fn send(&self, frame: Outgoing) -> Result<(), WebSocketError> {
+        if !self.is_connected() {
+            return Err(WebSocketError::SendFailed("Not connected".to_string()));
         }
+        self.sender
+            .send(frame)
+            .await
+            .map_err(|_| WebSocketError::SendFailed("Connection closed".to_string()))
     }
 
     /// Close the WebSocket connection
+    ///
+    /// The connection sends a close frame and ends its events with
+    /// [`WebSocketEvent::Closed`]. A closed handle doesn't connect again;
+    /// create a new one instead.
     pub async fn close(&self) {
-        *self.connected.lock().await = false;
+        self.closed.store(true, Ordering::SeqCst);
+        let _ = self.sender.send(Outgoing::Close).await;
+    }
+
+    /// Create a command that connects and passes every event to `handler`
+    ///
+    /// The command runs until the handle is closed or reconnecting gives up.
+    pub fn connect<M, F>(&self, handler: F) -> Cmd<M>
+    where
+        M: Message,
+        F: FnMut(WebSocketEvent) -> Option<M> + Send + 'static,
+    {
+        commands::stream(self.messages(handler))
+    }
+
+    /// Create a subscription that stays connected while it is listed
+    ///
+    /// The subscription's key is the handle's [`id`](Self::id).
+    pub fn subscribe<M, F>(&self, handler: F) -> Sub<M>
+    where
+        M: Message,
+        F: FnMut(WebSocketEvent) -> Option<M> + Send + 'static,
+    {
+        let handle = self.clone();
+        Sub::stream(self.id.clone(), move || handle.messages(handler))
+    }
+
+    fn messages<M, F>(&self, mut handler: F) -> BoxStream<'static, M>
+    where
+        M: Message,
+        F: FnMut(WebSocketEvent) -> Option<M> + Send + 'static,
+    {
+        self.events()
+            .filter_map(move |event| future::ready(handler(event)))
+            .boxed()
+    }
+
+    /// Stream the events of the connection
+    ///
+    /// Nothing happens until the stream is polled. Each successful connection
+    /// yields [`Connected`](WebSocketEvent::Connected) and, once it ends,
+    /// [`Closed`](WebSocketEvent::Closed). Failed attempts and lost
+    /// connections yield [`Error`](WebSocketEvent::Error) before reconnecting.
+    /// The stream ends when the handle is closed, the URL can't be used or the
+    /// reconnect attempts run out.
+    pub fn events(&self) -> BoxStream<'static, WebSocketEvent> {
+        let handle = self.clone();
+        let events = async_stream::stream! {
+            let mut outgoing = handle.receiver.clone().lock_owned().await;
+            let mut attempt = 0;
+            while !handle.closed.load(Ordering::SeqCst) {
+                if attempt > 0 {
+                    if attempt > handle.config.retries {
+                        break;
+                    }
+                    if let Some(delay) = handle.config.backoff.delay(attempt) {
+                        let wait = tokio::time::sleep(delay.min(MAX_RECONNECT_DELAY));
+                        if !wait_unless_closed(wait, &mut outgoing).await {
+                            break;
+                        }
+                    }
+                }
+
+                let mut socket = match connect(&handle.url, handle.config.timeout).await {
+                    Ok(socket) => socket,
+                    Err(ConnectError::Retry(error)) => {
+                        yield WebSocketEvent::Error(error);
+                        attempt += 1;
+                        continue;
+                    }
+                    Err(ConnectError::Fatal(error)) => {
+                        yield WebSocketEvent::Error(error);
+                        break;
+                    }
+                };
+                handle.connected.store(true, Ordering::SeqCst);
+                yield WebSocketEvent::Connected;
+                attempt = 1;
+
+                let mut heartbeat = handle.ping_interval.map(|interval| {
+                    let start = tokio::time::Instant::now() + interval;
+                    let mut heartbeat = tokio::time::interval_at(start, interval);
+                    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    heartbeat
+                });
+                let mut awaiting_reply = false;
+                let reason = loop {
+                    let step = tokio::select! {
+                        incoming = socket.recv() => Step::Incoming(incoming),
+                        frame = outgoing.recv() => Step::Outgoing(frame),
+                        _ = next_tick(&mut heartbeat) => Step::Heartbeat,
+                    };
+                    match step {
+                        Step::Incoming(Some(Ok(incoming))) => {
+                            awaiting_reply = false;
+                            match incoming {
+                                Incoming::Text(text) => yield WebSocketEvent::Message(text),
+                                Incoming::Binary(data) => yield WebSocketEvent::Binary(data),
+                                Incoming::Heartbeat => {}
+                                Incoming::Close(reason) => {
+                                    socket.finish().await;
+                                    break reason;
+                                }
+                            }
+                        }
+                        Step::Incoming(Some(Err(error))) => {
+                            yield WebSocketEvent::Error(error);
+                            break None;
+                        }
+                        Step::Incoming(None) => break None,
+                        Step::Outgoing(Some(Outgoing::Close)) | Step::Outgoing(None) => {
+                            if socket.send(Outgoing::Close).await.is_ok() {
+                                socket.finish().await;
+                            }
+                            break None;
+                        }
+                        Step::Outgoing(Some(frame)) => {
+                            if let Err(error) = socket.send(frame).await {
+                                yield WebSocketEvent::Error(error);
+                                break None;
+                            }
+                        }
+                        Step::Heartbeat => {
+                            if awaiting_reply {
+                                yield WebSocketEvent::Error(WebSocketError::Timeout);
+                                break None;
+                            }
+                            if let Err(error) = socket.send(Outgoing::Ping).await {
+                                yield WebSocketEvent::Error(error);
+                                break None;
+                            }
+                            awaiting_reply = true;
+                        }
+                    }
+                };
+                handle.connected.store(false, Ordering::SeqCst);
+                yield WebSocketEvent::Closed(reason);
+            }
+            handle.connected.store(false, Ordering::SeqCst);
+        };
+        events.boxed()
+    }
+}
+
+/// What woke up a running connection
+enum Step {
+    Incoming(Option<Result<Incoming, WebSocketError>>),
+    Outgoing(Option<Outgoing>),
+    Heartbeat,
+}
+
+/// Wait for the next heartbeat, or forever without one
+async fn next_tick(heartbeat: &mut Option<tokio::time::Interval>) {
+    match heartbeat {
+        Some(heartbeat) => {
+            heartbeat.tick().await;
+        }
+        None => future::pending().await,
+    }
+}
+
+/// Wait out a reconnect delay, returning `false` if the handle was closed
+///
+/// Frames sent in the meantime are dropped, as there is nothing to send them
+/// on.
+async fn wait_unless_closed(
+    wait: tokio::time::Sleep,
+    outgoing: &mut mpsc::Receiver<Outgoing>,
+) -> bool {
+    tokio::pin!(wait);
+    loop {
+        tokio::select! {
+            _ = &mut wait => return true,
+            frame = outgoing.recv() => match frame {
+                Some(Outgoing::Close) | None => return false,
+                Some(_) => {}
+            },
+        }
+    }
+}
+
+/// Open a connection, giving up after `timeout`
+async fn connect(url: &str, timeout: Option<Duration>) -> Result<connection::Socket, ConnectError> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connection::connect(url))
+            .await
+            .unwrap_or(Err(ConnectError::Retry(WebSocketError::Timeout))),
+        None => connection::connect(url).await,
+    }
+}
+
+#[cfg(feature = "websocket")]
+mod connection {
+    use super::{ConnectError, Incoming, Outgoing, WebSocketError};
+    use futures::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::{self, Message};
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    /// How long to wait for the server to finish the closing handshake
+    const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// An open RFC 6455 connection
+    pub(super) struct Socket(WebSocketStream<MaybeTlsStream<TcpStream>>);
+
+    pub(super) async fn connect(url: &str) -> Result<Socket, ConnectError> {
+        match tokio_tungstenite::connect_async(url).await {
+            Ok((stream, _response)) => Ok(Socket(stream)),
+            // The server answered but refused the upgrade, or the URL is unusable
+            Err(error @ (tungstenite::Error::Url(_) | tungstenite::Error::Http(_))) => Err(
+                ConnectError::Fatal(WebSocketError::ConnectionFailed(error.to_string())),
+            ),
+            Err(error) => Err(ConnectError::Retry(WebSocketError::ConnectionFailed(
+                error.to_string(),
+            ))),
+        }
+    }
+
+    impl Socket {
+        /// Read the next data, ping, pong or close frame
+        ///
+        /// Pings are answered automatically.
+        pub(super) async fn recv(&mut self) -> Option<Result<Incoming, WebSocketError>> {
+            loop {
+                let message = match self.0.next().await? {
+                    Ok(message) => message,
+                    Err(error) => return Some(Err(read_error(error))),
+                };
+                let incoming = match message {
+                    Message::Text(text) => Incoming::Text(text.as_str().to_owned()),
+                    Message::Binary(data) => Incoming::Binary(data.to_vec()),
+                    Message::Ping(_) | Message::Pong(_) => Incoming::Heartbeat,
+                    Message::Close(frame) => Incoming::Close(
+                        frame
+                            .map(|frame| frame.reason.as_str().to_owned())
+                            .filter(|reason| !reason.is_empty()),
+                    ),
+                    Message::Frame(_) => continue,
+                };
+                return Some(Ok(incoming));
+            }
+        }
+
+        pub(super) async fn send(&mut self, frame: Outgoing) -> Result<(), WebSocketError> {
+            let result = match frame {
+                Outgoing::Text(text) => self.0.send(Message::text(text)).await,
+                Outgoing::Binary(data) => self.0.send(Message::binary(data)).await,
+                Outgoing::Ping => self.0.send(Message::Ping(Default::default())).await,
+                Outgoing::Close => self.0.close(None).await,
+            };
+            result.map_err(|error| WebSocketError::SendFailed(error.to_string()))
+        }
+
+        /// Let the closing handshake finish, which reading drives
+        pub(super) async fn finish(&mut self) {
+            let drain = async { while let Some(Ok(_)) = self.0.next().await {} };
+            let _ = tokio::time::timeout(CLOSE_TIMEOUT, drain).await;
+        }
+    }
+
+    fn read_error(error: tungstenite::Error) -> WebSocketError {
+        match error {
+            tungstenite::Error::Protocol(_)
+            | tungstenite::Error::Utf8(_)
+            | tungstenite::Error::Capacity(_) => WebSocketError::ProtocolError(error.to_string()),
+            _ => WebSocketError::ConnectionFailed(error.to_string()),
+        }
+    }
+}
+
+#[cfg(not(feature = "websocket"))]
+mod connection {
+    use super::{ConnectError, Incoming, Outgoing, WebSocketError};
+
+    /// A connection can't be opened without the `websocket` feature
+    pub(super) enum Socket {}
+
+    pub(super) async fn connect(_url: &str) -> Result<Socket, ConnectError> {
+        Err(ConnectError::Fatal(WebSocketError::ConnectionFailed(
+            "WebSocket support is disabled, enable the `websocket` feature".to_string(),
+        )))
+    }
+
+    impl Socket {
+        pub(super) async fn recv(&mut self) -> Option<Result<Incoming, WebSocketError>> {
+            match *self {}
+        }
+
+        pub(super) async fn send(&mut self, _frame: Outgoing) -> Result<(), WebSocketError> {
+            match *self {}
+        }
+
+        pub(super) async fn finish(&mut self) {
+            match *self {}
+        }
     }
 }
 
 /// Create a WebSocket connection command
 ///
 /// This establishes a WebSocket connection and returns events through the handler.
-/// The connection will automatically reconnect on failure.
+/// The connection will automatically reconnect on failure. Use a
+/// [`WebSocketHandle`] to also send messages.
 ///
 /// # Example
 /// ```no_run
@@ -97,8 +547,8 @@ impl WebSocketHandle {
 /// #     WsMessage(String),
 /// #     WsDisconnected,
 /// # }
-/// 
-/// websocket("wss://echo.websocket.org", |event| {
+///
+/// websocket("ws://localhost:8080/feed", |event| {
 ///     match event {
 ///         WebSocketEvent::Connected => Some(Msg::WsConnected),
 ///         WebSocketEvent::Message(text) => Some(Msg::WsMessage(text)),
@@ -108,52 +558,29 @@ impl WebSocketHandle {
 /// })
 /// # ;
 /// ```
-pub fn websocket<M, F>(url: impl Into<String>, mut handler: F) -> Cmd<M>
+pub fn websocket<M, F>(url: impl Into<String>, handler: F) -> Cmd<M>
 where
     M: Message,
     F: FnMut(WebSocketEvent) -> Option<M> + Send + 'static,
 {
-    let url = url.into();
-    let handle = WebSocketHandle {
-        id: uuid::Uuid::new_v4().to_string(),
-        url: url.clone(),
-        connected: Arc::new(Mutex::new(false)),
-    };
-    
-    commands::spawn(async move {
-        // Simulate connection
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        
-        // Mark as connected
-        *handle.connected.lock().await = true;
-        
-        // Send connected event
-        handler(WebSocketEvent::Connected)
-    })
+    WebSocketHandle::new(url).connect(handler)
 }
 
 /// Create a WebSocket connection with automatic ping/pong
+///
+/// See [`WebSocketHandle::with_heartbeat`].
 pub fn websocket_with_heartbeat<M, F>(
     url: impl Into<String>,
     ping_interval: std::time::Duration,
-    mut handler: F,
+    handler: F,
 ) -> Cmd<M>
 where
     M: Message,
     F: FnMut(WebSocketEvent) -> Option<M> + Send + 'static,
 {
-    let url = url.into();
-    
-    commands::spawn(async move {
-        // In a real implementation, this would:
-        // 1. Establish WebSocket connection
-        // 2. Set up ping/pong heartbeat
-        // 3. Handle reconnection on failure
-        
-        // For now, simulate connection
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        handler(WebSocketEvent::Connected)
-    })
+    WebSocketHandle::new(url)
+        .with_heartbeat(ping_interval)
+        .connect(handler)
 }
 
 /// Helper to create a WebSocket message sender command
@@ -162,7 +589,9 @@ where
     M: Message,
 {
     commands::spawn(async move {
-        let _ = handle.send_text(message).await;
+        if let Err(e) = handle.send_text(message).await {
+            log::warn!("WebSocket {}: {}", handle.id, e);
+        }
         None
     })
 }
@@ -178,14 +607,251 @@ where
     })
 }
 
-// Note: In a real implementation, we would need to add uuid to dependencies
-// For now, we'll create a mock UUID module
-mod uuid {
-    pub struct Uuid;
-    impl Uuid {
-        pub fn new_v4() -> Self { Uuid }
-        pub fn to_string(&self) -> String { 
-            format!("ws-{}", std::process::id())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wait for the next event, failing the test if none comes
+    async fn next(events: &mut BoxStream<'static, WebSocketEvent>) -> Option<WebSocketEvent> {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("no WebSocket event")
+    }
+
+    #[test]
+    fn test_handles_have_unique_ids() {
+        let first = WebSocketHandle::new("ws://localhost/");
+        let second = WebSocketHandle::new("ws://localhost/");
+        assert_ne!(first.id, second.id);
+
+        let sub: Sub<()> = first.subscribe(|_| None);
+        assert_eq!(sub.key(), first.id);
+    }
+
+    #[tokio::test]
+    async fn test_sending_needs_a_connection() {
+        let handle = WebSocketHandle::new("ws://localhost/");
+        assert!(matches!(
+            handle.send_text("hi".into()).await,
+            Err(WebSocketError::SendFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closed_handle_does_not_connect() {
+        let handle = WebSocketHandle::new("ws://localhost/");
+        handle.close().await;
+        assert!(next(&mut handle.events()).await.is_none());
+    }
+
+    #[cfg(not(feature = "websocket"))]
+    #[tokio::test]
+    async fn test_connecting_without_the_feature_fails() {
+        let mut events = WebSocketHandle::new("ws://localhost/").events();
+        assert!(matches!(
+            next(&mut events).await,
+            Some(WebSocketEvent::Error(WebSocketError::ConnectionFailed(_)))
+        ));
+        assert!(next(&mut events).await.is_none());
+    }
+
+    #[cfg(feature = "websocket")]
+    mod tungstenite {
+        use super::*;
+        use futures::SinkExt;
+        use tokio::net::TcpListener;
+        use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+        use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+        /// Accept connections and echo every data frame back
+        async fn echo_server() -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    tokio::spawn(async move {
+                        let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                        while let Some(Ok(frame)) = ws.next().await {
+                            if frame.is_text() || frame.is_binary() {
+                                ws.send(frame).await.unwrap();
+                            }
+                        }
+                    });
+                }
+            });
+            url
+        }
+
+        fn no_reconnect(url: &str) -> WebSocketHandle {
+            WebSocketHandle::new(url).with_config(AsyncConfig::default())
+        }
+
+        #[tokio::test]
+        async fn test_echo_and_close() {
+            let url = echo_server().await;
+            let handle = no_reconnect(&url);
+            let mut events = handle.events();
+
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Connected)));
+            assert!(handle.is_connected());
+
+            handle.send_text("hello".into()).await.unwrap();
+            match next(&mut events).await {
+                Some(WebSocketEvent::Message(text)) => assert_eq!(text, "hello"),
+                other => panic!("expected the echo, got {:?}", other),
+            }
+            handle.send_binary(vec![1, 2, 3]).await.unwrap();
+            match next(&mut events).await {
+                Some(WebSocketEvent::Binary(data)) => assert_eq!(data, vec![1, 2, 3]),
+                other => panic!("expected the echo, got {:?}", other),
+            }
+
+            handle.close().await;
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Closed(None))));
+            assert!(next(&mut events).await.is_none());
+            assert!(!handle.is_connected());
+        }
+
+        #[tokio::test]
+        async fn test_reconnects_after_the_server_closes() {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move {
+                // The first connection is closed right away, the second echoes
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                ws.close(Some(CloseFrame {
+                    code: CloseCode::Restart,
+                    reason: "restarting".into(),
+                }))
+                .await
+                .unwrap();
+                while ws.next().await.is_some() {}
+                drop(ws);
+
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                while let Some(Ok(frame)) = ws.next().await {
+                    if frame.is_text() {
+                        ws.send(frame).await.unwrap();
+                    }
+                }
+            });
+
+            let handle = WebSocketHandle::new(url).with_config(
+                AsyncConfig::default()
+                    .with_retries(3, BackoffStrategy::Linear(Duration::from_millis(10))),
+            );
+            let mut events = handle.events();
+
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Connected)));
+            match next(&mut events).await {
+                Some(WebSocketEvent::Closed(reason)) => {
+                    assert_eq!(reason.as_deref(), Some("restarting"))
+                }
+                other => panic!("expected the close, got {:?}", other),
+            }
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Connected)));
+
+            handle.send_text("again".into()).await.unwrap();
+            assert!(matches!(
+                next(&mut events).await,
+                Some(WebSocketEvent::Message(text)) if text == "again"
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_gives_up_when_reconnects_run_out() {
+            // Nothing listens on a port that was just released
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            drop(listener);
+
+            let handle = WebSocketHandle::new(url).with_config(
+                AsyncConfig::default()
+                    .with_retries(2, BackoffStrategy::Exponential(Duration::from_millis(1))),
+            );
+            let mut events = handle.events();
+            for _ in 0..3 {
+                assert!(matches!(
+                    next(&mut events).await,
+                    Some(WebSocketEvent::Error(WebSocketError::ConnectionFailed(_)))
+                ));
+            }
+            assert!(next(&mut events).await.is_none());
+        }
+
+        #[tokio::test]
+        async fn test_heartbeat_drops_silent_servers() {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            let server = tokio::spawn(async move {
+                // Completes the handshake, then never reads, so pings go unanswered
+                let (socket, _) = listener.accept().await.unwrap();
+                let ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                drop(ws);
+            });
+
+            let handle = no_reconnect(&url).with_heartbeat(Duration::from_millis(20));
+            let mut events = handle.events();
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Connected)));
+            assert!(matches!(
+                next(&mut events).await,
+                Some(WebSocketEvent::Error(WebSocketError::Timeout))
+            ));
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Closed(None))));
+            assert!(next(&mut events).await.is_none());
+            server.abort();
+        }
+
+        #[tokio::test]
+        async fn test_heartbeat_keeps_answering_servers_connected() {
+            let url = echo_server().await;
+            let handle = no_reconnect(&url).with_heartbeat(Duration::from_millis(10));
+            let mut events = handle.events();
+            assert!(matches!(next(&mut events).await, Some(WebSocketEvent::Connected)));
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            handle.send_text("still here".into()).await.unwrap();
+            assert!(matches!(
+                next(&mut events).await,
+                Some(WebSocketEvent::Message(text)) if text == "still here"
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_commands_deliver_mapped_events() {
+            let url = echo_server().await;
+            let handle = no_reconnect(&url);
+            let sender = handle.clone();
+            let closer = handle.clone();
+
+            let cmd = handle.connect(move |event| match event {
+                WebSocketEvent::Connected => {
+                    let sender = sender.clone();
+                    tokio::spawn(async move { sender.send_text("ping".into()).await.unwrap() });
+                    None
+                }
+                WebSocketEvent::Message(text) => {
+                    let closer = closer.clone();
+                    tokio::spawn(async move { closer.close().await });
+                    Some(text)
+                }
+                _ => None,
+            });
+            let messages: Vec<String> = cmd.take_stream().unwrap().collect().await;
+            assert_eq!(messages, vec!["ping".to_string()]);
+        }
+
+        #[tokio::test]
+        async fn test_invalid_urls_are_not_retried() {
+            let mut events = WebSocketHandle::new("http://localhost/").events();
+            assert!(matches!(
+                next(&mut events).await,
+                Some(WebSocketEvent::Error(WebSocketError::ConnectionFailed(_)))
+            ));
+            assert!(next(&mut events).await.is_none());
         }
     }
-}
\ No newline at end of file
+}
@@ -6,9 +6,11 @@
 //! 
 //! ## Core Command Types
 //! 
-//! - **Synchronous**: Simple functions that return messages
+//! - **Synchronous**: Simple functions that return messages, run on blocking
+//!   threads so they can't hold up timers and streams
 //! - **Asynchronous**: Futures that eventually produce messages
 //! - **Timed**: Commands that execute after delays or at intervals
+//! - **Streaming**: Streams and channels that send many messages over time
 //! - **Composite**: Batch and sequence commands for complex flows
 //! 
 //! ## Common Patterns
@@ -53,9 +55,13 @@
 
 use crate::core::{Cmd, Message};
 use crate::event::WindowSize;
-use std::process::Command;
+use futures::stream::{self, Stream, StreamExt};
 use std::time::Duration;
 
+/// How many messages a [`run_with_sender`] task can send before it waits for
+/// the program to catch up
+const SENDER_BUFFER: usize = 64;
+
 /// Default maximum batch size
 /// 
 /// Batches larger than this will trigger a warning in debug mode.
@@ -68,8 +74,12 @@ const DEFAULT_MAX_BATCH_SIZE: usize = 100;
 /// This prevents accidental memory exhaustion from massive batches.
 const HARD_MAX_BATCH_SIZE: usize = 1000;
 
-/// Special message types for terminal control
-#[derive(Debug, Clone)]
+/// Terminal state changes carried by terminal control commands
+///
+/// The program loop applies these through its terminal manager on the render
+/// thread, so they never race with drawing. Outside of a running program they
+/// do nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TerminalControlMsg {
     /// Hide the terminal cursor from view
     HideCursor,
@@ -91,6 +101,20 @@ pub enum TerminalControlMsg {
     ClearScreen,
     /// Clear the current line
     ClearLine,
+    /// Enable bracketed paste mode
+    EnableBracketedPaste,
+    /// Disable bracketed paste mode
+    DisableBracketedPaste,
+    /// Enable focus change reporting
+    EnableFocusChange,
+    /// Disable focus change reporting
+    DisableFocusChange,
+    /// Print a line above an inline program
+    PrintLine(String),
+    /// Redraw the view even though no event changed the model
+    Invalidate,
+    /// Keep redrawing at the frame rate cap for the given duration
+    Animate(Duration),
 }
 
 /// Create a no-op command
@@ -106,6 +130,21 @@ pub fn none<M: Message>() -> Cmd<M> {
     Cmd::none()
 }
 
+/// Create a command that sends `msg` right away
+///
+/// Use it to report a message, e.g. from a component to its parent, without
+/// running a function for it. See [`Cmd::message`].
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::message};
+/// # enum Msg { Saved }
+/// let cmd: Cmd<Msg> = message(Msg::Saved);
+/// ```
+pub fn message<M: Message>(msg: M) -> Cmd<M> {
+    Cmd::message(msg)
+}
+
 /// Batch multiple commands to run concurrently
 ///
 /// Note: For performance optimization:
@@ -269,26 +308,79 @@ where
 
 /// Create a command that ticks at regular intervals
 ///
-/// Similar to Bubbletea's Every command, this aligns with system clock
-/// boundaries. For example, `every(Duration::from_secs(1))` will tick
-/// at the start of each second.
+/// The callback runs every `duration` until the program quits. Ticks are
+/// scheduled from the time the command starts, so a slow callback doesn't make
+/// the timer drift; if ticks are missed (e.g. while suspended) they are
+/// skipped rather than delivered in a burst.
+///
+/// Use [`every_aligned`] to tick on wall-clock boundaries instead, and
+/// [`every_until`] for a timer that can stop itself.
 ///
 /// # Example
 /// ```
 /// # use hojicha_core::{Cmd, commands::every};
 /// # use std::time::{Duration, Instant};
-/// # enum Msg { Tick(Instant) }
-/// // Send a message every second
-/// let cmd: Cmd<Msg> = every(Duration::from_secs(1), |instant| Msg::Tick(instant));
+/// # enum Msg { Tick(u64) }
+/// // Send a numbered message every second
+/// let mut count = 0;
+/// let cmd: Cmd<Msg> = every(Duration::from_secs(1), move |_| {
+///     count += 1;
+///     Msg::Tick(count)
+/// });
 /// ```
 pub fn every<M, F>(duration: Duration, f: F) -> Cmd<M>
 where
     M: Message,
-    F: FnOnce(std::time::Instant) -> M + Send + 'static,
+    F: FnMut(std::time::Instant) -> M + Send + 'static,
 {
     Cmd::every(duration, f)
 }
 
+/// Create a command that ticks on wall-clock boundaries
+///
+/// Like [`every`], but ticks land on multiples of `duration` since the Unix
+/// epoch, similar to Bubbletea's Every. For example,
+/// `every_aligned(Duration::from_secs(1), ..)` ticks at the start of each
+/// second, which keeps a clock display in step with the system clock.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::every_aligned};
+/// # use std::time::{Duration, Instant};
+/// # enum Msg { Tick(Instant) }
+/// // Tick at the top of every minute
+/// let cmd: Cmd<Msg> = every_aligned(Duration::from_secs(60), Msg::Tick);
+/// ```
+pub fn every_aligned<M, F>(duration: Duration, mut f: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnMut(std::time::Instant) -> M + Send + 'static,
+{
+    Cmd::every_with(duration, true, move |instant| Some(f(instant)))
+}
+
+/// Create a recurring timer that stops when the callback returns `None`
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::every_until};
+/// # use std::time::Duration;
+/// # enum Msg { Countdown(u32) }
+/// // Count down from 10, then stop
+/// let mut remaining: u32 = 10;
+/// let cmd: Cmd<Msg> = every_until(Duration::from_secs(1), move |_| {
+///     remaining = remaining.checked_sub(1)?;
+///     Some(Msg::Countdown(remaining))
+/// });
+/// ```
+pub fn every_until<M, F>(duration: Duration, f: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnMut(std::time::Instant) -> Option<M> + Send + 'static,
+{
+    Cmd::every_with(duration, false, f)
+}
+
 /// Query the terminal for its current size
 ///
 /// This command returns a WindowSize message with the current terminal dimensions.
@@ -332,12 +424,76 @@ where
 /// let cmd: Cmd<Msg> = set_window_title("My Awesome App");
 /// ```
 pub fn set_window_title<M: Message>(title: impl Into<String>) -> Cmd<M> {
-    let title = title.into();
-    Cmd::new(move || {
-        use crossterm::{execute, terminal::SetTitle};
-        let _ = execute!(std::io::stdout(), SetTitle(&title));
-        None
-    })
+    Cmd::terminal_control(TerminalControlMsg::SetWindowTitle(title.into()))
+}
+
+/// Print a line above the program's inline viewport
+///
+/// The line is permanent: it scrolls up into the terminal's scrollback
+/// instead of being redrawn. Only programs using `ProgramOptions::with_inline`
+/// have room for it; other programs print the line to stderr, like
+/// `Program::println`.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::println};
+/// # enum Msg {}
+/// // Log a finished step above a progress bar
+/// let cmd: Cmd<Msg> = println("Downloaded crate index");
+/// ```
+pub fn println<M: Message>(text: impl Into<String>) -> Cmd<M> {
+    Cmd::terminal_control(TerminalControlMsg::PrintLine(text.into()))
+}
+
+/// Cancel the command running under `key`
+///
+/// Cancels a command started with [`Cmd::keyed`] or [`Cmd::keyed_with`] and
+/// drops any commands queued behind it. Its message is never delivered. Does
+/// nothing if no command is running under the key.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::cancel};
+/// # enum Msg {}
+/// // The user cleared the search box
+/// let cmd: Cmd<Msg> = cancel("search");
+/// ```
+pub fn cancel<M: Message>(key: impl Into<String>) -> Cmd<M> {
+    Cmd::cancel(key)
+}
+
+/// Redraw the view on the next frame
+///
+/// The program only redraws after an event reaches `update`, so a model whose
+/// view depends on state changed elsewhere (e.g. shared with another thread)
+/// uses this to ask for a frame.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::invalidate};
+/// # enum Msg {}
+/// let cmd: Cmd<Msg> = invalidate();
+/// ```
+pub fn invalidate<M: Message>() -> Cmd<M> {
+    Cmd::terminal_control(TerminalControlMsg::Invalidate)
+}
+
+/// Redraw continuously for a while
+///
+/// For views that animate on their own, computing each frame from the clock
+/// rather than from messages. Frames are drawn at the `with_fps` cap until
+/// `duration` has passed; calling it again extends the animation.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::animate};
+/// # use std::time::Duration;
+/// # enum Msg {}
+/// // Fade a highlight out over half a second
+/// let cmd: Cmd<Msg> = animate(Duration::from_millis(500));
+/// ```
+pub fn animate<M: Message>(duration: Duration) -> Cmd<M> {
+    Cmd::terminal_control(TerminalControlMsg::Animate(duration))
 }
 
 /// Send an interrupt signal (simulates Ctrl+C)
@@ -374,22 +530,50 @@ pub fn interrupt<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = hide_cursor();
 /// ```
 pub fn hide_cursor<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::HideCursor)
 }
 
 /// Show the terminal cursor
+///
+/// Ratatui hides the cursor again on the next frame unless the view places it
+/// with `Frame::set_cursor_position`.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::show_cursor};
+/// # enum Msg {}
+/// let cmd: Cmd<Msg> = show_cursor();
+/// ```
 pub fn show_cursor<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::ShowCursor)
 }
 
 /// Enter alternate screen buffer
+///
+/// The next frame is a full redraw on the alternate screen.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::enter_alt_screen};
+/// # enum Msg {}
+/// let cmd: Cmd<Msg> = enter_alt_screen();
+/// ```
 pub fn enter_alt_screen<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::EnterAltScreen)
 }
 
 /// Exit alternate screen buffer
+///
+/// The program keeps running and drawing on the main screen.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::exit_alt_screen};
+/// # enum Msg {}
+/// let cmd: Cmd<Msg> = exit_alt_screen();
+/// ```
 pub fn exit_alt_screen<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::ExitAltScreen)
 }
 
 /// Create a custom command from an async function
@@ -441,6 +625,76 @@ where
     Cmd::async_cmd(fut)
 }
 
+/// Create a command that sends every message of a stream
+///
+/// Unlike [`spawn`], which sends at most one message, the stream can send any
+/// number of them over time, e.g. progress updates or lines of a log. The
+/// command ends with the stream; in a [`sequence`] the next command waits
+/// until then. Run it under a key with [`Cmd::keyed`] to be able to
+/// [`cancel`] it.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands};
+/// # enum Msg { Progress(u32) }
+/// use futures::stream;
+///
+/// let cmd: Cmd<Msg> = commands::stream(stream::iter((1..=10).map(|i| Msg::Progress(i * 10))))
+///     .keyed("download");
+/// ```
+pub fn stream<M, S>(stream: S) -> Cmd<M>
+where
+    M: Message,
+    S: Stream<Item = M> + Send + 'static,
+{
+    Cmd::stream(stream)
+}
+
+/// Create a command from an async task that sends messages through a channel
+///
+/// `f` gets the sending half of a channel and its future runs on the
+/// program's runtime; every message sent arrives in `update`. The command ends
+/// once the future has finished and every clone of the sender is dropped.
+///
+/// `send` waits while the program is behind and fails once the command is
+/// cancelled or the program quits, so the task should stop then.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands};
+/// # enum Msg { Page(Vec<String>), Done }
+/// # async fn fetch_page(page: usize) -> Option<Vec<String>> { None }
+/// let cmd: Cmd<Msg> = commands::run_with_sender(|tx| async move {
+///     let mut page = 0;
+///     while let Some(items) = fetch_page(page).await {
+///         if tx.send(Msg::Page(items)).await.is_err() {
+///             return; // Cancelled
+///         }
+///         page += 1;
+///     }
+///     let _ = tx.send(Msg::Done).await;
+/// });
+/// ```
+pub fn run_with_sender<M, F, Fut>(f: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnOnce(tokio::sync::mpsc::Sender<M>) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    // Nothing runs until the executor polls the stream
+    let messages = stream::once(async move {
+        let (tx, rx) = tokio::sync::mpsc::channel(SENDER_BUFFER);
+        let task = stream::once(f(tx)).filter_map(|()| async { None });
+        let received = stream::unfold(rx, |mut rx| async move {
+            let msg = rx.recv().await?;
+            Some((msg, rx))
+        });
+        stream::select(received, task)
+    })
+    .flatten();
+    Cmd::stream(messages)
+}
+
 /// Create a custom command from a blocking function
 ///
 /// This is a convenience wrapper for creating simple custom commands.
@@ -465,6 +719,32 @@ where
     Cmd::new(f)
 }
 
+/// Run blocking work, such as parsing a large file or waiting on a
+/// `std::process::Command`, and send its result
+///
+/// Every synchronous command runs on the program's blocking thread pool rather
+/// than on an async worker, so timers and streams keep running while it works.
+/// At most `ResourceLimits::max_blocking_tasks` of them run at once; the rest
+/// wait for a free thread. This constructor makes that explicit for work that
+/// always produces a message.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Cmd, commands::blocking};
+/// # enum Msg { Counted(usize) }
+/// let cmd: Cmd<Msg> = blocking(|| {
+///     let text = std::fs::read_to_string("big.log").unwrap_or_default();
+///     Msg::Counted(text.lines().count())
+/// });
+/// ```
+pub fn blocking<M, F>(f: F) -> Cmd<M>
+where
+    M: Message,
+    F: FnOnce() -> M + Send + 'static,
+{
+    Cmd::new(move || Some(f()))
+}
+
 /// Create a custom fallible command
 ///
 /// This allows you to create commands that can fail and handle errors gracefully.
@@ -521,7 +801,10 @@ where
 /// Execute a command in a subprocess, releasing the terminal while it runs
 ///
 /// This is useful for running interactive programs like editors or shells.
-/// The terminal will be restored after the command completes.
+/// The program leaves raw mode and the alternate screen, runs the process with
+/// inherited stdio, then restores the terminal and redraws. The callback
+/// receives the exit code, or `None` if the process couldn't be started or
+/// was killed by a signal.
 ///
 /// # Example
 /// ```
@@ -561,16 +844,11 @@ where
 {
     let command = command.into();
 
-    Cmd::new(move || {
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd").args(["/C", &command]).status()
-        } else {
-            Command::new("sh").args(["-c", &command]).status()
-        };
-
-        let exit_code = output.ok().and_then(|status| status.code());
-        Some(callback(exit_code))
-    })
+    if cfg!(target_os = "windows") {
+        Cmd::exec_process("cmd".to_string(), vec!["/C".to_string(), command], callback)
+    } else {
+        Cmd::exec_process("sh".to_string(), vec!["-c".to_string(), command], callback)
+    }
 }
 
 /// Enable mouse cell motion tracking
@@ -585,7 +863,7 @@ where
 /// let cmd: Cmd<Msg> = enable_mouse_cell_motion();
 /// ```
 pub fn enable_mouse_cell_motion<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::EnableMouseCellMotion)
 }
 
 /// Enable mouse all motion tracking
@@ -601,7 +879,7 @@ pub fn enable_mouse_cell_motion<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = enable_mouse_all_motion();
 /// ```
 pub fn enable_mouse_all_motion<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::EnableMouseAllMotion)
 }
 
 /// Disable mouse tracking
@@ -614,7 +892,7 @@ pub fn enable_mouse_all_motion<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = disable_mouse();
 /// ```
 pub fn disable_mouse<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::DisableMouse)
 }
 
 /// Clear the entire screen
@@ -627,7 +905,7 @@ pub fn disable_mouse<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = clear_screen();
 /// ```
 pub fn clear_screen<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::ClearScreen)
 }
 
 /// Clear the current line
@@ -640,7 +918,7 @@ pub fn clear_screen<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = clear_line();
 /// ```
 pub fn clear_line<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::terminal_control(TerminalControlMsg::ClearLine)
 }
 
 /// Quit the program gracefully
@@ -677,7 +955,13 @@ pub fn quit<M: Message>() -> Cmd<M> {
 /// Suspend the program (Ctrl+Z)
 ///
 /// This will suspend the program and return control to the shell.
-/// When the program is resumed, a Resume event will be sent.
+/// The model first receives `Event::Suspend`, where it can prepare or veto
+/// the suspend with [`cancel_suspend`]. The terminal is then released and the
+/// process is stopped with SIGTSTP; once it is continued (e.g. with `fg`) the
+/// terminal is restored and an `Event::Resume` is sent.
+///
+/// Pressing Ctrl+Z has the same effect. On platforms without job control the
+/// program is not stopped, but the model still sees both events.
 ///
 /// # Example
 /// ```
@@ -687,7 +971,33 @@ pub fn quit<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = suspend();
 /// ```
 pub fn suspend<M: Message>() -> Cmd<M> {
-    Cmd::new(|| None) // This will be handled by the runtime
+    Cmd::suspend()
+}
+
+/// Veto a pending suspend
+///
+/// Return this from `update` when handling `Event::Suspend` to keep the
+/// program running. Outside of a suspend request it does nothing.
+///
+/// # Example
+/// ```
+/// # use hojicha_core::{Model, Cmd, Event, commands::cancel_suspend};
+/// # use ratatui::{Frame, layout::Rect};
+/// # struct Editor { unsaved: bool }
+/// # impl Model for Editor {
+/// #     type Message = ();
+/// fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+///     match event {
+///         // Use Ctrl+Z for undo while there are unsaved changes
+///         Event::Suspend if self.unsaved => cancel_suspend(),
+///         _ => Cmd::none(),
+///     }
+/// }
+/// #     fn view(&self, _: &mut Frame, _: Rect) {}
+/// # }
+/// ```
+pub fn cancel_suspend<M: Message>() -> Cmd<M> {
+    Cmd::cancel_suspend()
 }
 
 /// Enable bracketed paste mode
@@ -705,14 +1015,7 @@ pub fn suspend<M: Message>() -> Cmd<M> {
 /// // Now pasted text will be delivered as Event::Paste(String)
 /// ```
 pub fn enable_bracketed_paste<M: Message>() -> Cmd<M> {
-    Cmd::new(|| {
-        use crossterm::{event::EnableBracketedPaste, execute};
-        use std::io;
-
-        // Send the enable bracketed paste sequence to the terminal
-        let _ = execute!(io::stdout(), EnableBracketedPaste);
-        None
-    })
+    Cmd::terminal_control(TerminalControlMsg::EnableBracketedPaste)
 }
 
 /// Disable bracketed paste mode
@@ -725,14 +1028,7 @@ pub fn enable_bracketed_paste<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = disable_bracketed_paste();
 /// ```
 pub fn disable_bracketed_paste<M: Message>() -> Cmd<M> {
-    Cmd::new(|| {
-        use crossterm::{event::DisableBracketedPaste, execute};
-        use std::io;
-
-        // Send the disable bracketed paste sequence to the terminal
-        let _ = execute!(io::stdout(), DisableBracketedPaste);
-        None
-    })
+    Cmd::terminal_control(TerminalControlMsg::DisableBracketedPaste)
 }
 
 /// Enable focus change reporting
@@ -749,14 +1045,7 @@ pub fn disable_bracketed_paste<M: Message>() -> Cmd<M> {
 /// // Now the program will receive Event::Focus and Event::Blur
 /// ```
 pub fn enable_focus_change<M: Message>() -> Cmd<M> {
-    Cmd::new(|| {
-        use crossterm::{event::EnableFocusChange, execute};
-        use std::io;
-
-        // Send the enable focus change sequence to the terminal
-        let _ = execute!(io::stdout(), EnableFocusChange);
-        None
-    })
+    Cmd::terminal_control(TerminalControlMsg::EnableFocusChange)
 }
 
 /// Disable focus change reporting
@@ -769,14 +1058,7 @@ pub fn enable_focus_change<M: Message>() -> Cmd<M> {
 /// let cmd: Cmd<Msg> = disable_focus_change();
 /// ```
 pub fn disable_focus_change<M: Message>() -> Cmd<M> {
-    Cmd::new(|| {
-        use crossterm::{event::DisableFocusChange, execute};
-        use std::io;
-
-        // Send the disable focus change sequence to the terminal
-        let _ = execute!(io::stdout(), DisableFocusChange);
-        None
-    })
+    Cmd::terminal_control(TerminalControlMsg::DisableFocusChange)
 }
 
 #[cfg(test)]
@@ -790,6 +1072,43 @@ mod tests {
         Three,
     }
 
+    #[test]
+    fn test_stream_is_a_stream_command() {
+        let cmd: Cmd<TestMsg> = stream(stream::iter(vec![TestMsg::One, TestMsg::Two]));
+        assert!(cmd.is_stream());
+
+        let messages = futures::executor::block_on(cmd.take_stream().unwrap().collect::<Vec<_>>());
+        assert_eq!(messages, vec![TestMsg::One, TestMsg::Two]);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_sender_sends_until_task_ends() {
+        let cmd: Cmd<TestMsg> = run_with_sender(|tx| async move {
+            for msg in [TestMsg::One, TestMsg::Two, TestMsg::Three] {
+                tx.send(msg).await.unwrap();
+            }
+        });
+
+        let messages = cmd.take_stream().unwrap().collect::<Vec<_>>().await;
+        assert_eq!(messages, vec![TestMsg::One, TestMsg::Two, TestMsg::Three]);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_sender_task_is_dropped_with_command() {
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+        let cmd: Cmd<TestMsg> = run_with_sender(|tx| async move {
+            tx.send(TestMsg::One).await.unwrap();
+            std::future::pending::<()>().await;
+            let _ = done_tx.send(());
+        });
+
+        // Take the first message, then drop the command like a cancelled key
+        let mut messages = cmd.take_stream().unwrap();
+        assert_eq!(messages.next().await, Some(TestMsg::One));
+        drop(messages);
+        assert!(done_rx.await.is_err());
+    }
+
     #[test]
     fn test_batch_empty() {
         let result: Cmd<TestMsg> = batch(vec![]);
@@ -839,6 +1158,16 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_every_variants() {
+        let aligned: Cmd<TestMsg> = every_aligned(Duration::from_secs(1), |_| TestMsg::One);
+        assert!(aligned.is_every());
+
+        let until: Cmd<TestMsg> = every_until(Duration::from_secs(1), |_| None);
+        assert!(until.is_every());
+        assert_eq!(until.debug_name(), "Every");
+    }
+
     #[test]
     fn test_window_size_command() {
         // Test that window_size returns a valid WindowSize
@@ -904,6 +1233,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_blocking_command() {
+        let cmd = blocking(|| TestMsg::One);
+        assert!(cmd.is_blocking());
+        assert_eq!(cmd.execute().unwrap(), Some(TestMsg::One));
+
+        assert!(custom_fallible::<TestMsg, _>(|| Ok(None)).is_blocking());
+        assert!(!custom_async::<TestMsg, _, _>(|| async { None }).is_blocking());
+        assert!(!none::<TestMsg>().is_blocking());
+        assert!(!message(TestMsg::One).is_blocking());
+    }
+
+    #[test]
+    fn test_message_command() {
+        let cmd = message(TestMsg::Two);
+        assert!(cmd.is_message());
+        assert_eq!(cmd.execute().unwrap(), Some(TestMsg::Two));
+    }
+
     #[test]
     fn test_custom_async_command() {
         let cmd = custom_async::<TestMsg, _, _>(|| async { Some(TestMsg::Three) });
@@ -913,6 +1261,44 @@ mod tests {
         assert_eq!(result.unwrap(), None);
     }
 
+    #[test]
+    fn test_terminal_commands_carry_control_msg() {
+        let cases: Vec<(Cmd<TestMsg>, TerminalControlMsg)> = vec![
+            (hide_cursor(), TerminalControlMsg::HideCursor),
+            (show_cursor(), TerminalControlMsg::ShowCursor),
+            (enter_alt_screen(), TerminalControlMsg::EnterAltScreen),
+            (exit_alt_screen(), TerminalControlMsg::ExitAltScreen),
+            (
+                set_window_title("Title"),
+                TerminalControlMsg::SetWindowTitle("Title".to_string()),
+            ),
+            (enable_mouse_cell_motion(), TerminalControlMsg::EnableMouseCellMotion),
+            (enable_mouse_all_motion(), TerminalControlMsg::EnableMouseAllMotion),
+            (disable_mouse(), TerminalControlMsg::DisableMouse),
+            (clear_screen(), TerminalControlMsg::ClearScreen),
+            (clear_line(), TerminalControlMsg::ClearLine),
+            (enable_bracketed_paste(), TerminalControlMsg::EnableBracketedPaste),
+            (disable_bracketed_paste(), TerminalControlMsg::DisableBracketedPaste),
+            (enable_focus_change(), TerminalControlMsg::EnableFocusChange),
+            (disable_focus_change(), TerminalControlMsg::DisableFocusChange),
+            (
+                println("Done"),
+                TerminalControlMsg::PrintLine("Done".to_string()),
+            ),
+            (invalidate(), TerminalControlMsg::Invalidate),
+            (
+                animate(Duration::from_millis(500)),
+                TerminalControlMsg::Animate(Duration::from_millis(500)),
+            ),
+        ];
+
+        for (cmd, expected) in cases {
+            assert!(cmd.is_terminal_control());
+            assert_eq!(cmd.debug_name(), "TerminalControl");
+            assert_eq!(cmd.take_terminal_control(), Some(expected));
+        }
+    }
+
     #[test]
     fn test_window_title_command() {
         let cmd: Cmd<TestMsg> = set_window_title("Test Title");
@@ -931,6 +1317,22 @@ mod tests {
         assert!(process_info.is_some());
     }
 
+    #[test]
+    fn test_exec_command_uses_shell() {
+        let cmd: Cmd<TestMsg> = exec_command("exit 3", |_| TestMsg::One);
+        assert!(cmd.is_exec_process());
+
+        let (program, args, callback) = cmd.take_exec_process().unwrap();
+        if cfg!(target_os = "windows") {
+            assert_eq!(program, "cmd");
+            assert_eq!(args, vec!["/C", "exit 3"]);
+        } else {
+            assert_eq!(program, "sh");
+            assert_eq!(args, vec!["-c", "exit 3"]);
+        }
+        assert_eq!(callback(Some(3)), TestMsg::One);
+    }
+
     #[test]
     fn test_mouse_commands() {
         let cell_motion: Cmd<TestMsg> = enable_mouse_cell_motion();
@@ -954,7 +1356,20 @@ mod tests {
     #[test]
     fn test_suspend_command() {
         let cmd: Cmd<TestMsg> = suspend();
+        assert!(cmd.is_suspend());
         assert!(cmd.test_execute().is_ok());
+
+        let cancel: Cmd<TestMsg> = cancel_suspend();
+        assert!(cancel.is_cancel_suspend());
+        assert!(!cancel.is_suspend());
+        assert!(matches!(cancel.test_execute(), Ok(None)));
+
+        let nested: Cmd<TestMsg> = batch(vec![
+            custom(|| Some(TestMsg::One)),
+            sequence(vec![cancel_suspend(), quit()]),
+        ]);
+        assert!(nested.cancels_suspend());
+        assert!(!batch::<TestMsg>(vec![suspend(), quit()]).cancels_suspend());
     }
 
     #[test]
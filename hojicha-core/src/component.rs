@@ -0,0 +1,209 @@
+//! Reusable, stateful UI components
+//!
+//! A [`Component`] is a small model that lives inside another one. It has its
+//! own message type, handles events in `update`, draws itself in `view`, and
+//! can take part in focus handling and layout. Because every component has the
+//! same interface, containers can host any of them.
+//!
+//! The parent routes events to the child and wraps the child's messages in
+//! its own with [`Cmd::map`], [`Event::try_map`] and [`Subs::map`]:
+//!
+//! ```
+//! # use hojicha_core::{Cmd, Component, Event, Model, SizeHint};
+//! # use ratatui::{layout::Rect, Frame};
+//! #[derive(Default)]
+//! struct Counter {
+//!     count: u32,
+//!     focused: bool,
+//! }
+//!
+//! enum CounterMsg {
+//!     Increment,
+//! }
+//!
+//! impl Component for Counter {
+//!     type Message = CounterMsg;
+//!
+//!     fn update(&mut self, event: Event<CounterMsg>) -> Cmd<CounterMsg> {
+//!         if let Event::User(CounterMsg::Increment) = event {
+//!             self.count += 1;
+//!         }
+//!         Cmd::none()
+//!     }
+//!
+//!     fn view(&self, _frame: &mut Frame, _area: Rect) {}
+//!
+//!     fn focus(&mut self) {
+//!         self.focused = true;
+//!     }
+//!
+//!     fn blur(&mut self) {
+//!         self.focused = false;
+//!     }
+//!
+//!     fn is_focused(&self) -> bool {
+//!         self.focused
+//!     }
+//!
+//!     fn size_hint(&self) -> SizeHint {
+//!         SizeHint::fixed(10, 1)
+//!     }
+//! }
+//!
+//! enum Msg {
+//!     Counter(CounterMsg),
+//! }
+//!
+//! struct App {
+//!     counter: Counter,
+//! }
+//!
+//! impl Model for App {
+//!     type Message = Msg;
+//!
+//!     fn update(&mut self, event: Event<Msg>) -> Cmd<Msg> {
+//!         let child = event.try_map(|msg| match msg {
+//!             Msg::Counter(msg) => Some(msg),
+//!         });
+//!         match child {
+//!             Some(event) => self.counter.update(event).map(Msg::Counter),
+//!             None => Cmd::none(),
+//!         }
+//!     }
+//!
+//!     fn view(&self, frame: &mut Frame, area: Rect) {
+//!         self.counter.view(frame, area);
+//!     }
+//! }
+//! ```
+//!
+//! [`Cmd::map`]: crate::core::Cmd::map
+//! [`Event::try_map`]: crate::event::Event::try_map
+//! [`Subs::map`]: crate::subscriptions::Subs::map
+
+use crate::core::{Cmd, Message};
+use crate::event::Event;
+use crate::subscriptions::Subs;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+/// A reusable piece of UI with its own state and messages
+///
+/// Components work like a [`Model`](crate::core::Model) that is embedded in
+/// another model instead of run by a program. Only `update` and `view` are
+/// required; focus handling, size hints and subscriptions have defaults for
+/// components that don't need them.
+pub trait Component {
+    /// The messages this component sends and receives
+    type Message: Message;
+
+    /// Handle an event and return a command
+    ///
+    /// Messages the component reports to its parent (e.g. "pressed" or
+    /// "selection changed") are sent with the returned command.
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message>;
+
+    /// Draw the component into `area`
+    fn view(&self, frame: &mut Frame, area: Rect);
+
+    /// Declare the event sources the component listens to
+    ///
+    /// Parents merge these into their own [`Model::subscriptions`]. Keys must
+    /// be unique across the components of a model.
+    ///
+    /// [`Model::subscriptions`]: crate::core::Model::subscriptions
+    fn subscriptions(&self) -> Subs<Self::Message> {
+        Subs::none()
+    }
+
+    /// Give the component keyboard focus
+    fn focus(&mut self) {}
+
+    /// Take keyboard focus away from the component
+    fn blur(&mut self) {}
+
+    /// Whether the component has keyboard focus
+    fn is_focused(&self) -> bool {
+        false
+    }
+
+    /// The size the component would like to be drawn at
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+/// The size a component needs and would like, in cells
+///
+/// `None` means the component has no preference and fills what it's given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SizeHint {
+    /// Narrowest width the component can be drawn at
+    pub min_width: u16,
+    /// Lowest height the component can be drawn at
+    pub min_height: u16,
+    /// Width the component would like
+    pub width: Option<u16>,
+    /// Height the component would like
+    pub height: Option<u16>,
+}
+
+impl SizeHint {
+    /// A component of exactly this size
+    pub fn fixed(width: u16, height: u16) -> Self {
+        Self {
+            min_width: width,
+            min_height: height,
+            width: Some(width),
+            height: Some(height),
+        }
+    }
+
+    /// A component that fills the width it's given and is `height` rows tall
+    pub fn rows(height: u16) -> Self {
+        Self {
+            min_height: height,
+            height: Some(height),
+            ..Self::default()
+        }
+    }
+
+    /// Set the smallest size the component can be drawn at
+    pub fn with_min(mut self, width: u16, height: u16) -> Self {
+        self.min_width = width;
+        self.min_height = height;
+        self
+    }
+
+    /// Fit the hint into `area`, keeping preferred sizes where possible
+    pub fn fit(&self, area: Rect) -> Rect {
+        Rect {
+            width: self.width.unwrap_or(area.width).min(area.width),
+            height: self.height.unwrap_or(area.height).min(area.height),
+            ..area
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_hint_fit() {
+        let area = Rect::new(2, 3, 40, 10);
+
+        assert_eq!(SizeHint::default().fit(area), area);
+        assert_eq!(SizeHint::fixed(12, 3).fit(area), Rect::new(2, 3, 12, 3));
+        assert_eq!(SizeHint::rows(1).fit(area), Rect::new(2, 3, 40, 1));
+        assert_eq!(SizeHint::fixed(80, 30).fit(area), area);
+    }
+
+    #[test]
+    fn test_size_hint_min() {
+        let hint = SizeHint::rows(3).with_min(20, 1);
+        assert_eq!(hint.min_width, 20);
+        assert_eq!(hint.min_height, 1);
+        assert_eq!(hint.height, Some(3));
+    }
+}
@@ -48,9 +48,12 @@
 //! });
 //! ```
 
+use crate::commands::TerminalControlMsg;
 use crate::event::Event;
+use crate::subscriptions::Subs;
 use ratatui::layout::Rect;
 use ratatui::Frame;
+use futures::stream::{BoxStream, StreamExt};
 use std::fmt::Debug;
 
 /// Type alias for exec process callback function
@@ -59,6 +62,34 @@ type ExecCallback<M> = Box<dyn Fn(Option<i32>) -> M + Send>;
 /// Type alias for exec process details
 type ExecDetails<M> = (String, Vec<String>, ExecCallback<M>);
 
+/// Type alias for recurring timer callback function
+///
+/// Returning `None` stops the timer.
+type EveryCallback<M> = Box<dyn FnMut(std::time::Instant) -> Option<M> + Send>;
+
+/// Type alias for recurring timer details
+type EveryDetails<M> = (std::time::Duration, bool, EveryCallback<M>);
+
+/// Type alias for keyed command details
+type KeyedDetails<M> = (String, KeyPolicy, Cmd<M>);
+
+/// Type alias for a message conversion shared by every part of a mapped command
+type MapFn<M, N> = std::sync::Arc<dyn Fn(M) -> N + Send + Sync>;
+
+/// What happens when a keyed command starts while its key is still running
+///
+/// See [`Cmd::keyed`] and [`Cmd::keyed_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyPolicy {
+    /// Cancel the running command and start the new one ("latest wins")
+    #[default]
+    Supersede,
+    /// Start the new command once the running one has finished
+    Queue,
+    /// Keep the running command and discard the new one
+    Drop,
+}
+
 /// A message that can be sent to update the model.
 ///
 /// Messages are typically enums that represent different events
@@ -170,6 +201,18 @@ pub trait Model: Sized {
     /// # }
     /// ```
     fn view(&self, frame: &mut Frame, area: Rect);
+
+    /// Declare the event sources the model listens to
+    ///
+    /// Called after `init` and after every update. Each [`Sub`] is identified
+    /// by its key: a new key starts its stream, a key that is no longer
+    /// returned has its stream cancelled, and a key that stays keeps running.
+    /// See [`crate::subscriptions`] for an example.
+    ///
+    /// [`Sub`]: crate::subscriptions::Sub
+    fn subscriptions(&self) -> Subs<Self::Message> {
+        Subs::none()
+    }
 }
 
 /// A command is an asynchronous operation that produces a message.
@@ -186,6 +229,8 @@ pub struct Cmd<M: Message> {
 pub(crate) enum CmdInner<M: Message> {
     /// No operation - continue running without doing anything
     NoOp,
+    /// Send a message right away
+    Message(M),
     /// A simple function command
     Function(Box<dyn FnOnce() -> Option<M> + Send>),
     /// A function command that can return errors
@@ -198,6 +243,12 @@ pub(crate) enum CmdInner<M: Message> {
     },
     /// Quit the program
     Quit,
+    /// Suspend the program (Ctrl+Z)
+    Suspend,
+    /// Veto a pending suspend
+    CancelSuspend,
+    /// Change terminal state from the program loop
+    TerminalControl(TerminalControlMsg),
     /// Execute multiple commands concurrently
     Batch(Vec<Cmd<M>>),
     /// Execute multiple commands sequentially
@@ -210,10 +261,22 @@ pub(crate) enum CmdInner<M: Message> {
     /// Execute repeatedly at intervals
     Every {
         duration: std::time::Duration,
-        callback: Box<dyn FnOnce(std::time::Instant) -> M + Send>,
+        /// Align ticks to wall-clock multiples of the interval
+        wall_clock: bool,
+        callback: EveryCallback<M>,
     },
     /// Execute an async future
     Async(Box<dyn std::future::Future<Output = Option<M>> + Send>),
+    /// Send every message of a stream
+    Stream(BoxStream<'static, M>),
+    /// Run a command under a key so it can be superseded or cancelled
+    Keyed {
+        key: String,
+        policy: KeyPolicy,
+        cmd: Box<Cmd<M>>,
+    },
+    /// Cancel the keyed command running under a key
+    Cancel(String),
 }
 
 impl<M: Message> Cmd<M> {
@@ -221,6 +284,9 @@ impl<M: Message> Cmd<M> {
     /// 
     /// Note: If the function returns `None`, consider using `Cmd::none()` instead
     /// for better performance and clearer intent.
+    ///
+    /// The program runs the function on a blocking thread, so it may do
+    /// blocking I/O without holding up timers or other commands.
     /// 
     /// # Example
     /// ```
@@ -263,6 +329,58 @@ impl<M: Message> Cmd<M> {
         }
     }
 
+    /// Name this command in the errors it returns
+    ///
+    /// An error from a fallible command inside it reaches the error handler,
+    /// and a fallible model's `handle_error`, as
+    /// [`Error::CommandFailed`](crate::Error::CommandFailed) carrying `label`.
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::Cmd;
+    /// # enum Msg { ConfigLoaded(String) }
+    /// let cmd: Cmd<Msg> = Cmd::fallible(|| {
+    ///     let data = std::fs::read_to_string("/nonexistent/config.json")?;
+    ///     Ok(Some(Msg::ConfigLoaded(data)))
+    /// })
+    /// .labeled("load config");
+    ///
+    /// let error = cmd.test_execute().err().unwrap();
+    /// assert_eq!(error.command_label(), Some("load config"));
+    /// ```
+    pub fn labeled(self, label: impl Into<String>) -> Self {
+        self.label_with(label.into())
+    }
+
+    fn label_with(self, label: String) -> Self {
+        let inner = match self.inner {
+            CmdInner::Fallible(func) => CmdInner::Fallible(Box::new(move || {
+                func().map_err(|source| crate::Error::CommandFailed {
+                    label,
+                    source: Box::new(source),
+                })
+            })),
+            CmdInner::Batch(cmds) => CmdInner::Batch(
+                cmds.into_iter()
+                    .map(|cmd| cmd.label_with(label.clone()))
+                    .collect(),
+            ),
+            CmdInner::Sequence(cmds) => CmdInner::Sequence(
+                cmds.into_iter()
+                    .map(|cmd| cmd.label_with(label.clone()))
+                    .collect(),
+            ),
+            CmdInner::Keyed { key, policy, cmd } => CmdInner::Keyed {
+                key,
+                policy,
+                cmd: Box::new(cmd.label_with(label)),
+            },
+            // Nothing else can fail
+            inner => inner,
+        };
+        Cmd { inner }
+    }
+
     /// Returns a no-op command that continues running without doing anything
     ///
     /// This is the idiomatic way to return "no command" from update().
@@ -293,6 +411,25 @@ impl<M: Message> Cmd<M> {
         }
     }
 
+    /// Create a command that sends `msg` right away
+    ///
+    /// Unlike `Cmd::new(move || Some(msg))`, the message doesn't wait for a
+    /// blocking thread, so it can't be overtaken by commands returned after it.
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::Cmd;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Msg { Selected(usize) }
+    /// let cmd: Cmd<Msg> = Cmd::message(Msg::Selected(3));
+    /// # assert_eq!(cmd.test_execute().unwrap(), Some(Msg::Selected(3)));
+    /// ```
+    pub fn message(msg: M) -> Self {
+        Cmd {
+            inner: CmdInner::Message(msg),
+        }
+    }
+
     /// Internal method
     #[doc(hidden)]
     pub fn exec_process<F>(program: String, args: Vec<String>, callback: F) -> Self
@@ -335,6 +472,33 @@ impl<M: Message> Cmd<M> {
         }
     }
 
+    /// Create a suspend command
+    /// Internal method
+    #[doc(hidden)]
+    pub fn suspend() -> Self {
+        Cmd {
+            inner: CmdInner::Suspend,
+        }
+    }
+
+    /// Create a command that vetoes a pending suspend
+    /// Internal method
+    #[doc(hidden)]
+    pub fn cancel_suspend() -> Self {
+        Cmd {
+            inner: CmdInner::CancelSuspend,
+        }
+    }
+
+    /// Create a terminal control command
+    /// Internal method
+    #[doc(hidden)]
+    pub fn terminal_control(msg: TerminalControlMsg) -> Self {
+        Cmd {
+            inner: CmdInner::TerminalControl(msg),
+        }
+    }
+
     /// Create a tick command
     /// Internal method
     #[doc(hidden)]
@@ -353,18 +517,42 @@ impl<M: Message> Cmd<M> {
     /// Create an every command
     /// Internal method
     #[doc(hidden)]
-    pub fn every<F>(duration: std::time::Duration, callback: F) -> Self
+    pub fn every<F>(duration: std::time::Duration, mut callback: F) -> Self
+    where
+        F: FnMut(std::time::Instant) -> M + Send + 'static,
+    {
+        Self::every_with(duration, false, move |instant| Some(callback(instant)))
+    }
+
+    /// Create a recurring timer command that stops when `callback` returns
+    /// `None`, optionally aligned to wall-clock multiples of `duration`
+    /// Internal method
+    #[doc(hidden)]
+    pub fn every_with<F>(duration: std::time::Duration, wall_clock: bool, callback: F) -> Self
     where
-        F: FnOnce(std::time::Instant) -> M + Send + 'static,
+        F: FnMut(std::time::Instant) -> Option<M> + Send + 'static,
     {
         Cmd {
             inner: CmdInner::Every {
                 duration,
+                wall_clock,
                 callback: Box::new(callback),
             },
         }
     }
 
+    /// Create a command that sends every message of `stream`
+    /// Internal method
+    #[doc(hidden)]
+    pub fn stream<S>(stream: S) -> Self
+    where
+        S: futures::Stream<Item = M> + Send + 'static,
+    {
+        Cmd {
+            inner: CmdInner::Stream(stream.boxed()),
+        }
+    }
+
     /// Create an async command
     /// Internal method
     #[doc(hidden)]
@@ -377,6 +565,49 @@ impl<M: Message> Cmd<M> {
         }
     }
 
+    /// Run this command under `key`, cancelling any command still running under it
+    ///
+    /// Only the latest command for a key delivers its message, so a stale
+    /// response can't overwrite a fresh one. Cancel it with
+    /// [`commands::cancel`](crate::commands::cancel).
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::{Cmd, commands};
+    /// # enum Msg { Results(Vec<String>) }
+    /// # async fn search(query: &str) -> Vec<String> { vec![query.to_string()] }
+    /// let query = String::from("hoj");
+    /// // Every keystroke starts a new search; the previous one is cancelled
+    /// let cmd: Cmd<Msg> = commands::spawn(async move {
+    ///     Some(Msg::Results(search(&query).await))
+    /// })
+    /// .keyed("search");
+    /// ```
+    pub fn keyed(self, key: impl Into<String>) -> Self {
+        self.keyed_with(key, KeyPolicy::Supersede)
+    }
+
+    /// Run this command under `key`, with `policy` deciding what happens when
+    /// the key is still running
+    pub fn keyed_with(self, key: impl Into<String>, policy: KeyPolicy) -> Self {
+        Cmd {
+            inner: CmdInner::Keyed {
+                key: key.into(),
+                policy,
+                cmd: Box::new(self),
+            },
+        }
+    }
+
+    /// Create a command that cancels the keyed command running under `key`
+    /// Internal method
+    #[doc(hidden)]
+    pub fn cancel(key: impl Into<String>) -> Self {
+        Cmd {
+            inner: CmdInner::Cancel(key.into()),
+        }
+    }
+
     /// Execute the command and return its message
     /// Internal method
     #[doc(hidden)]
@@ -389,8 +620,8 @@ impl<M: Message> Cmd<M> {
                 args,
                 callback,
             } => {
-                // In the real implementation, this would be handled by the runtime
-                // For now, we'll just run it directly
+                // The runtime intercepts exec commands so it can hand the terminal
+                // over to the child; this path only runs outside of a Program
                 use std::process::Command;
                 let output = Command::new(&program).args(&args).status();
                 let exit_code = output.ok().and_then(|status| status.code());
@@ -400,18 +631,35 @@ impl<M: Message> Cmd<M> {
                 // NoOp commands don't produce messages, just continue running
                 Ok(None)
             }
+            CmdInner::Message(msg) => Ok(Some(msg)),
             CmdInner::Quit => {
                 // Quit commands don't produce messages, they're handled specially
                 Ok(None)
             }
+            CmdInner::Suspend | CmdInner::CancelSuspend => {
+                // Suspending is handled by the Program loop
+                Ok(None)
+            }
+            CmdInner::TerminalControl(_) => {
+                // Applied by the Program loop, which owns the terminal
+                Ok(None)
+            }
             CmdInner::Batch(_) | CmdInner::Sequence(_) => {
                 // These are handled specially by the CommandExecutor
                 Ok(None)
             }
-            CmdInner::Tick { .. } | CmdInner::Every { .. } | CmdInner::Async(_) => {
+            CmdInner::Tick { .. }
+            | CmdInner::Every { .. }
+            | CmdInner::Async(_)
+            | CmdInner::Stream(_) => {
                 // These are handled specially by the CommandExecutor with async runtime
                 Ok(None)
             }
+            CmdInner::Keyed { cmd, .. } => {
+                // Keys only matter to the CommandExecutor, which tracks running commands
+                cmd.execute()
+            }
+            CmdInner::Cancel(_) => Ok(None),
         }
     }
 
@@ -427,6 +675,11 @@ impl<M: Message> Cmd<M> {
         matches!(self.inner, CmdInner::ExecProcess { .. })
     }
 
+    /// Check if this is a synchronous command that runs on a blocking thread
+    pub fn is_blocking(&self) -> bool {
+        matches!(self.inner, CmdInner::Function(_) | CmdInner::Fallible(_))
+    }
+
     /// Check if this is a no-op command
     pub fn is_noop(&self) -> bool {
         matches!(self.inner, CmdInner::NoOp)
@@ -437,6 +690,43 @@ impl<M: Message> Cmd<M> {
         matches!(self.inner, CmdInner::Quit)
     }
 
+    /// Check if this is a suspend command
+    pub fn is_suspend(&self) -> bool {
+        matches!(self.inner, CmdInner::Suspend)
+    }
+
+    /// Check if this command vetoes a pending suspend
+    pub fn is_cancel_suspend(&self) -> bool {
+        matches!(self.inner, CmdInner::CancelSuspend)
+    }
+
+    /// Check if this command, or one in its batch or sequence, vetoes a
+    /// pending suspend
+    pub fn cancels_suspend(&self) -> bool {
+        match &self.inner {
+            CmdInner::CancelSuspend => true,
+            CmdInner::Batch(cmds) | CmdInner::Sequence(cmds) => {
+                cmds.iter().any(Cmd::cancels_suspend)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if this is a terminal control command
+    pub fn is_terminal_control(&self) -> bool {
+        matches!(self.inner, CmdInner::TerminalControl(_))
+    }
+
+    /// Take the terminal control message (consumes the command)
+    /// Internal method
+    #[doc(hidden)]
+    pub fn take_terminal_control(self) -> Option<TerminalControlMsg> {
+        match self.inner {
+            CmdInner::TerminalControl(msg) => Some(msg),
+            _ => None,
+        }
+    }
+
     /// Extract exec process details if this is an exec process command
     #[allow(clippy::type_complexity)]
     /// Internal method
@@ -507,17 +797,30 @@ impl<M: Message> Cmd<M> {
         }
     }
 
-    #[allow(clippy::type_complexity)]
     /// Internal method
     #[doc(hidden)]
-    pub fn take_every(
-        self,
-    ) -> Option<(
-        std::time::Duration,
-        Box<dyn FnOnce(std::time::Instant) -> M + Send>,
-    )> {
+    pub fn take_every(self) -> Option<EveryDetails<M>> {
         match self.inner {
-            CmdInner::Every { duration, callback } => Some((duration, callback)),
+            CmdInner::Every {
+                duration,
+                wall_clock,
+                callback,
+            } => Some((duration, wall_clock, callback)),
+            _ => None,
+        }
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn is_message(&self) -> bool {
+        matches!(self.inner, CmdInner::Message(_))
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn take_message(self) -> Option<M> {
+        match self.inner {
+            CmdInner::Message(msg) => Some(msg),
             _ => None,
         }
     }
@@ -537,6 +840,146 @@ impl<M: Message> Cmd<M> {
         }
     }
 
+    /// Internal method
+    #[doc(hidden)]
+    pub fn is_stream(&self) -> bool {
+        matches!(self.inner, CmdInner::Stream(_))
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn take_stream(self) -> Option<BoxStream<'static, M>> {
+        match self.inner {
+            CmdInner::Stream(stream) => Some(stream),
+            _ => None,
+        }
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn is_keyed(&self) -> bool {
+        matches!(self.inner, CmdInner::Keyed { .. })
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn take_keyed(self) -> Option<KeyedDetails<M>> {
+        match self.inner {
+            CmdInner::Keyed { key, policy, cmd } => Some((key, policy, *cmd)),
+            _ => None,
+        }
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn is_cancel(&self) -> bool {
+        matches!(self.inner, CmdInner::Cancel(_))
+    }
+
+    /// Internal method
+    #[doc(hidden)]
+    pub fn take_cancel(self) -> Option<String> {
+        match self.inner {
+            CmdInner::Cancel(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Get the key of a keyed command
+    pub fn key(&self) -> Option<&str> {
+        match &self.inner {
+            CmdInner::Keyed { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Convert the messages this command produces with `f`
+    ///
+    /// This lets a parent model run a child's commands: the child's messages
+    /// are wrapped in the parent's message type. Every kind of command is
+    /// converted, including batches, sequences, timers, async and exec
+    /// commands.
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::{Cmd, commands};
+    /// # use std::time::Duration;
+    /// #[derive(Debug, PartialEq)]
+    /// enum CounterMsg { Increment }
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum AppMsg { Counter(CounterMsg) }
+    ///
+    /// let child: Cmd<CounterMsg> = Cmd::new(|| Some(CounterMsg::Increment));
+    /// let cmd: Cmd<AppMsg> = child.map(AppMsg::Counter);
+    /// # assert_eq!(cmd.test_execute().unwrap(), Some(AppMsg::Counter(CounterMsg::Increment)));
+    /// ```
+    pub fn map<N, F>(self, f: F) -> Cmd<N>
+    where
+        N: Message,
+        F: Fn(M) -> N + Send + Sync + 'static,
+    {
+        self.map_with(std::sync::Arc::new(f))
+    }
+
+    fn map_with<N: Message>(self, f: MapFn<M, N>) -> Cmd<N> {
+        let inner = match self.inner {
+            CmdInner::NoOp => CmdInner::NoOp,
+            CmdInner::Message(msg) => CmdInner::Message(f(msg)),
+            CmdInner::Quit => CmdInner::Quit,
+            CmdInner::Suspend => CmdInner::Suspend,
+            CmdInner::CancelSuspend => CmdInner::CancelSuspend,
+            CmdInner::TerminalControl(msg) => CmdInner::TerminalControl(msg),
+            CmdInner::Cancel(key) => CmdInner::Cancel(key),
+            CmdInner::Function(func) => CmdInner::Function(Box::new(move || func().map(&*f))),
+            CmdInner::Fallible(func) => {
+                CmdInner::Fallible(Box::new(move || func().map(|msg| msg.map(&*f))))
+            }
+            CmdInner::ExecProcess {
+                program,
+                args,
+                callback,
+            } => CmdInner::ExecProcess {
+                program,
+                args,
+                callback: Box::new(move |exit_code| f(callback(exit_code))),
+            },
+            CmdInner::Batch(cmds) => CmdInner::Batch(
+                cmds.into_iter()
+                    .map(|cmd| cmd.map_with(f.clone()))
+                    .collect(),
+            ),
+            CmdInner::Sequence(cmds) => CmdInner::Sequence(
+                cmds.into_iter()
+                    .map(|cmd| cmd.map_with(f.clone()))
+                    .collect(),
+            ),
+            CmdInner::Tick { duration, callback } => CmdInner::Tick {
+                duration,
+                callback: Box::new(move || f(callback())),
+            },
+            CmdInner::Every {
+                duration,
+                wall_clock,
+                mut callback,
+            } => CmdInner::Every {
+                duration,
+                wall_clock,
+                callback: Box::new(move |instant| callback(instant).map(&*f)),
+            },
+            CmdInner::Async(future) => CmdInner::Async(Box::new(async move {
+                Box::into_pin(future).await.map(&*f)
+            })),
+            CmdInner::Stream(stream) => CmdInner::Stream(stream.map(move |msg| f(msg)).boxed()),
+            CmdInner::Keyed { key, policy, cmd } => CmdInner::Keyed {
+                key,
+                policy,
+                cmd: Box::new(cmd.map_with(f)),
+            },
+        };
+        Cmd { inner }
+    }
+
     /// Inspect this command for debugging
     ///
     /// This allows you to observe command execution without modifying behavior.
@@ -600,12 +1043,19 @@ impl<M: Message> Cmd<M> {
             CmdInner::Fallible(_) => "Fallible",
             CmdInner::ExecProcess { .. } => "ExecProcess",
             CmdInner::NoOp => "NoOp",
+            CmdInner::Message(_) => "Message",
             CmdInner::Quit => "Quit",
+            CmdInner::Suspend => "Suspend",
+            CmdInner::CancelSuspend => "CancelSuspend",
+            CmdInner::TerminalControl(_) => "TerminalControl",
             CmdInner::Batch(_) => "Batch",
             CmdInner::Sequence(_) => "Sequence",
             CmdInner::Tick { .. } => "Tick",
             CmdInner::Every { .. } => "Every",
             CmdInner::Async(_) => "Async",
+            CmdInner::Stream(_) => "Stream",
+            CmdInner::Keyed { .. } => "Keyed",
+            CmdInner::Cancel(_) => "Cancel",
         }
     }
 }
@@ -713,6 +1163,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_cmd_labeled_reaches_batched_commands() {
+        let failing = || Cmd::fallible(|| Err(crate::Error::Model("boom".to_string())));
+        let cmd: Cmd<Msg> = Cmd::batch(vec![failing(), Cmd::new(|| Some(Msg::Increment))])
+            .labeled("save");
+
+        let mut cmds = cmd.take_batch().unwrap().into_iter();
+        let error = cmds.next().unwrap().test_execute().unwrap_err();
+        assert_eq!(error.command_label(), Some("save"));
+        assert_eq!(error.to_string(), "save failed: Model error: boom");
+        assert_eq!(cmds.next().unwrap().test_execute().unwrap(), Some(Msg::Increment));
+    }
+
     #[test]
     fn test_cmd_exec_process() {
         let cmd = Cmd::exec_process("echo".to_string(), vec!["test".to_string()], |_| {
@@ -727,6 +1190,66 @@ mod tests {
         assert_eq!(args, vec!["test"]);
     }
 
+    #[test]
+    fn test_cmd_keyed() {
+        let cmd = Cmd::new(|| Some(Msg::Increment)).keyed_with("counter", KeyPolicy::Queue);
+        assert!(cmd.is_keyed());
+        assert_eq!(cmd.key(), Some("counter"));
+
+        let (key, policy, inner) = cmd.take_keyed().unwrap();
+        assert_eq!(key, "counter");
+        assert_eq!(policy, KeyPolicy::Queue);
+        assert_eq!(inner.test_execute().unwrap(), Some(Msg::Increment));
+
+        let cancel: Cmd<Msg> = Cmd::cancel("counter");
+        assert_eq!(cancel.take_cancel().as_deref(), Some("counter"));
+    }
+
+    #[test]
+    fn test_cmd_map() {
+        #[derive(Debug, PartialEq)]
+        enum Parent {
+            Child(Msg),
+        }
+
+        let cmd = Cmd::new(|| Some(Msg::Increment)).map(Parent::Child);
+        assert_eq!(cmd.test_execute().unwrap(), Some(Parent::Child(Msg::Increment)));
+
+        let cmd = Cmd::exec_process("true".to_string(), vec![], |_| Msg::Decrement)
+            .map(Parent::Child);
+        let (_, _, callback) = cmd.take_exec_process().unwrap();
+        assert_eq!(callback(Some(0)), Parent::Child(Msg::Decrement));
+
+        let tick = Cmd::tick(std::time::Duration::ZERO, || Msg::SetValue(1));
+        let batch = Cmd::batch(vec![tick, Cmd::new(|| Some(Msg::Noop))]).map(Parent::Child);
+        let mut cmds = batch.take_batch().unwrap();
+        assert_eq!(cmds.len(), 2);
+        let (_, callback) = cmds.remove(0).take_tick().unwrap();
+        assert_eq!(callback(), Parent::Child(Msg::SetValue(1)));
+
+        let mut count = 0;
+        let every = Cmd::every(std::time::Duration::from_secs(1), move |_| {
+            count += 1;
+            Msg::SetValue(count)
+        })
+        .map(Parent::Child);
+        let (_, _, mut callback) = every.take_every().unwrap();
+        callback(std::time::Instant::now());
+        let msg = callback(std::time::Instant::now());
+        assert_eq!(msg, Some(Parent::Child(Msg::SetValue(2))));
+
+        let keyed = Cmd::async_cmd(async { Some(Msg::Increment) })
+            .keyed("load")
+            .map(Parent::Child);
+        let (key, _, inner) = keyed.take_keyed().unwrap();
+        assert_eq!(key, "load");
+        let future = Box::into_pin(inner.take_async().unwrap());
+        let msg = futures::executor::block_on(future);
+        assert_eq!(msg, Some(Parent::Child(Msg::Increment)));
+
+        assert!(Cmd::<Msg>::quit().map(Parent::Child).is_quit());
+    }
+
     #[test]
     fn test_cmd_debug() {
         let cmd = Cmd::new(|| Some(Msg::Increment));
@@ -4,12 +4,17 @@
 
 use std::fmt;
 use std::io;
+use std::sync::{Arc, Mutex};
 
 /// Result type alias for hojicha operations
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Main error type for the hojicha framework
+///
+/// New kinds of errors are added from time to time, so matches need a
+/// wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// I/O error (terminal operations, file access, etc.)
     Io(io::Error),
@@ -34,6 +39,24 @@ pub enum Error {
 
     /// Custom error for user-defined errors
     Custom(Box<dyn std::error::Error + Send + Sync>),
+
+    /// A labeled command failed (see [`Cmd::labeled`](crate::core::Cmd::labeled))
+    CommandFailed {
+        /// The label of the command that failed
+        label: String,
+        /// The error the command returned
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// The label of the command this error came from, if it was labeled
+    pub fn command_label(&self) -> Option<&str> {
+        match self {
+            Error::CommandFailed { label, .. } => Some(label),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -47,6 +70,7 @@ impl fmt::Display for Error {
             Error::Model(msg) => write!(f, "Model error: {msg}"),
             Error::Config(msg) => write!(f, "Configuration error: {msg}"),
             Error::Custom(err) => write!(f, "Custom error: {err}"),
+            Error::CommandFailed { label, source } => write!(f, "{label} failed: {source}"),
         }
     }
 }
@@ -56,6 +80,7 @@ impl std::error::Error for Error {
         match self {
             Error::Io(err) => Some(err),
             Error::Custom(err) => Some(err.as_ref()),
+            Error::CommandFailed { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -79,6 +104,31 @@ impl<T> From<std::sync::mpsc::SendError<T>> for Error {
     }
 }
 
+/// An error on its way from a command back to the model
+///
+/// Errors aren't `Clone`, but events are, so this carries the error in a
+/// shared slot that the program takes it out of once.
+#[derive(Debug, Clone)]
+pub struct SharedError(Arc<Mutex<Option<Error>>>);
+
+impl SharedError {
+    /// Wrap an error so it can be sent as an event
+    pub fn new(error: Error) -> Self {
+        Self(Arc::new(Mutex::new(Some(error))))
+    }
+
+    /// Take the error out, leaving `None` for any clones
+    pub fn take(&self) -> Option<Error> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+impl PartialEq for SharedError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 /// Error context trait for adding context to errors
 pub trait ErrorContext<T> {
     /// Add context to an error
@@ -241,6 +291,28 @@ mod tests {
         assert_eq!(err.to_string(), "I/O error: File not found");
     }
 
+    #[test]
+    fn test_command_failed() {
+        let err = Error::CommandFailed {
+            label: "load config".to_string(),
+            source: Box::new(Error::Io(io::Error::other("missing"))),
+        };
+        assert_eq!(err.command_label(), Some("load config"));
+        assert_eq!(err.to_string(), "load config failed: I/O error: missing");
+        assert!(StdError::source(&err).is_some());
+        assert_eq!(Error::Model("oops".to_string()).command_label(), None);
+    }
+
+    #[test]
+    fn test_shared_error_is_taken_once() {
+        let shared = SharedError::new(Error::Model("oops".to_string()));
+        let clone = shared.clone();
+        assert_eq!(shared, clone);
+
+        assert!(clone.take().is_some());
+        assert!(shared.take().is_none());
+    }
+
     #[test]
     fn test_error_context() {
         let result: Result<()> = Err(Error::Terminal("Base error".to_string()));
@@ -40,6 +40,7 @@
 //! - `Event::Resize` - Terminal was resized
 //! - `Event::Focus` / `Event::Blur` - Terminal gained/lost focus  
 //! - `Event::Suspend` / `Event::Resume` - App was suspended/resumed
+//! - `Event::Interrupt` - The process received SIGINT
 //! - `Event::Tick` - Periodic timer tick
 //! - `Event::Quit` - Application should exit
 
@@ -47,7 +48,10 @@ use crossterm::event::KeyCode;
 pub use crossterm::event::{KeyModifiers, MouseButton, MouseEventKind};
 
 /// An event that can be received by the program
+///
+/// New events are added from time to time, so matches need a wildcard arm.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Event<M> {
     /// A keyboard event
     Key(KeyEvent),
@@ -74,11 +78,22 @@ pub enum Event<M> {
     Suspend,
     /// Program resumed from suspend
     Resume,
+    /// The process received an interrupt signal (SIGINT)
+    ///
+    /// In raw mode Ctrl+C arrives as a key event instead, so this is mostly seen
+    /// in headless programs or when the signal comes from another process.
+    Interrupt,
     /// Bracketed paste event
     Paste(String),
     /// Internal event to trigger external process execution
     #[doc(hidden)]
     ExecProcess,
+    /// Internal event carrying a terminal control command to the program loop
+    #[doc(hidden)]
+    TerminalControl(crate::commands::TerminalControlMsg),
+    /// Internal event carrying a command error to a fallible model
+    #[doc(hidden)]
+    Error(crate::error::SharedError),
 }
 
 impl<M> Event<M> {
@@ -243,6 +258,72 @@ impl<M> Event<M> {
     pub fn is_resume(&self) -> bool {
         matches!(self, Event::Resume)
     }
+
+    /// Check if this is an interrupt event
+    pub fn is_interrupt(&self) -> bool {
+        matches!(self, Event::Interrupt)
+    }
+
+    /// Convert the user message with `f`, keeping every other event as is
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::Event;
+    /// enum Parent { Counter(u32) }
+    /// let event: Event<Parent> = Event::User(5).map(Parent::Counter);
+    /// assert!(matches!(event, Event::User(Parent::Counter(5))));
+    /// ```
+    pub fn map<N, F>(self, f: F) -> Event<N>
+    where
+        F: FnOnce(M) -> N,
+    {
+        match self.try_map(|msg| Some(f(msg))) {
+            Some(event) => event,
+            None => unreachable!("the message is always mapped"),
+        }
+    }
+
+    /// Convert the user message with `f`, or `None` if `f` doesn't accept it
+    ///
+    /// Every other event is kept as is. A parent uses this to forward its
+    /// events to a child model: terminal events go to the child, and only the
+    /// user messages that belong to the child get through.
+    ///
+    /// # Example
+    /// ```
+    /// # use hojicha_core::Event;
+    /// enum Parent { Counter(u32), Quit }
+    /// let child = |msg| match msg {
+    ///     Parent::Counter(n) => Some(n),
+    ///     _ => None,
+    /// };
+    ///
+    /// assert_eq!(Event::User(Parent::Counter(5)).try_map(child), Some(Event::User(5)));
+    /// assert_eq!(Event::User(Parent::Quit).try_map(child), None);
+    /// assert_eq!(Event::<Parent>::Tick.try_map(child), Some(Event::Tick));
+    /// ```
+    pub fn try_map<N, F>(self, f: F) -> Option<Event<N>>
+    where
+        F: FnOnce(M) -> Option<N>,
+    {
+        Some(match self {
+            Event::User(msg) => Event::User(f(msg)?),
+            Event::Key(key) => Event::Key(key),
+            Event::Mouse(mouse) => Event::Mouse(mouse),
+            Event::Resize { width, height } => Event::Resize { width, height },
+            Event::Tick => Event::Tick,
+            Event::Quit => Event::Quit,
+            Event::Focus => Event::Focus,
+            Event::Blur => Event::Blur,
+            Event::Suspend => Event::Suspend,
+            Event::Resume => Event::Resume,
+            Event::Interrupt => Event::Interrupt,
+            Event::Paste(text) => Event::Paste(text),
+            Event::ExecProcess => Event::ExecProcess,
+            Event::TerminalControl(msg) => Event::TerminalControl(msg),
+            Event::Error(error) => Event::Error(error),
+        })
+    }
 }
 
 /// Window size information
@@ -796,6 +877,7 @@ mod tests {
         let blur_event = Event::<String>::Blur;
         let suspend_event = Event::<String>::Suspend;
         let resume_event = Event::<String>::Resume;
+        let interrupt_event = Event::<String>::Interrupt;
         let paste_event = Event::<String>::Paste("pasted text".to_string());
 
         // Test using new helper methods
@@ -818,11 +900,32 @@ mod tests {
         assert!(blur_event.is_blur());
         assert!(suspend_event.is_suspend());
         assert!(resume_event.is_resume());
+        assert!(interrupt_event.is_interrupt());
+        assert!(!resume_event.is_interrupt());
         
         assert!(paste_event.is_paste());
         assert_eq!(paste_event.as_paste(), Some("pasted text"));
     }
 
+    #[test]
+    fn test_event_map() {
+        let user_event = Event::User("test".to_string());
+        assert_eq!(user_event.map(|msg| msg.len()), Event::User(4));
+
+        let paste_event = Event::<String>::Paste("pasted".to_string());
+        assert_eq!(paste_event.map(|msg| msg.len()), Event::Paste("pasted".to_string()));
+
+        let resize_event = Event::<String>::Resize { width: 80, height: 24 };
+        assert_eq!(
+            resize_event.try_map(|_| None::<usize>),
+            Some(Event::Resize { width: 80, height: 24 })
+        );
+
+        let parse = |msg: String| msg.parse::<u32>().ok();
+        assert_eq!(Event::User("42".to_string()).try_map(parse), Some(Event::User(42)));
+        assert_eq!(Event::User("nope".to_string()).try_map(parse), None);
+    }
+
     #[test]
     fn test_key_variants() {
         let keys = vec![
@@ -3,6 +3,10 @@
 //! This module provides the `FallibleModel` trait which extends the basic `Model`
 //! trait with error handling capabilities. This allows models to handle errors
 //! gracefully without panicking or silently ignoring failures.
+//!
+//! `Program::with_fallible_model` runs a `FallibleModel` directly: events go to
+//! `try_update`, and errors from it and from fallible commands go to
+//! `handle_error`. Use [`Cmd::labeled`] to tell which command an error came from.
 
 use crate::{
     core::{Cmd, Model},
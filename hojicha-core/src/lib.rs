@@ -20,6 +20,7 @@
 //! - [`Model`]: The main trait your application must implement
 //! - [`Message`]: Marker trait for your application's message types
 //! - [`Cmd`]: Commands for side effects and async operations
+//! - [`Component`]: Reusable sub-models that parents embed in their own model
 //!
 //! ## Example
 //!
@@ -60,20 +61,24 @@
 // Core TEA abstractions
 pub mod async_helpers;
 pub mod commands;
+pub mod component;
 pub mod core;
 pub mod debug;
 pub mod error;
 pub mod event;
 pub mod fallible;
 pub mod logging;
+pub mod subscriptions;
 
 // Testing utilities (only in tests)
 #[cfg(test)]
 pub mod testing;
 
 // Re-export core types
-pub use core::{Cmd, Message, Model};
+pub use component::{Component, SizeHint};
+pub use core::{Cmd, KeyPolicy, Message, Model};
 pub use error::{Error, ErrorContext, ErrorHandler, Result};
+pub use subscriptions::{Sub, Subs};
 pub use event::{Event, Key, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind, MouseButton, WindowSize};
 
 // Re-export command constructors
@@ -96,6 +101,7 @@ pub use commands::{
 /// - [`Model`] - The main trait your application implements
 /// - [`Message`] - Marker trait for messages
 /// - [`Cmd`] - Commands for side effects
+/// - [`Component`] - Reusable, stateful sub-models
 /// 
 /// ### Events
 /// - [`Event`] - All event types (Key, Mouse, User, etc.)
@@ -118,7 +124,9 @@ pub use commands::{
 /// - All of ratatui's prelude for building views
 pub mod prelude {
     // Core traits and types
+    pub use crate::component::{Component, SizeHint};
     pub use crate::core::{Cmd, Message, Model};
+    pub use crate::subscriptions::{Sub, Subs};
     
     // Events
     pub use crate::event::{Event, Key, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind, MouseButton, WindowSize};
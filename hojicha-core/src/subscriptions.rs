@@ -0,0 +1,279 @@
+//! Declarative subscriptions
+//!
+//! A model lists the event sources it wants to hear from in
+//! [`Model::subscriptions`](crate::core::Model::subscriptions). The runtime
+//! asks again after every update and compares the keys with the ones already
+//! running: new keys start their stream, missing keys are cancelled, and keys
+//! that stay keep their stream untouched.
+//!
+//! # Example
+//!
+//! ```
+//! # use hojicha_core::{Cmd, Event, Model, Sub, Subs};
+//! # use std::time::Duration;
+//! struct Loader {
+//!     loading: bool,
+//!     frame: usize,
+//! }
+//!
+//! enum Msg {
+//!     Spin,
+//! }
+//!
+//! impl Model for Loader {
+//!     type Message = Msg;
+//!
+//!     fn update(&mut self, event: Event<Msg>) -> Cmd<Msg> {
+//!         if let Event::User(Msg::Spin) = event {
+//!             self.frame += 1;
+//!         }
+//!         Cmd::none()
+//!     }
+//!
+//!     // The spinner only ticks while something is loading
+//!     fn subscriptions(&self) -> Subs<Msg> {
+//!         if self.loading {
+//!             Sub::every("spinner", Duration::from_millis(80), |_| Msg::Spin).into()
+//!         } else {
+//!             Subs::none()
+//!         }
+//!     }
+//!
+//!     fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+//! }
+//! ```
+
+use crate::core::Message;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use std::time::{Duration, Instant};
+
+/// Builds a subscription's stream when it starts
+type StreamFactory<M> = Box<dyn FnOnce() -> BoxStream<'static, M> + Send>;
+
+/// A single subscription: a keyed stream of messages
+///
+/// The stream isn't created until the runtime starts the subscription, so
+/// building a `Sub` in `subscriptions` is cheap.
+pub struct Sub<M: Message> {
+    key: String,
+    start: StreamFactory<M>,
+}
+
+impl<M: Message> Sub<M> {
+    /// Subscribe to a stream created by `start`
+    ///
+    /// `start` is called on the async runtime once the key first appears.
+    pub fn stream<S, F>(key: impl Into<String>, start: F) -> Self
+    where
+        S: Stream<Item = M> + Send + 'static,
+        F: FnOnce() -> S + Send + 'static,
+    {
+        Self {
+            key: key.into(),
+            start: Box::new(move || start().boxed()),
+        }
+    }
+
+    /// Send a message every `duration` while subscribed
+    ///
+    /// The first message arrives one interval after the subscription starts.
+    /// Missed ticks are skipped rather than delivered in a burst.
+    pub fn every<F>(key: impl Into<String>, duration: Duration, f: F) -> Self
+    where
+        F: FnMut(Instant) -> M + Send + 'static,
+    {
+        Self::stream(key, move || {
+            if duration.is_zero() {
+                log::error!("Every subscription needs a non-zero interval");
+                return stream::empty().boxed();
+            }
+
+            let start = tokio::time::Instant::now() + duration;
+            let mut interval = tokio::time::interval_at(start, duration);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            stream::unfold(interval, |mut interval| async move {
+                let at = interval.tick().await;
+                Some((at.into_std(), interval))
+            })
+            .map(f)
+            .boxed()
+        })
+    }
+
+    /// Convert the messages of this subscription with `f`
+    ///
+    /// The key stays the same, so a parent that embeds several children
+    /// should make their keys unique.
+    pub fn map<N, F>(self, f: F) -> Sub<N>
+    where
+        N: Message,
+        F: FnMut(M) -> N + Send + 'static,
+    {
+        let start = self.start;
+        Sub {
+            key: self.key,
+            start: Box::new(move || start().map(f).boxed()),
+        }
+    }
+
+    /// Get the key identifying this subscription
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Turn the subscription into its stream
+    ///
+    /// The stream is created lazily on first poll, so this can be called
+    /// outside of the async runtime.
+    pub fn into_stream(self) -> BoxStream<'static, M> {
+        let start = self.start;
+        stream::once(async move { start() }).flatten().boxed()
+    }
+}
+
+impl<M: Message> std::fmt::Debug for Sub<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sub").field("key", &self.key).finish()
+    }
+}
+
+/// The set of subscriptions a model wants active
+pub struct Subs<M: Message> {
+    subs: Vec<Sub<M>>,
+}
+
+impl<M: Message> Subs<M> {
+    /// No subscriptions
+    pub fn none() -> Self {
+        Self { subs: Vec::new() }
+    }
+
+    /// Combine several subscriptions
+    pub fn batch(subs: impl IntoIterator<Item = Sub<M>>) -> Self {
+        Self {
+            subs: subs.into_iter().collect(),
+        }
+    }
+
+    /// Add a subscription to the set
+    pub fn with(mut self, sub: Sub<M>) -> Self {
+        self.subs.push(sub);
+        self
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.subs.is_empty()
+    }
+
+    /// Number of subscriptions in the set
+    pub fn len(&self) -> usize {
+        self.subs.len()
+    }
+
+    /// Convert the messages of every subscription in the set with `f`
+    pub fn map<N, F>(self, f: F) -> Subs<N>
+    where
+        N: Message,
+        F: Fn(M) -> N + Clone + Send + 'static,
+    {
+        Subs {
+            subs: self
+                .subs
+                .into_iter()
+                .map(|sub| sub.map(f.clone()))
+                .collect(),
+        }
+    }
+
+    /// Iterate over the keys in the set
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.subs.iter().map(Sub::key)
+    }
+}
+
+impl<M: Message> Default for Subs<M> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<M: Message> From<Sub<M>> for Subs<M> {
+    fn from(sub: Sub<M>) -> Self {
+        Self { subs: vec![sub] }
+    }
+}
+
+impl<M: Message> FromIterator<Sub<M>> for Subs<M> {
+    fn from_iter<I: IntoIterator<Item = Sub<M>>>(iter: I) -> Self {
+        Self::batch(iter)
+    }
+}
+
+impl<M: Message> IntoIterator for Subs<M> {
+    type Item = Sub<M>;
+    type IntoIter = std::vec::IntoIter<Sub<M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.subs.into_iter()
+    }
+}
+
+impl<M: Message> std::fmt::Debug for Subs<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Item(u32),
+        Tick,
+    }
+
+    #[test]
+    fn test_subs_collect_keys() {
+        let subs = Subs::none()
+            .with(Sub::stream("numbers", || stream::iter(vec![Msg::Item(1)])))
+            .with(Sub::every("clock", Duration::from_secs(1), |_| Msg::Tick));
+
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs.keys().collect::<Vec<_>>(), vec!["numbers", "clock"]);
+        assert!(Subs::<Msg>::none().is_empty());
+    }
+
+    #[test]
+    fn test_stream_is_created_lazily() {
+        let sub = Sub::stream("numbers", || stream::iter(vec![Msg::Item(1), Msg::Item(2)]));
+        let stream = sub.into_stream();
+
+        let items = futures::executor::block_on(stream.collect::<Vec<_>>());
+        assert_eq!(items, vec![Msg::Item(1), Msg::Item(2)]);
+    }
+
+    #[test]
+    fn test_map_converts_messages() {
+        let subs = Subs::from(Sub::stream("numbers", || stream::iter(vec![1, 2]))).map(Msg::Item);
+        let sub = subs.into_iter().next().unwrap();
+        assert_eq!(sub.key(), "numbers");
+
+        let items = futures::executor::block_on(sub.into_stream().collect::<Vec<_>>());
+        assert_eq!(items, vec![Msg::Item(1), Msg::Item(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_every_ticks_after_each_interval() {
+        let start = std::time::Instant::now();
+        let mut stream =
+            Sub::every("clock", Duration::from_millis(20), |_| Msg::Tick).into_stream();
+
+        assert_eq!(stream.next().await, Some(Msg::Tick));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(stream.next().await, Some(Msg::Tick));
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
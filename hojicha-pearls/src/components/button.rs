@@ -3,6 +3,8 @@
 //! A clickable button with various styles and states.
 
 use crate::style::{BorderStyle, Color, ColorProfile, Style, Theme};
+use hojicha_core::component::{Component, SizeHint};
+use hojicha_core::core::Cmd;
 use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::Rect,
@@ -60,6 +62,10 @@ pub struct Button {
     width: Option<u16>,
     /// Callback key (Enter to activate by default)
     activation_key: Key,
+    /// Theme used when drawn as a component
+    theme: Theme,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl Button {
@@ -75,6 +81,8 @@ impl Button {
             custom_style: None,
             width: None,
             activation_key: Key::Enter,
+            theme: Theme::default(),
+            profile: ColorProfile::default(),
         }
     }
 
@@ -114,6 +122,12 @@ impl Button {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Focus the button
     pub fn focus(&mut self) {
         if !self.disabled {
@@ -232,8 +246,11 @@ impl Button {
     }
 
     /// Apply a theme to this button
-    pub fn apply_theme(&mut self, _theme: &Theme) {
-        // Theme is applied dynamically in get_style
+    ///
+    /// The theme is used by [`Component::view`]; [`render`](Self::render)
+    /// takes the theme to draw with.
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
     }
 
     /// Render the button
@@ -244,22 +261,7 @@ impl Button {
 
         let style = self.get_style(theme);
 
-        // Calculate button width
-        let button_width = self.width.unwrap_or_else(|| {
-            let padding = match self.size {
-                ButtonSize::Small => 2,
-                ButtonSize::Medium => 4,
-                ButtonSize::Large => 6,
-            };
-            self.label.len() as u16 + padding
-        });
-
-        // Calculate button height
-        let button_height = match self.size {
-            ButtonSize::Small => 1,
-            ButtonSize::Medium => 1,
-            ButtonSize::Large => 3,
-        };
+        let (button_width, button_height) = self.button_size();
 
         // Center button in area if it's smaller
         let button_area = if button_width < area.width || button_height < area.height {
@@ -308,8 +310,70 @@ impl Button {
     }
 }
 
+impl Button {
+    /// Width and height of the button itself
+    fn button_size(&self) -> (u16, u16) {
+        let width = self.width.unwrap_or_else(|| {
+            let padding = match self.size {
+                ButtonSize::Small => 2,
+                ButtonSize::Medium => 4,
+                ButtonSize::Large => 6,
+            };
+            self.label.len() as u16 + padding
+        });
+
+        let height = match self.size {
+            ButtonSize::Small => 1,
+            ButtonSize::Medium => 1,
+            ButtonSize::Large => 3,
+        };
+
+        (width, height)
+    }
+}
+
 impl Default for Button {
     fn default() -> Self {
         Self::new("Button")
     }
 }
+
+/// Messages sent by a [`Button`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonMsg {
+    /// The button was activated with its activation key or space
+    Pressed,
+}
+
+impl Component for Button {
+    type Message = ButtonMsg;
+
+    fn update(&mut self, event: Event<ButtonMsg>) -> Cmd<ButtonMsg> {
+        if self.handle_event(event.map(|_| ())) {
+            super::utils::emit(ButtonMsg::Pressed)
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.theme, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        Button::focus(self);
+    }
+
+    fn blur(&mut self) {
+        Button::blur(self);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        let (width, height) = self.button_size();
+        SizeHint::fixed(width, height)
+    }
+}
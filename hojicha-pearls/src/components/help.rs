@@ -4,6 +4,9 @@
 //! from keybindings or manually configured.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
 use ratatui::{
     layout::Rect,
     text::{Line, Span},
@@ -78,6 +81,8 @@ pub struct Help {
     key_width: Option<usize>,
     /// Title for the help section
     title: Option<String>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl Help {
@@ -96,6 +101,7 @@ impl Help {
             hide_unavailable: false,
             key_width: None,
             title: None,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -218,6 +224,12 @@ impl Help {
         self.key_width = self.entries.iter().map(|e| e.key.len()).max();
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme to this help component
     pub fn apply_theme(&mut self, theme: &Theme) {
         self.key_style = Style::new().bold().fg(theme.colors.primary.clone());
@@ -423,6 +435,19 @@ impl Default for Help {
     }
 }
 
+/// Help is only drawn, so it has no messages
+impl Component for Help {
+    type Message = ();
+
+    fn update(&mut self, _event: Event<()>) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+}
+
 /// Builder for creating help components with common shortcuts
 pub struct HelpBuilder {
     help: Help,
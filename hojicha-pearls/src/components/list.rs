@@ -2,12 +2,16 @@
 //!
 //! A list provides navigation through a collection of items with keyboard and mouse support.
 
-use hojicha_core::event::{Key, KeyEvent, MouseEvent, MouseEventKind};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Widget};
+use ratatui::Frame;
+use std::cell::Cell;
 use std::cmp::min;
 
 /// Options for customizing list behavior
@@ -51,7 +55,9 @@ pub struct List<T> {
     /// Whether the list has focus
     focused: bool,
     /// Visible height (set during render)
-    height: usize,
+    height: Cell<usize>,
+    /// Area the list was last rendered to, for mouse handling
+    area: Cell<Rect>,
     /// List options
     options: ListOptions,
     /// Optional block for borders/title
@@ -66,7 +72,8 @@ impl<T> List<T> {
             selected: 0,
             offset: 0,
             focused: false,
-            height: 10,
+            height: Cell::new(10),
+            area: Cell::new(Rect::default()),
             options: ListOptions::default(),
             block: None,
         }
@@ -187,8 +194,8 @@ impl<T> List<T> {
     fn ensure_visible(&mut self) {
         if self.selected < self.offset {
             self.offset = self.selected;
-        } else if self.selected >= self.offset + self.height {
-            self.offset = self.selected.saturating_sub(self.height - 1);
+        } else if self.selected >= self.offset + self.height.get() {
+            self.offset = self.selected.saturating_sub(self.height.get() - 1);
         }
     }
 
@@ -319,7 +326,9 @@ impl<T> List<T> {
 
 impl<T: ToString> List<T> {
     /// Render the list to a buffer
-    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        self.area.set(area);
+
         // Draw block if present
         let inner = if let Some(ref block) = self.block {
             let widget = block.clone();
@@ -330,10 +339,11 @@ impl<T: ToString> List<T> {
         };
 
         // Update height for scrolling calculations
-        self.height = inner.height as usize;
+        let height = inner.height as usize;
+        self.height.set(height);
 
         // Calculate visible range
-        let end = min(self.offset + self.height, self.items.len());
+        let end = min(self.offset + height, self.items.len());
 
         // Render visible items
         for (i, item_index) in (self.offset..end).enumerate() {
@@ -355,15 +365,13 @@ impl<T: ToString> List<T> {
         }
 
         // Draw scrollbar if needed
-        if self.items.len() > self.height {
+        if self.items.len() > height {
             let scrollbar_x = inner.x + inner.width - 1;
             let scrollbar_height = inner.height;
 
             // Calculate thumb size and position
-            let thumb_height = max(
-                1,
-                (self.height * scrollbar_height as usize) / self.items.len(),
-            ) as u16;
+            let thumb_height =
+                max(1, (height * scrollbar_height as usize) / self.items.len()) as u16;
             let thumb_pos = ((self.offset * scrollbar_height as usize) / self.items.len()) as u16;
 
             // Draw scrollbar track
@@ -385,6 +393,48 @@ impl<T: ToString> List<T> {
 
 use std::cmp::max;
 
+/// Messages sent by a [`List`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMsg {
+    /// The item at this index was selected
+    SelectionChanged(usize),
+}
+
+impl<T: ToString> Component for List<T> {
+    type Message = ListMsg;
+
+    fn update(&mut self, event: Event<ListMsg>) -> Cmd<ListMsg> {
+        let before = self.selected;
+        let handled = match event {
+            Event::Key(key) => self.handle_key(&key),
+            Event::Mouse(mouse) => self.handle_mouse(&mouse, self.area.get()),
+            _ => false,
+        };
+
+        if handled && self.selected != before {
+            super::utils::emit(ListMsg::SelectionChanged(self.selected))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(area, frame.buffer_mut());
+    }
+
+    fn focus(&mut self) {
+        self.set_focused(true);
+    }
+
+    fn blur(&mut self) {
+        self.set_focused(false);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -594,7 +644,7 @@ mod tests {
     fn test_list_offset_management() {
         let items: Vec<i32> = (1..=30).collect();
         let mut list = List::new(items);
-        list.height = 10; // Simulate viewport height
+        list.height.set(10); // Simulate viewport height
 
         // Initially offset should be 0
         assert_eq!(list.offset, 0);
@@ -609,14 +659,14 @@ mod tests {
 
         // Selected should be visible
         assert!(list.selected >= list.offset);
-        assert!(list.selected < list.offset + list.height);
+        assert!(list.selected < list.offset + list.height.get());
     }
 
     #[test]
     fn test_list_ensure_visible() {
         let items: Vec<i32> = (1..=20).collect();
         let mut list = List::new(items);
-        list.height = 5;
+        list.height.set(5);
 
         // Select item beyond viewport
         list.selected = 10;
@@ -624,7 +674,7 @@ mod tests {
 
         // Item should now be visible
         assert!(list.selected >= list.offset);
-        assert!(list.selected < list.offset + list.height);
+        assert!(list.selected < list.offset + list.height.get());
 
         // Select item before viewport
         list.offset = 10;
@@ -686,7 +736,7 @@ mod tests {
         use ratatui::buffer::Buffer;
 
         let items = vec!["Item 1", "Item 2", "Item 3"];
-        let mut list = List::new(items);
+        let list = List::new(items);
 
         let mut buf = Buffer::empty(Rect::new(0, 0, 20, 10));
         list.render(Rect::new(0, 0, 20, 10), &mut buf);
@@ -709,7 +759,7 @@ mod tests {
 
         let items = vec!["A", "B", "C", "D", "E"];
         let mut list = List::new(items);
-        list.height = 5;
+        list.height.set(5);
         list.offset = 0;
         list.focused = true; // Need to be focused to handle mouse
 
@@ -747,4 +797,39 @@ mod tests {
         list.handle_mouse(&scroll_event, Rect::new(0, 0, 20, 5));
         assert_eq!(list.selected(), 2);
     }
+
+    #[test]
+    fn test_component_reports_selection() {
+        use hojicha_core::event::{KeyModifiers, MouseButton};
+
+        let mut list = List::new(vec!["A", "B", "C", "D"]);
+        Component::focus(&mut list);
+
+        let down = Event::Key(KeyEvent::new(Key::Down, KeyModifiers::empty()));
+        let cmd = list.update(down);
+        assert_eq!(
+            cmd.test_execute().unwrap(),
+            Some(ListMsg::SelectionChanged(1))
+        );
+
+        // Already at the top, so nothing changes
+        list.select_first();
+        let up = Event::Key(KeyEvent::new(Key::Up, KeyModifiers::empty()));
+        assert!(list.update(up).is_noop());
+
+        // Clicks are mapped through the area the list was last drawn to
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 10));
+        list.render(Rect::new(0, 5, 20, 4), &mut buf);
+        let click = Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            3,
+            7,
+            KeyModifiers::empty(),
+        ));
+        let cmd = list.update(click);
+        assert_eq!(
+            cmd.test_execute().unwrap(),
+            Some(ListMsg::SelectionChanged(2))
+        );
+    }
 }
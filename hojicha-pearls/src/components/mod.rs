@@ -27,21 +27,21 @@ pub mod timer;
 pub mod utils;
 pub mod viewport;
 
-pub use button::{Button, ButtonSize, ButtonVariant};
+pub use button::{Button, ButtonMsg, ButtonSize, ButtonVariant};
 pub use help::{Help, HelpBuilder, HelpEntry, HelpMode};
 pub use keybinding::{KeyBinding, KeyMap};
-pub use list::{List, ListOptions};
-pub use modal::{Modal, ModalSize};
-pub use paginator::{Paginator, PaginatorStyle};
+pub use list::{List, ListMsg, ListOptions};
+pub use modal::{Modal, ModalMsg, ModalSize};
+pub use paginator::{Paginator, PaginatorMsg, PaginatorStyle};
 pub use progress_bar::{ProgressBar, ProgressStyle};
-pub use spinner::{Spinner, SpinnerStyle};
+pub use spinner::{Spinner, SpinnerMsg, SpinnerStyle};
 pub use status_bar::{StatusBar, StatusBarBuilder, StatusBarPosition, StatusSegment};
-pub use stopwatch::{Lap, Stopwatch, StopwatchFormat, StopwatchState};
-pub use styled_list::{ListItemTrait, StyledList};
-pub use styled_table::{Column, SortDirection, SortState, StyledTable};
-pub use table::{Table, TableOptions, TableRow};
-pub use tabs::{Tab, TabPosition, TabStyle, Tabs, TabsBuilder};
-pub use text_input::{TextInput, ValidationResult};
-pub use textarea::{TextArea, TextAreaOptions};
-pub use timer::{Timer, TimerFormat, TimerState};
+pub use stopwatch::{Lap, Stopwatch, StopwatchFormat, StopwatchMsg, StopwatchState};
+pub use styled_list::{ListItemTrait, StyledList, StyledListMsg};
+pub use styled_table::{Column, SortDirection, SortState, StyledTable, StyledTableMsg};
+pub use table::{Table, TableMsg, TableOptions, TableRow};
+pub use tabs::{Tab, TabPosition, TabStyle, Tabs, TabsBuilder, TabsMsg};
+pub use text_input::{TextInput, TextInputMsg, ValidationResult};
+pub use textarea::{TextArea, TextAreaMsg, TextAreaOptions};
+pub use timer::{Timer, TimerFormat, TimerMsg, TimerState};
 pub use viewport::{Viewport, ViewportOptions};
@@ -3,6 +3,8 @@
 //! Overlay dialogs for confirmations, forms, and information display.
 
 use crate::style::{BorderStyle, Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
 use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -52,6 +54,8 @@ pub struct Modal {
     footer: Option<String>,
     /// Footer style
     footer_style: Style,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl Modal {
@@ -70,6 +74,7 @@ impl Modal {
             content_style: Style::new(),
             footer: None,
             footer_style: Style::new().italic(),
+            profile: ColorProfile::default(),
         }
     }
 
@@ -134,6 +139,12 @@ impl Modal {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Open the modal
     pub fn open(&mut self) {
         self.open = true;
@@ -217,6 +228,10 @@ impl Modal {
 
     /// Render the modal
     pub fn render(&self, frame: &mut Frame, area: Rect, _theme: &Theme, profile: &ColorProfile) {
+        self.draw(frame, area, profile);
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, profile: &ColorProfile) {
         if !super::utils::is_valid_area(area) {
             return;
         }
@@ -334,3 +349,42 @@ impl Modal {
             .with_size(ModalSize::Small)
     }
 }
+
+/// Messages sent by a [`Modal`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalMsg {
+    /// The modal was closed with Escape
+    Closed,
+    /// Enter was pressed while the modal was open
+    Confirmed,
+}
+
+impl Component for Modal {
+    type Message = ModalMsg;
+
+    fn update(&mut self, event: Event<ModalMsg>) -> Cmd<ModalMsg> {
+        if !self.open {
+            return Cmd::none();
+        }
+        if let Event::Key(KeyEvent {
+            key: Key::Enter, ..
+        }) = event
+        {
+            return super::utils::emit(ModalMsg::Confirmed);
+        }
+        if self.handle_event(event.map(|_| ())) {
+            super::utils::emit(ModalMsg::Closed)
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.draw(frame, area, &self.profile);
+    }
+
+    /// An open modal takes all keyboard input
+    fn is_focused(&self) -> bool {
+        self.open
+    }
+}
@@ -3,6 +3,9 @@
 //! Supports both dot-style and numeric pagination displays.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::{Alignment, Rect},
     text::{Line, Span},
@@ -50,6 +53,10 @@ pub struct Paginator {
     inactive_dot: String,
     /// Show first/last page shortcuts
     show_shortcuts: bool,
+    /// Whether the paginator has keyboard focus
+    focused: bool,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl Paginator {
@@ -68,6 +75,8 @@ impl Paginator {
             active_dot: "●".to_string(),
             inactive_dot: "○".to_string(),
             show_shortcuts: false,
+            focused: false,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -183,6 +192,12 @@ impl Paginator {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme
     pub fn apply_theme(&mut self, theme: &Theme) {
         self.active_style = Style::new().bold().fg(theme.colors.primary.clone());
@@ -447,3 +462,53 @@ impl Default for Paginator {
         Self::new(1)
     }
 }
+
+/// Messages sent by a [`Paginator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaginatorMsg {
+    /// The page at this index (0-indexed) is now shown
+    PageChanged(usize),
+}
+
+impl Component for Paginator {
+    type Message = PaginatorMsg;
+
+    fn update(&mut self, event: Event<PaginatorMsg>) -> Cmd<PaginatorMsg> {
+        if !self.focused {
+            return Cmd::none();
+        }
+
+        let before = self.current_page;
+        if let Event::Key(KeyEvent { key, .. }) = event {
+            match key {
+                Key::Left | Key::Char('h') => self.previous_page(),
+                Key::Right | Key::Char('l') => self.next_page(),
+                Key::Home => self.first_page(),
+                Key::End => self.last_page(),
+                _ => {}
+            }
+        }
+
+        if self.current_page != before {
+            super::utils::emit(PaginatorMsg::PageChanged(self.current_page))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn blur(&mut self) {
+        self.focused = false;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
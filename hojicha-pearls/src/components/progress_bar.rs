@@ -3,6 +3,9 @@
 //! Visual progress indicators with various styles.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
 use ratatui::{
     layout::Rect,
     text::{Line, Span},
@@ -59,6 +62,10 @@ pub struct ProgressBar {
     container_style: Style,
     /// Use gradient colors
     use_gradient: bool,
+    /// Theme used when drawn as a component
+    theme: Theme,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl ProgressBar {
@@ -79,6 +86,8 @@ impl ProgressBar {
             label_style: Style::new().bold(),
             container_style: Style::new(),
             use_gradient: false,
+            theme: Theme::default(),
+            profile: ColorProfile::default(),
         }
     }
 
@@ -229,8 +238,18 @@ impl ProgressBar {
         parts.join(" ")
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme to this progress bar
+    ///
+    /// The theme is also used by [`Component::view`].
     pub fn apply_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+
         self.bar_style = Style::new().fg(theme.colors.primary.clone());
 
         self.background_style = Style::new().fg(theme.colors.surface.clone());
@@ -360,3 +379,16 @@ impl Default for ProgressBar {
         Self::new()
     }
 }
+
+/// A progress bar is only drawn, so it has no messages
+impl Component for ProgressBar {
+    type Message = ();
+
+    fn update(&mut self, _event: Event<()>) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.theme, &self.profile);
+    }
+}
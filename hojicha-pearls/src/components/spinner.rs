@@ -2,10 +2,15 @@
 //!
 //! Provides various spinner styles for loading indicators.
 
+use hojicha_core::component::{Component, SizeHint};
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
+use hojicha_core::subscriptions::{Sub, Subs};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Style;
 use ratatui::text::Span;
+use ratatui::Frame;
 use std::time::{Duration, Instant};
 
 /// Spinner animation styles
@@ -56,7 +61,7 @@ impl SpinnerStyle {
 }
 
 /// Animated spinner component
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Spinner {
     /// The spinner style
     style: SpinnerStyle,
@@ -70,6 +75,23 @@ pub struct Spinner {
     message: String,
     /// Text style
     text_style: Style,
+    /// Id used to key the animation subscription
+    id: u64,
+}
+
+// A clone gets its own id so its ticks don't reach the original
+impl Clone for Spinner {
+    fn clone(&self) -> Self {
+        Self {
+            style: self.style,
+            frame_index: self.frame_index,
+            last_update: self.last_update,
+            running: self.running,
+            message: self.message.clone(),
+            text_style: self.text_style,
+            id: super::utils::next_id(),
+        }
+    }
 }
 
 impl Spinner {
@@ -82,6 +104,7 @@ impl Spinner {
             running: false,
             message: String::new(),
             text_style: Style::default(),
+            id: super::utils::next_id(),
         }
     }
 
@@ -133,17 +156,20 @@ impl Spinner {
             return false;
         }
 
-        let now = Instant::now();
-        if now.duration_since(self.last_update) >= self.style.frame_duration() {
-            self.last_update = now;
-            let frames = self.style.frames();
-            self.frame_index = (self.frame_index + 1) % frames.len();
+        if self.last_update.elapsed() >= self.style.frame_duration() {
+            self.advance();
             true
         } else {
             false
         }
     }
 
+    /// Move to the next frame
+    fn advance(&mut self) {
+        self.last_update = Instant::now();
+        self.frame_index = (self.frame_index + 1) % self.style.frames().len();
+    }
+
     /// Get the current frame
     pub fn current_frame(&self) -> &str {
         if self.running {
@@ -162,6 +188,14 @@ impl Spinner {
 
         // Update animation
         self.tick();
+        self.draw(area, buf);
+    }
+
+    /// Draw the current frame without advancing the animation
+    fn draw(&self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
 
         // Build the display text
         let frame = self.current_frame();
@@ -211,6 +245,48 @@ impl Default for Spinner {
     }
 }
 
+/// Messages sent by a [`Spinner`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinnerMsg {
+    /// Time to show the next frame
+    Tick,
+}
+
+impl Component for Spinner {
+    type Message = SpinnerMsg;
+
+    fn update(&mut self, event: Event<SpinnerMsg>) -> Cmd<SpinnerMsg> {
+        if let Event::User(SpinnerMsg::Tick) = event {
+            if self.running {
+                self.advance();
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.draw(area, frame.buffer_mut());
+    }
+
+    /// Ticks once per frame while the spinner is running
+    fn subscriptions(&self) -> Subs<SpinnerMsg> {
+        if !self.running {
+            return Subs::none();
+        }
+
+        Sub::every(
+            format!("spinner-{}", self.id),
+            self.style.frame_duration(),
+            |_| SpinnerMsg::Tick,
+        )
+        .into()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::rows(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -396,4 +472,50 @@ mod tests {
         spinner.render(Rect::new(0, 0, 0, 0), &mut buf);
         spinner.render_centered(Rect::new(0, 0, 0, 0), &mut buf);
     }
+
+    #[test]
+    fn test_component_ticks_while_running() {
+        let mut spinner = Spinner::new();
+        assert!(spinner.subscriptions().is_empty());
+
+        spinner.start();
+        assert_eq!(spinner.subscriptions().len(), 1);
+
+        let _ = spinner.update(Event::User(SpinnerMsg::Tick));
+        assert_eq!(spinner.frame_index, 1);
+
+        // Every spinner gets its own subscription key
+        let other = Spinner::new();
+        assert_ne!(spinner.id, other.id);
+
+        spinner.stop();
+        assert!(spinner.subscriptions().is_empty());
+    }
+
+    #[test]
+    fn test_clones_tick_on_their_own_key() {
+        use crate::components::{Stopwatch, Timer};
+
+        fn keys<C: Component>(component: &C) -> Vec<String> {
+            component
+                .subscriptions()
+                .keys()
+                .map(str::to_string)
+                .collect()
+        }
+
+        let mut spinner = Spinner::new();
+        spinner.start();
+        let clone = spinner.clone();
+        assert!(clone.is_running());
+        assert_ne!(keys(&spinner), keys(&clone));
+
+        let mut timer = Timer::from_seconds(5);
+        timer.start();
+        assert_ne!(keys(&timer), keys(&timer.clone()));
+
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.start();
+        assert_ne!(keys(&stopwatch), keys(&stopwatch.clone()));
+    }
 }
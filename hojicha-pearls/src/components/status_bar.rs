@@ -3,6 +3,9 @@
 //! A customizable status bar that can display multiple segments with different styles.
 
 use crate::style::{Color, ColorProfile, Style, TextAlign, Theme};
+use hojicha_core::component::{Component, SizeHint};
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     widgets::{Block, Borders, Paragraph},
@@ -96,6 +99,8 @@ pub struct StatusBar {
     height: u16,
     /// Whether to show borders
     show_borders: bool,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl StatusBar {
@@ -110,6 +115,7 @@ impl StatusBar {
             separator_style: Style::new().fg(Color::gray()),
             height: 1,
             show_borders: false,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -219,6 +225,12 @@ impl StatusBar {
         }
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme
     pub fn apply_theme(&mut self, theme: &Theme) {
         self.container_style = Style::new()
@@ -349,6 +361,23 @@ impl Default for StatusBar {
     }
 }
 
+/// A status bar is only drawn, so it has no messages
+impl Component for StatusBar {
+    type Message = ();
+
+    fn update(&mut self, _event: Event<()>) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::rows(self.height())
+    }
+}
+
 /// Builder for creating status bars with common patterns
 pub struct StatusBarBuilder {
     status_bar: StatusBar,
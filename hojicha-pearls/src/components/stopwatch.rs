@@ -3,6 +3,10 @@
 //! A flexible component for measuring elapsed time with customizable display formats.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
+use hojicha_core::subscriptions::Subs;
 use ratatui::{
     layout::{Alignment, Rect},
     widgets::{Block, Borders, Paragraph},
@@ -60,7 +64,6 @@ pub struct Lap {
 }
 
 /// A stopwatch component for counting up
-#[derive(Clone)]
 pub struct Stopwatch {
     /// Elapsed duration
     elapsed: Duration,
@@ -84,6 +87,31 @@ pub struct Stopwatch {
     title: Option<String>,
     /// Maximum laps to store
     max_laps: usize,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
+    /// Id used to key the clock subscription
+    id: u64,
+}
+
+// A clone gets its own id so its ticks don't reach the original
+impl Clone for Stopwatch {
+    fn clone(&self) -> Self {
+        Self {
+            elapsed: self.elapsed,
+            state: self.state.clone(),
+            format: self.format.clone(),
+            laps: self.laps.clone(),
+            normal_style: self.normal_style.clone(),
+            running_style: self.running_style.clone(),
+            paused_style: self.paused_style.clone(),
+            container_style: self.container_style.clone(),
+            show_milliseconds: self.show_milliseconds,
+            title: self.title.clone(),
+            max_laps: self.max_laps,
+            profile: self.profile.clone(),
+            id: super::utils::next_id(),
+        }
+    }
 }
 
 impl Stopwatch {
@@ -101,6 +129,8 @@ impl Stopwatch {
             show_milliseconds: true,
             title: None,
             max_laps: 100,
+            profile: ColorProfile::default(),
+            id: super::utils::next_id(),
         }
     }
 
@@ -256,6 +286,12 @@ impl Stopwatch {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme
     pub fn apply_theme(&mut self, theme: &Theme) {
         self.normal_style = Style::new().fg(theme.colors.text.clone());
@@ -409,3 +445,39 @@ impl Default for Stopwatch {
         Self::new()
     }
 }
+
+/// Messages sent by a [`Stopwatch`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopwatchMsg {
+    /// This much time passed while running
+    Tick(Duration),
+}
+
+impl Component for Stopwatch {
+    type Message = StopwatchMsg;
+
+    fn update(&mut self, event: Event<StopwatchMsg>) -> Cmd<StopwatchMsg> {
+        if let Event::User(StopwatchMsg::Tick(elapsed)) = event {
+            self.tick(elapsed);
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    /// Counts up while the stopwatch is running
+    fn subscriptions(&self) -> Subs<StopwatchMsg> {
+        if !self.is_running() {
+            return Subs::none();
+        }
+
+        super::utils::elapsed_every(
+            format!("stopwatch-{}", self.id),
+            super::utils::CLOCK_INTERVAL,
+            StopwatchMsg::Tick,
+        )
+        .into()
+    }
+}
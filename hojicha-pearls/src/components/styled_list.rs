@@ -3,6 +3,8 @@
 //! A scrollable list with selection, filtering, and rich styling options.
 
 use crate::style::{ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
 use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::Rect,
@@ -11,6 +13,7 @@ use ratatui::{
     widgets::{Block, Borders, List as RatatuiList, ListItem as RatatuiListItem, ListState},
     Frame,
 };
+use std::cell::Cell;
 
 /// List item that can be displayed
 pub trait ListItemTrait: Clone {
@@ -57,8 +60,10 @@ pub struct StyledList<T: ListItemTrait> {
     filter: String,
     /// Whether filtering is enabled
     filter_enabled: bool,
-    /// The list state (selection, scroll position)
+    /// The list state (selection)
     state: ListState,
+    /// Scroll position (set during render)
+    offset: Cell<usize>,
     /// Title of the list
     title: Option<String>,
     /// Style for normal items
@@ -79,6 +84,8 @@ pub struct StyledList<T: ListItemTrait> {
     selection_indicator: String,
     /// Maximum height (in rows)
     max_height: Option<u16>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl<T: ListItemTrait> StyledList<T> {
@@ -97,6 +104,7 @@ impl<T: ListItemTrait> StyledList<T> {
             filter: String::new(),
             filter_enabled: false,
             state,
+            offset: Cell::new(0),
             title: None,
             item_style: Style::new(),
             selected_style: Style::new().bg(crate::style::Color::blue()).bold(),
@@ -107,6 +115,7 @@ impl<T: ListItemTrait> StyledList<T> {
             show_selection: true,
             selection_indicator: "> ".to_string(),
             max_height: None,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -287,6 +296,12 @@ impl<T: ListItemTrait> StyledList<T> {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Handle keyboard input
     pub fn handle_event(&mut self, event: Event<()>) -> bool {
         if !self.focused {
@@ -337,7 +352,7 @@ impl<T: ListItemTrait> StyledList<T> {
     }
 
     /// Render the list
-    pub fn render(&mut self, frame: &mut Frame, area: Rect, profile: &ColorProfile) {
+    pub fn render(&self, frame: &mut Frame, area: Rect, profile: &ColorProfile) {
         // Create list items with styling
         let items: Vec<RatatuiListItem> = self
             .filtered_items
@@ -414,8 +429,10 @@ impl<T: ListItemTrait> StyledList<T> {
             area
         };
 
-        // Render the list
-        frame.render_stateful_widget(list, render_area, &mut self.state);
+        // Render the list, keeping the scroll position for the next frame
+        let mut state = self.state.clone().with_offset(self.offset.get());
+        frame.render_stateful_widget(list, render_area, &mut state);
+        self.offset.set(state.offset());
     }
 
     /// Get the number of items (after filtering)
@@ -428,3 +445,44 @@ impl<T: ListItemTrait> StyledList<T> {
         self.filtered_items.is_empty()
     }
 }
+
+/// Messages sent by a [`StyledList`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyledListMsg {
+    /// The item at this index of the filtered items was selected
+    SelectionChanged(usize),
+}
+
+impl<T: ListItemTrait> Component for StyledList<T> {
+    type Message = StyledListMsg;
+
+    fn update(&mut self, event: Event<StyledListMsg>) -> Cmd<StyledListMsg> {
+        let before = self.selected_index();
+        if !self.handle_event(event.map(|_| ())) {
+            return Cmd::none();
+        }
+
+        match self.selected_index() {
+            Some(index) if Some(index) != before => {
+                super::utils::emit(StyledListMsg::SelectionChanged(index))
+            }
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        StyledList::focus(self);
+    }
+
+    fn blur(&mut self) {
+        StyledList::blur(self);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
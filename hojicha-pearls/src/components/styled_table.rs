@@ -3,6 +3,8 @@
 //! A table with rich styling, sorting, and selection capabilities.
 
 use crate::style::{BorderStyle, Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
 use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::{Constraint, Rect},
@@ -10,6 +12,7 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Row, Table as RatatuiTable, TableState},
     Frame,
 };
+use std::cell::Cell as StateCell;
 
 /// Table column definition
 #[derive(Clone)]
@@ -85,6 +88,8 @@ pub struct StyledTable {
     original_indices: Vec<usize>,
     /// Table state (selection)
     state: TableState,
+    /// Scroll position (set during render)
+    offset: StateCell<usize>,
     /// Sort state
     sort_state: Option<SortState>,
     /// Table title
@@ -109,6 +114,8 @@ pub struct StyledTable {
     show_header: bool,
     /// Maximum height in rows
     max_height: Option<u16>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl StyledTable {
@@ -119,6 +126,7 @@ impl StyledTable {
             rows: Vec::new(),
             original_indices: Vec::new(),
             state: TableState::default(),
+            offset: StateCell::new(0),
             sort_state: None,
             title: None,
             focused: false,
@@ -131,6 +139,7 @@ impl StyledTable {
             title_style: Style::new().bold(),
             show_header: true,
             max_height: None,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -203,6 +212,12 @@ impl StyledTable {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Focus the table
     pub fn focus(&mut self) {
         self.focused = true;
@@ -409,7 +424,7 @@ impl StyledTable {
     }
 
     /// Render the table
-    pub fn render(&mut self, frame: &mut Frame, area: Rect, profile: &ColorProfile) {
+    pub fn render(&self, frame: &mut Frame, area: Rect, profile: &ColorProfile) {
         // Create header row
         let header_cells: Vec<Cell> = self
             .columns
@@ -532,8 +547,10 @@ impl StyledTable {
             area
         };
 
-        // Render the table
-        frame.render_stateful_widget(table, render_area, &mut self.state);
+        // Render the table, keeping the scroll position for the next frame
+        let mut state = self.state.clone().with_offset(self.offset.get());
+        frame.render_stateful_widget(table, render_area, &mut state);
+        self.offset.set(state.offset());
     }
 
     /// Get the number of rows
@@ -546,3 +563,56 @@ impl StyledTable {
         self.rows.is_empty()
     }
 }
+
+/// Messages sent by a [`StyledTable`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyledTableMsg {
+    /// The row at this index was selected
+    SelectionChanged(usize),
+    /// The rows were sorted by a column
+    Sorted(usize, SortDirection),
+}
+
+impl Component for StyledTable {
+    type Message = StyledTableMsg;
+
+    fn update(&mut self, event: Event<StyledTableMsg>) -> Cmd<StyledTableMsg> {
+        let sort_key = matches!(
+            event,
+            Event::Key(KeyEvent {
+                key: Key::Char('1'..='9'),
+                ..
+            })
+        );
+        let before = self.selected();
+        if !self.handle_event(event.map(|_| ())) {
+            return Cmd::none();
+        }
+
+        if let (true, Some(sort)) = (sort_key, &self.sort_state) {
+            return super::utils::emit(StyledTableMsg::Sorted(sort.column, sort.direction));
+        }
+        match self.selected() {
+            Some(index) if Some(index) != before => {
+                super::utils::emit(StyledTableMsg::SelectionChanged(index))
+            }
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        StyledTable::focus(self);
+    }
+
+    fn blur(&mut self) {
+        StyledTable::blur(self);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
@@ -2,11 +2,14 @@
 //!
 //! A table provides a structured view of data with columns, headers, and row selection.
 
-use hojicha_core::event::{Key, KeyEvent, MouseEvent, MouseEventKind};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::widgets::{Block, Cell, Row, Table as RatatuiTable, Widget};
+use ratatui::Frame;
 use std::cmp::{max, min};
 
 /// Options for customizing table behavior
@@ -63,7 +66,9 @@ pub struct Table<T> {
     /// Whether the table has focus
     focused: bool,
     /// Visible height (set during render)
-    height: usize,
+    height: std::cell::Cell<usize>,
+    /// Area the table was last rendered to, for mouse handling
+    area: std::cell::Cell<Rect>,
     /// Table options
     options: TableOptions,
     /// Optional block for borders/title
@@ -79,7 +84,8 @@ impl<T> Table<T> {
             selected: 0,
             offset: 0,
             focused: false,
-            height: 10,
+            height: std::cell::Cell::new(10),
+            area: std::cell::Cell::new(Rect::default()),
             options: TableOptions::default(),
             block: None,
         }
@@ -206,8 +212,8 @@ impl<T> Table<T> {
     fn ensure_visible(&mut self) {
         if self.selected < self.offset {
             self.offset = self.selected;
-        } else if self.selected >= self.offset + self.height {
-            self.offset = self.selected.saturating_sub(self.height - 1);
+        } else if self.selected >= self.offset + self.height.get() {
+            self.offset = self.selected.saturating_sub(self.height.get() - 1);
         }
     }
 
@@ -349,7 +355,9 @@ pub trait TableRow {
 
 impl<T: TableRow> Table<T> {
     /// Render the table to a buffer
-    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        self.area.set(area);
+
         // Draw block if present
         let inner = if let Some(ref block) = self.block {
             let widget = block.clone();
@@ -360,10 +368,11 @@ impl<T: TableRow> Table<T> {
         };
 
         // Calculate available height for rows (minus header and separator)
-        self.height = inner.height.saturating_sub(2) as usize;
+        let height = inner.height.saturating_sub(2) as usize;
+        self.height.set(height);
 
         // Calculate visible range
-        let end = min(self.offset + self.height, self.rows.len());
+        let end = min(self.offset + height, self.rows.len());
 
         // Prepare headers
         let header_cells: Vec<Cell> = if self.options.show_row_numbers {
@@ -424,16 +433,14 @@ impl<T: TableRow> Table<T> {
         Widget::render(table, inner, buf);
 
         // Draw scrollbar if needed
-        if self.rows.len() > self.height {
+        if self.rows.len() > height {
             let scrollbar_x = inner.x + inner.width - 1;
             let scrollbar_height = inner.height.saturating_sub(2); // Minus header
             let scrollbar_y = inner.y + 2; // After header
 
             // Calculate thumb size and position
-            let thumb_height = max(
-                1,
-                (self.height * scrollbar_height as usize) / self.rows.len(),
-            ) as u16;
+            let thumb_height =
+                max(1, (height * scrollbar_height as usize) / self.rows.len()) as u16;
             let thumb_pos = ((self.offset * scrollbar_height as usize) / self.rows.len()) as u16;
 
             // Draw scrollbar track
@@ -453,6 +460,48 @@ impl<T: TableRow> Table<T> {
     }
 }
 
+/// Messages sent by a [`Table`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableMsg {
+    /// The row at this index was selected
+    SelectionChanged(usize),
+}
+
+impl<T: TableRow> Component for Table<T> {
+    type Message = TableMsg;
+
+    fn update(&mut self, event: Event<TableMsg>) -> Cmd<TableMsg> {
+        let before = self.selected;
+        let handled = match event {
+            Event::Key(key) => self.handle_key(&key),
+            Event::Mouse(mouse) => self.handle_mouse(&mouse, self.area.get()),
+            _ => false,
+        };
+
+        if handled && self.selected != before {
+            super::utils::emit(TableMsg::SelectionChanged(self.selected))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(area, frame.buffer_mut());
+    }
+
+    fn focus(&mut self) {
+        self.set_focused(true);
+    }
+
+    fn blur(&mut self) {
+        self.set_focused(false);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -696,7 +745,7 @@ mod tests {
             .collect();
 
         let mut table = Table::new(headers).with_rows(rows);
-        table.height = 5; // Simulate small viewport
+        table.height.set(5); // Simulate small viewport
 
         // Initial offset should be 0
         assert_eq!(table.offset, 0);
@@ -709,7 +758,7 @@ mod tests {
         // Offset should have adjusted
         assert!(table.offset > 0);
         assert!(table.selected >= table.offset);
-        assert!(table.selected < table.offset + table.height);
+        assert!(table.selected < table.offset + table.height.get());
     }
 
     #[test]
@@ -759,7 +808,7 @@ mod tests {
             value: 1.0,
         }];
 
-        let mut table = Table::new(headers).with_rows(rows);
+        let table = Table::new(headers).with_rows(rows);
 
         let mut buf = Buffer::empty(Rect::new(0, 0, 30, 10));
         table.render(Rect::new(0, 0, 30, 10), &mut buf);
@@ -846,4 +895,28 @@ mod tests {
         table.select_previous();
         assert_eq!(table.selected(), 0);
     }
+
+    #[test]
+    fn test_component_reports_selection() {
+        use hojicha_core::event::KeyModifiers;
+
+        let rows = (1..=3)
+            .map(|id| TestRow {
+                id,
+                name: format!("Row {id}"),
+                value: id as f64,
+            })
+            .collect();
+        let mut table = Table::new(vec!["ID".to_string()]).with_rows(rows);
+
+        // Unfocused tables ignore keys
+        let down = || Event::Key(KeyEvent::new(Key::Down, KeyModifiers::empty()));
+        assert!(table.update(down()).is_noop());
+
+        Component::focus(&mut table);
+        assert_eq!(
+            table.update(down()).test_execute().unwrap(),
+            Some(TableMsg::SelectionChanged(1))
+        );
+    }
 }
@@ -3,6 +3,8 @@
 //! A flexible tab bar component with support for icons, badges, and closeable tabs.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
 use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -127,6 +129,8 @@ pub struct Tabs {
     divider: String,
     /// Callback for tab close (stored as index)
     on_close_requested: Option<usize>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl Tabs {
@@ -146,6 +150,7 @@ impl Tabs {
             show_dividers: false,
             divider: "│".to_string(),
             on_close_requested: None,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -286,6 +291,12 @@ impl Tabs {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Focus the component
     pub fn focus(&mut self) {
         self.focused = true;
@@ -588,6 +599,50 @@ impl Default for Tabs {
     }
 }
 
+/// Messages sent by [`Tabs`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabsMsg {
+    /// Another tab was selected
+    Selected(usize),
+    /// Closing the tab at this index was requested
+    CloseRequested(usize),
+}
+
+impl Component for Tabs {
+    type Message = TabsMsg;
+
+    fn update(&mut self, event: Event<TabsMsg>) -> Cmd<TabsMsg> {
+        let before = self.selected;
+        if !self.handle_event(event.map(|_| ())) {
+            return Cmd::none();
+        }
+
+        if let Some(index) = self.close_requested() {
+            super::utils::emit(TabsMsg::CloseRequested(index))
+        } else if self.selected != before {
+            super::utils::emit(TabsMsg::Selected(self.selected))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        Tabs::focus(self);
+    }
+
+    fn blur(&mut self) {
+        Tabs::blur(self);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
 /// Builder for creating tabs
 pub struct TabsBuilder {
     tabs: Vec<Tab>,
@@ -3,6 +3,9 @@
 //! A text input field with validation, placeholder text, and theming support.
 
 use crate::style::{ColorProfile, Style, Theme};
+use hojicha_core::component::{Component, SizeHint};
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style as RatatuiStyle},
@@ -47,6 +50,8 @@ pub struct TextInput {
     error_style: Style,
     /// Maximum length allowed
     max_length: Option<usize>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
 }
 
 impl TextInput {
@@ -71,6 +76,7 @@ impl TextInput {
                 .border(crate::style::BorderStyle::Normal)
                 .border_color(crate::style::Color::red()),
             max_length: None,
+            profile: ColorProfile::default(),
         }
     }
 
@@ -115,6 +121,12 @@ impl TextInput {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Set focused state
     pub fn focus(&mut self) {
         self.focused = true;
@@ -198,6 +210,27 @@ impl TextInput {
         self.cursor_position = self.value.len();
     }
 
+    /// Handle editing and cursor keys
+    ///
+    /// Returns `true` if the key was handled.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.focused {
+            return false;
+        }
+
+        match key.key {
+            Key::Char(c) if !key.is_ctrl() && !key.is_alt() => self.insert_char(c),
+            Key::Backspace => self.delete_char(),
+            Key::Delete => self.delete_char_forward(),
+            Key::Left => self.move_cursor_left(),
+            Key::Right => self.move_cursor_right(),
+            Key::Home => self.move_cursor_start(),
+            Key::End => self.move_cursor_end(),
+            _ => return false,
+        }
+        true
+    }
+
     /// Clear the input
     pub fn clear(&mut self) {
         self.value.clear();
@@ -376,3 +409,64 @@ impl TextInput {
         }
     }
 }
+
+/// Messages sent by a [`TextInput`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInputMsg {
+    /// The value was edited
+    Changed(String),
+    /// Enter was pressed
+    Submitted(String),
+}
+
+impl Component for TextInput {
+    type Message = TextInputMsg;
+
+    fn update(&mut self, event: Event<TextInputMsg>) -> Cmd<TextInputMsg> {
+        if !self.focused {
+            return Cmd::none();
+        }
+
+        let before = self.value.clone();
+        match event {
+            Event::Key(KeyEvent {
+                key: Key::Enter, ..
+            }) => return super::utils::emit(TextInputMsg::Submitted(before)),
+            Event::Key(key) => {
+                self.handle_key(&key);
+            }
+            Event::Paste(text) => text
+                .chars()
+                .filter(|c| !c.is_control())
+                .for_each(|c| self.insert_char(c)),
+            _ => {}
+        }
+
+        if self.value != before {
+            super::utils::emit(TextInputMsg::Changed(self.value.clone()))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut ratatui::Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    fn focus(&mut self) {
+        TextInput::focus(self);
+    }
+
+    fn blur(&mut self) {
+        TextInput::blur(self);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// One line of text inside a border
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::rows(3)
+    }
+}
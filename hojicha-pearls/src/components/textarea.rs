@@ -7,12 +7,15 @@
 //! - Line numbers
 //! - Word wrapping
 
-use hojicha_core::event::{Key, KeyEvent, KeyModifiers};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent, KeyModifiers};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Widget};
+use ratatui::Frame;
 use std::cmp::min;
 
 /// Multi-line text input component
@@ -540,6 +543,52 @@ impl Default for TextArea {
     }
 }
 
+/// Messages sent by a [`TextArea`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextAreaMsg {
+    /// The text was edited
+    Changed(String),
+}
+
+impl Component for TextArea {
+    type Message = TextAreaMsg;
+
+    fn update(&mut self, event: Event<TextAreaMsg>) -> Cmd<TextAreaMsg> {
+        let before = self.lines.clone();
+        match event {
+            Event::Key(key) => {
+                self.handle_event(&key);
+            }
+            Event::Paste(text) => {
+                self.handle_paste(&text);
+            }
+            _ => {}
+        }
+
+        if self.lines != before {
+            super::utils::emit(TextAreaMsg::Changed(self.value()))
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(area, frame.buffer_mut());
+    }
+
+    fn focus(&mut self) {
+        self.set_focused(true);
+    }
+
+    fn blur(&mut self) {
+        self.set_focused(false);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -748,4 +797,29 @@ mod tests {
         let buffer = terminal.backend().buffer();
         assert!(!buffer.content().is_empty());
     }
+
+    #[test]
+    fn test_component_reports_changes() {
+        let mut textarea = TextArea::new();
+        Component::focus(&mut textarea);
+
+        let cmd = textarea.update(Event::Key(KeyEvent::new(
+            Key::Char('a'),
+            KeyModifiers::empty(),
+        )));
+        assert_eq!(
+            cmd.test_execute().unwrap(),
+            Some(TextAreaMsg::Changed("a".to_string()))
+        );
+
+        // Moving the cursor doesn't change the text
+        let cmd = textarea.update(Event::Key(KeyEvent::new(Key::Left, KeyModifiers::empty())));
+        assert!(cmd.is_noop());
+
+        let cmd = textarea.update(Event::Paste("b\nc".to_string()));
+        assert_eq!(
+            cmd.test_execute().unwrap(),
+            Some(TextAreaMsg::Changed("b\nca".to_string()))
+        );
+    }
 }
@@ -3,6 +3,10 @@
 //! A flexible component for counting down time with customizable display formats.
 
 use crate::style::{Color, ColorProfile, Style, Theme};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::Event;
+use hojicha_core::subscriptions::Subs;
 use ratatui::{
     layout::{Alignment, Rect},
     widgets::{Block, Borders, Paragraph},
@@ -49,7 +53,6 @@ pub enum TimerState {
 }
 
 /// A countdown timer component
-#[derive(Clone)]
 pub struct Timer {
     /// Initial duration for the timer
     initial_duration: Duration,
@@ -79,6 +82,34 @@ pub struct Timer {
     title: Option<String>,
     /// Message to show when finished
     finished_message: Option<String>,
+    /// Color profile used when drawn as a component
+    profile: ColorProfile,
+    /// Id used to key the clock subscription
+    id: u64,
+}
+
+// A clone gets its own id so its ticks don't reach the original
+impl Clone for Timer {
+    fn clone(&self) -> Self {
+        Self {
+            initial_duration: self.initial_duration,
+            remaining: self.remaining,
+            state: self.state.clone(),
+            format: self.format.clone(),
+            normal_style: self.normal_style.clone(),
+            warning_style: self.warning_style.clone(),
+            critical_style: self.critical_style.clone(),
+            finished_style: self.finished_style.clone(),
+            container_style: self.container_style.clone(),
+            warning_threshold: self.warning_threshold,
+            critical_threshold: self.critical_threshold,
+            show_milliseconds: self.show_milliseconds,
+            title: self.title.clone(),
+            finished_message: self.finished_message.clone(),
+            profile: self.profile.clone(),
+            id: super::utils::next_id(),
+        }
+    }
 }
 
 impl Timer {
@@ -99,6 +130,8 @@ impl Timer {
             show_milliseconds: false,
             title: None,
             finished_message: Some("Time's up!".to_string()),
+            profile: ColorProfile::default(),
+            id: super::utils::next_id(),
         }
     }
 
@@ -245,6 +278,12 @@ impl Timer {
         self
     }
 
+    /// Set the color profile used by [`Component::view`]
+    pub fn with_profile(mut self, profile: ColorProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Apply a theme
     pub fn apply_theme(&mut self, theme: &Theme) {
         self.normal_style = Style::new().fg(theme.colors.text.clone());
@@ -372,3 +411,46 @@ impl Default for Timer {
         Self::from_seconds(60)
     }
 }
+
+/// Messages sent by a [`Timer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMsg {
+    /// This much time passed while running
+    Tick(Duration),
+    /// The countdown reached zero
+    Finished,
+}
+
+impl Component for Timer {
+    type Message = TimerMsg;
+
+    fn update(&mut self, event: Event<TimerMsg>) -> Cmd<TimerMsg> {
+        if let Event::User(TimerMsg::Tick(elapsed)) = event {
+            if self.is_running() {
+                self.tick(elapsed);
+                if self.is_finished() {
+                    return super::utils::emit(TimerMsg::Finished);
+                }
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(frame, area, &self.profile);
+    }
+
+    /// Counts down while the timer is running
+    fn subscriptions(&self) -> Subs<TimerMsg> {
+        if !self.is_running() {
+            return Subs::none();
+        }
+
+        super::utils::elapsed_every(
+            format!("timer-{}", self.id),
+            super::utils::CLOCK_INTERVAL,
+            TimerMsg::Tick,
+        )
+        .into()
+    }
+}
@@ -2,10 +2,42 @@
 //!
 //! Common helper functions used across components.
 
+use hojicha_core::core::{Cmd, Message};
+use hojicha_core::subscriptions::Sub;
 use ratatui::layout::Rect;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 /// Check if an area is valid for rendering (non-zero width and height)
 #[inline]
 pub fn is_valid_area(area: Rect) -> bool {
     area.width > 0 && area.height > 0
 }
+
+/// A command that reports `msg` to the component's parent
+pub(crate) fn emit<M: Message>(msg: M) -> Cmd<M> {
+    Cmd::message(msg)
+}
+
+/// A unique id, used to key a component's subscriptions
+pub(crate) fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// How often clock components update while running
+pub(crate) const CLOCK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A subscription that reports the time passed since its previous message
+pub(crate) fn elapsed_every<M: Message>(
+    key: String,
+    interval: Duration,
+    f: fn(Duration) -> M,
+) -> Sub<M> {
+    let mut last: Option<Instant> = None;
+    Sub::every(key, interval, move |at| {
+        let elapsed = last.map_or(interval, |last| at.duration_since(last));
+        last = Some(at);
+        f(elapsed)
+    })
+}
@@ -2,12 +2,15 @@
 //!
 //! A viewport provides a scrollable view of content that exceeds the visible area.
 
-use hojicha_core::event::{Key, KeyEvent, MouseEvent, MouseEventKind};
+use hojicha_core::component::Component;
+use hojicha_core::core::Cmd;
+use hojicha_core::event::{Event, Key, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Widget};
+use ratatui::Frame;
 use std::cmp::{max, min};
 
 /// Scrollable viewport for displaying content
@@ -348,6 +351,40 @@ impl Default for Viewport {
     }
 }
 
+/// A viewport only scrolls, so it has no messages to report
+impl Component for Viewport {
+    type Message = ();
+
+    fn update(&mut self, event: Event<()>) -> Cmd<()> {
+        match event {
+            Event::Key(key) => {
+                self.handle_key(&key);
+            }
+            Event::Mouse(mouse) => {
+                self.handle_mouse(&mouse);
+            }
+            _ => {}
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.render(area, frame.buffer_mut());
+    }
+
+    fn focus(&mut self) {
+        self.set_focused(true);
+    }
+
+    fn blur(&mut self) {
+        self.set_focused(false);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -572,4 +609,27 @@ mod tests {
         let buffer = terminal.backend().buffer();
         assert!(!buffer.content().is_empty());
     }
+
+    #[test]
+    fn test_component_scrolls_when_focused() {
+        use hojicha_core::event::KeyModifiers;
+
+        let mut viewport = Viewport::new();
+        viewport.height = 5;
+        viewport.set_content(
+            (0..20)
+                .map(|i| format!("Line {i}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        let down = || Event::Key(KeyEvent::new(Key::Down, KeyModifiers::empty()));
+
+        viewport.update(down());
+        assert_eq!(viewport.y_offset, 0);
+
+        Component::focus(&mut viewport);
+        assert!(Component::is_focused(&viewport));
+        viewport.update(down());
+        assert_eq!(viewport.y_offset, 1);
+    }
 }
@@ -0,0 +1,683 @@
+//! Decoding raw terminal input into events
+//!
+//! Programs read the terminal through crossterm by default. When input comes
+//! from somewhere else (`ProgramOptions::with_input`), the bytes are decoded
+//! here instead. The decoder understands what terminals send in raw mode:
+//!
+//! - UTF-8 text and control characters (Ctrl+letter, Enter, Tab, Backspace)
+//! - CSI and SS3 key sequences, including xterm modifier parameters
+//!   (`ESC [1;5A` is Ctrl+Up) and `CSI u` key reports
+//! - SGR mouse reports (`ESC [<0;10;5M`) and legacy X10 mouse reports
+//! - Bracketed paste (`ESC [200~ ... ESC [201~`) as a single `Event::Paste`
+//! - Focus in/out (`ESC [I` / `ESC [O`) as `Event::Focus` / `Event::Blur`
+//! - Alt+key sent as an ESC prefix
+//!
+//! Sequences split across reads are buffered until the rest arrives.
+//!
+//! ## The ESC ambiguity
+//!
+//! A lone ESC byte is either the Escape key or the start of a sequence whose
+//! remaining bytes haven't arrived yet. The decoder holds on to it and reports
+//! [`InputDecoder::is_waiting`]; if nothing follows within the ESC timeout,
+//! call [`InputDecoder::flush`] to resolve it as the Escape key.
+//!
+//! ```
+//! use hojicha_core::event::{Event, Key, KeyModifiers};
+//! use hojicha_runtime::input_decoder::InputDecoder;
+//!
+//! let mut decoder = InputDecoder::new();
+//! let events: Vec<Event<()>> = decoder.feed(b"hi\x1b[1;5A");
+//! assert_eq!(events.len(), 3);
+//! assert!(events[2].is_key_with_modifiers(Key::Up, KeyModifiers::CONTROL));
+//!
+//! // A lone ESC waits until the timeout passes
+//! assert!(decoder.feed::<()>(b"\x1b").is_empty());
+//! assert!(decoder.is_waiting());
+//! let events: Vec<Event<()>> = decoder.flush();
+//! assert!(events[0].is_key_press(Key::Esc));
+//! ```
+
+use crossterm::event::{MouseButton, MouseEventKind};
+use hojicha_core::event::{Event, Key, KeyEvent, KeyModifiers, MouseEvent};
+use std::time::Duration;
+
+/// How long to wait after a lone ESC before treating it as the Escape key
+pub const DEFAULT_ESC_TIMEOUT: Duration = Duration::from_millis(50);
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Turns a stream of terminal input bytes into events
+#[derive(Debug)]
+pub struct InputDecoder {
+    pending: Vec<u8>,
+    esc_timeout: Duration,
+}
+
+impl Default for InputDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of decoding the start of the buffer
+enum Decoded<M> {
+    /// An event and the number of bytes it used
+    Event(Event<M>, usize),
+    /// Bytes that don't mean anything and can be dropped
+    Skip(usize),
+    /// The bytes so far are the start of something longer
+    Incomplete,
+}
+
+impl InputDecoder {
+    /// Create a decoder with the default ESC timeout
+    pub fn new() -> Self {
+        Self::with_esc_timeout(DEFAULT_ESC_TIMEOUT)
+    }
+
+    /// Create a decoder with a custom ESC timeout
+    ///
+    /// The decoder doesn't keep time itself; the timeout is what its reader
+    /// should wait before calling [`InputDecoder::flush`].
+    pub fn with_esc_timeout(esc_timeout: Duration) -> Self {
+        Self {
+            pending: Vec::new(),
+            esc_timeout,
+        }
+    }
+
+    /// How long to wait for the rest of an escape sequence
+    pub fn esc_timeout(&self) -> Duration {
+        self.esc_timeout
+    }
+
+    /// Decode the next chunk of input
+    ///
+    /// Anything incomplete at the end is kept for the next call.
+    pub fn feed<M>(&mut self, bytes: &[u8]) -> Vec<Event<M>> {
+        self.pending.extend_from_slice(bytes);
+        self.decode(false)
+    }
+
+    /// Check if buffered input is waiting on the ESC timeout
+    ///
+    /// This is true when the buffer starts with an ESC that could be the
+    /// Escape key. Unfinished pastes and UTF-8 characters wait for more input
+    /// instead.
+    pub fn is_waiting(&self) -> bool {
+        self.pending.first() == Some(&0x1b) && !self.pending.starts_with(PASTE_START)
+    }
+
+    /// Decode everything that is buffered, assuming no more input is coming
+    ///
+    /// Call this once the ESC timeout has passed or the input has ended. A
+    /// dangling ESC becomes the Escape key and an unfinished paste is delivered
+    /// as it is.
+    pub fn flush<M>(&mut self) -> Vec<Event<M>> {
+        self.decode(true)
+    }
+
+    fn decode<M>(&mut self, flush: bool) -> Vec<Event<M>> {
+        let mut events = Vec::new();
+        let mut pos = 0;
+        while pos < self.pending.len() {
+            match decode_one(&self.pending[pos..], flush) {
+                Decoded::Event(event, len) => {
+                    events.push(event);
+                    pos += len;
+                }
+                Decoded::Skip(len) => pos += len,
+                Decoded::Incomplete => break,
+            }
+        }
+        self.pending.drain(..pos);
+        events
+    }
+}
+
+fn key<M>(key: Key, modifiers: KeyModifiers) -> Event<M> {
+    Event::Key(KeyEvent::new(key, modifiers))
+}
+
+/// Decode a single event from the start of `bytes`
+///
+/// With `flush` set nothing is incomplete: unfinished escape sequences are
+/// read as the Escape key followed by ordinary input.
+fn decode_one<M>(bytes: &[u8], flush: bool) -> Decoded<M> {
+    match bytes[0] {
+        0x1b => {
+            let decoded = decode_escape(bytes, flush);
+            match decoded {
+                Decoded::Incomplete if flush => {
+                    Decoded::Event(key(Key::Esc, KeyModifiers::NONE), 1)
+                }
+                decoded => decoded,
+            }
+        }
+        b'\r' | b'\n' => Decoded::Event(key(Key::Enter, KeyModifiers::NONE), 1),
+        b'\t' => Decoded::Event(key(Key::Tab, KeyModifiers::NONE), 1),
+        0x7f | 0x08 => Decoded::Event(key(Key::Backspace, KeyModifiers::NONE), 1),
+        0x00 => Decoded::Event(key(Key::Char(' '), KeyModifiers::CONTROL), 1),
+        b @ 0x01..=0x1a => Decoded::Event(
+            key(Key::Char((b - 0x01 + b'a') as char), KeyModifiers::CONTROL),
+            1,
+        ),
+        b @ 0x1c..=0x1f => Decoded::Event(
+            key(Key::Char((b - 0x1c + b'4') as char), KeyModifiers::CONTROL),
+            1,
+        ),
+        _ => decode_char(bytes, flush),
+    }
+}
+
+/// Decode a UTF-8 character
+fn decode_char<M>(bytes: &[u8], flush: bool) -> Decoded<M> {
+    let len = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Decoded::Skip(1), // Stray continuation byte
+    };
+    if bytes.len() < len {
+        return if flush {
+            Decoded::Skip(bytes.len())
+        } else {
+            Decoded::Incomplete
+        };
+    }
+
+    match std::str::from_utf8(&bytes[..len])
+        .ok()
+        .and_then(|s| s.chars().next())
+    {
+        Some(c) => {
+            let modifiers = if c.is_uppercase() {
+                KeyModifiers::SHIFT
+            } else {
+                KeyModifiers::NONE
+            };
+            Decoded::Event(key(Key::Char(c), modifiers), len)
+        }
+        None => Decoded::Skip(1),
+    }
+}
+
+/// Decode input starting with ESC
+fn decode_escape<M>(bytes: &[u8], flush: bool) -> Decoded<M> {
+    match bytes.get(1) {
+        None => Decoded::Incomplete,
+        Some(b'[') => decode_csi(bytes, flush),
+        Some(b'O') => match bytes.get(2) {
+            None => Decoded::Incomplete,
+            Some(&b) => match ss3_key(b) {
+                Some(k) => Decoded::Event(key(k, KeyModifiers::NONE), 3),
+                None => Decoded::Skip(3),
+            },
+        },
+        // A double ESC is Escape followed by whatever the second one starts
+        Some(0x1b) => Decoded::Event(key(Key::Esc, KeyModifiers::NONE), 1),
+        Some(_) => match decode_one::<M>(&bytes[1..], flush) {
+            // ESC before a key means Alt
+            Decoded::Event(Event::Key(k), len) => {
+                Decoded::Event(key(k.key, k.modifiers | KeyModifiers::ALT), len + 1)
+            }
+            Decoded::Event(_, len) | Decoded::Skip(len) => Decoded::Skip(len + 1),
+            Decoded::Incomplete => Decoded::Incomplete,
+        },
+    }
+}
+
+/// Keys sent as `ESC O <byte>`
+fn ss3_key(b: u8) -> Option<Key> {
+    Some(match b {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'E' => Key::KeypadBegin,
+        b'P' => Key::F(1),
+        b'Q' => Key::F(2),
+        b'R' => Key::F(3),
+        b'S' => Key::F(4),
+        b'M' => Key::Enter,
+        _ => return None,
+    })
+}
+
+/// Decode a control sequence, `ESC [ <params> <intermediates> <final>`
+fn decode_csi<M>(bytes: &[u8], flush: bool) -> Decoded<M> {
+    if bytes.starts_with(PASTE_START) {
+        return decode_paste(bytes, flush);
+    }
+
+    // Legacy X10 mouse reports carry three raw bytes after `ESC [M`
+    if bytes.get(2) == Some(&b'M') {
+        return match bytes.get(3..6) {
+            Some(report) => decode_x10_mouse(report),
+            None => Decoded::Incomplete,
+        };
+    }
+
+    let mut end = 2;
+    loop {
+        match bytes.get(end) {
+            None => return Decoded::Incomplete,
+            Some(0x20..=0x3f) => end += 1,
+            Some(0x40..=0x7e) => break,
+            // Not a valid sequence, drop what we have so far
+            Some(_) => return Decoded::Skip(end),
+        }
+    }
+    let len = end + 1;
+    let final_byte = bytes[end];
+    let params = match std::str::from_utf8(&bytes[2..end]) {
+        Ok(params) => params,
+        Err(_) => return Decoded::Skip(len),
+    };
+
+    if let Some(sgr) = params.strip_prefix('<') {
+        return match decode_sgr_mouse(sgr, final_byte) {
+            Some(event) => Decoded::Event(event, len),
+            None => Decoded::Skip(len),
+        };
+    }
+
+    let event = match (final_byte, params) {
+        (b'I', "") => Some(Event::Focus),
+        (b'O', "") => Some(Event::Blur),
+        _ => csi_key(final_byte, params).map(|(k, m)| key(k, m)),
+    };
+    match event {
+        Some(event) => Decoded::Event(event, len),
+        None => Decoded::Skip(len),
+    }
+}
+
+/// Decode the key in a CSI sequence
+fn csi_key(final_byte: u8, params: &str) -> Option<(Key, KeyModifiers)> {
+    let mut fields = params.split(';');
+    let first = fields.next().unwrap_or("");
+    // Kitty reports can add alternate keys and event types after colons
+    let code: Option<u32> = first.split(':').next().and_then(|s| s.parse().ok());
+    let modifiers = fields
+        .next()
+        .and_then(|s| s.split(':').next())
+        .and_then(|s| s.parse().ok())
+        .map(modifiers_from_param)
+        .unwrap_or(KeyModifiers::NONE);
+
+    let key = match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'E' => Key::KeypadBegin,
+        b'P' => Key::F(1),
+        b'Q' => Key::F(2),
+        b'R' => Key::F(3),
+        b'S' => Key::F(4),
+        b'Z' => return Some((Key::Tab, KeyModifiers::SHIFT | modifiers)),
+        b'~' => match code? {
+            1 | 7 => Key::Home,
+            2 => Key::Insert,
+            3 => Key::Delete,
+            4 | 8 => Key::End,
+            5 => Key::PageUp,
+            6 => Key::PageDown,
+            n @ 11..=15 => Key::F((n - 10) as u8),
+            n @ 17..=21 => Key::F((n - 11) as u8),
+            n @ 23..=26 => Key::F((n - 12) as u8),
+            n @ 28..=29 => Key::F((n - 13) as u8),
+            n @ 31..=34 => Key::F((n - 14) as u8),
+            _ => return None,
+        },
+        b'u' => match code? {
+            9 => Key::Tab,
+            13 => Key::Enter,
+            27 => Key::Esc,
+            127 => Key::Backspace,
+            n => Key::Char(char::from_u32(n)?),
+        },
+        _ => return None,
+    };
+    Some((key, modifiers))
+}
+
+/// Convert an xterm modifier parameter (1 + bitmask) into modifiers
+fn modifiers_from_param(param: u8) -> KeyModifiers {
+    let mask = param.saturating_sub(1);
+    let mut modifiers = KeyModifiers::NONE;
+    if mask & 1 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if mask & 2 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if mask & 4 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+    if mask & 8 != 0 {
+        modifiers |= KeyModifiers::SUPER;
+    }
+    if mask & 16 != 0 {
+        modifiers |= KeyModifiers::HYPER;
+    }
+    if mask & 32 != 0 {
+        modifiers |= KeyModifiers::META;
+    }
+    modifiers
+}
+
+/// Decode everything between the paste markers as one event
+fn decode_paste<M>(bytes: &[u8], flush: bool) -> Decoded<M> {
+    let body = &bytes[PASTE_START.len()..];
+    match body.windows(PASTE_END.len()).position(|w| w == PASTE_END) {
+        Some(end) => Decoded::Event(
+            paste_event(&body[..end]),
+            PASTE_START.len() + end + PASTE_END.len(),
+        ),
+        None if flush => Decoded::Event(paste_event(body), bytes.len()),
+        None => Decoded::Incomplete,
+    }
+}
+
+fn paste_event<M>(text: &[u8]) -> Event<M> {
+    // Terminals send newlines as carriage returns
+    let text = String::from_utf8_lossy(text)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+    Event::Paste(text)
+}
+
+/// Decode the button byte shared by SGR and X10 mouse reports
+fn mouse_kind(code: u16, release: bool) -> Option<(MouseEventKind, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    if code & 4 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if code & 8 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if code & 16 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    let button = match code & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let kind = if code & 64 != 0 {
+        match code & 3 {
+            0 => MouseEventKind::ScrollUp,
+            1 => MouseEventKind::ScrollDown,
+            2 => MouseEventKind::ScrollLeft,
+            _ => MouseEventKind::ScrollRight,
+        }
+    } else if code & 32 != 0 {
+        match button {
+            Some(button) => MouseEventKind::Drag(button),
+            None => MouseEventKind::Moved,
+        }
+    } else if release {
+        // X10 reports don't say which button was released
+        MouseEventKind::Up(button.unwrap_or(MouseButton::Left))
+    } else {
+        MouseEventKind::Down(button?)
+    };
+    Some((kind, modifiers))
+}
+
+/// Decode an SGR mouse report, `ESC [< button ; column ; row (M|m)`
+fn decode_sgr_mouse<M>(params: &str, final_byte: u8) -> Option<Event<M>> {
+    let mut fields = params.split(';').map(|s| s.parse::<u16>().ok());
+    let code = fields.next()??;
+    let column = fields.next()??;
+    let row = fields.next()??;
+    let release = match final_byte {
+        b'M' => false,
+        b'm' => true,
+        _ => return None,
+    };
+
+    let (kind, modifiers) = mouse_kind(code, release)?;
+    Some(Event::Mouse(MouseEvent::new(
+        kind,
+        column.saturating_sub(1),
+        row.saturating_sub(1),
+        modifiers,
+    )))
+}
+
+/// Decode a legacy X10 mouse report, `ESC [M` followed by three raw bytes
+fn decode_x10_mouse<M>(report: &[u8]) -> Decoded<M> {
+    let code = u16::from(report[0].saturating_sub(32));
+    let column = u16::from(report[1].saturating_sub(33));
+    let row = u16::from(report[2].saturating_sub(33));
+    let release = code & 3 == 3 && code & 64 == 0 && code & 32 == 0;
+
+    match mouse_kind(code, release) {
+        Some((kind, modifiers)) => Decoded::Event(
+            Event::Mouse(MouseEvent::new(kind, column, row, modifiers)),
+            6,
+        ),
+        None => Decoded::Skip(6),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> Vec<Event<()>> {
+        InputDecoder::new().feed(bytes)
+    }
+
+    fn single(bytes: &[u8]) -> Event<()> {
+        let mut events = decode(bytes);
+        assert_eq!(events.len(), 1, "{bytes:?} decoded to {events:?}");
+        events.remove(0)
+    }
+
+    fn key_event(k: Key, modifiers: KeyModifiers) -> Event<()> {
+        key(k, modifiers)
+    }
+
+    #[test]
+    fn test_decodes_chars_and_control_keys() {
+        assert_eq!(
+            decode(b"a\r\t\x7f\x03"),
+            vec![
+                key_event(Key::Char('a'), KeyModifiers::NONE),
+                key_event(Key::Enter, KeyModifiers::NONE),
+                key_event(Key::Tab, KeyModifiers::NONE),
+                key_event(Key::Backspace, KeyModifiers::NONE),
+                key_event(Key::Char('c'), KeyModifiers::CONTROL),
+            ]
+        );
+        assert_eq!(
+            single("é".as_bytes()),
+            key_event(Key::Char('é'), KeyModifiers::NONE)
+        );
+        assert_eq!(single(b"Q"), key_event(Key::Char('Q'), KeyModifiers::SHIFT));
+    }
+
+    #[test]
+    fn test_csi_and_ss3_keys() {
+        let cases: &[(&[u8], Key)] = &[
+            (b"\x1b[A", Key::Up),
+            (b"\x1b[D", Key::Left),
+            (b"\x1bOB", Key::Down),
+            (b"\x1bOH", Key::Home),
+            (b"\x1b[4~", Key::End),
+            (b"\x1b[3~", Key::Delete),
+            (b"\x1b[5~", Key::PageUp),
+            (b"\x1bOP", Key::F(1)),
+            (b"\x1b[15~", Key::F(5)),
+            (b"\x1b[24~", Key::F(12)),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(
+                single(bytes),
+                key_event(*expected, KeyModifiers::NONE),
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_modifier_parameters() {
+        assert_eq!(
+            single(b"\x1b[1;5A"),
+            key_event(Key::Up, KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            single(b"\x1b[1;2P"),
+            key_event(Key::F(1), KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            single(b"\x1b[3;7~"),
+            key_event(Key::Delete, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(single(b"\x1b[Z"), key_event(Key::Tab, KeyModifiers::SHIFT));
+        assert_eq!(
+            single(b"\x1b[97;5u"),
+            key_event(Key::Char('a'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            single(b"\x1bx"),
+            key_event(Key::Char('x'), KeyModifiers::ALT)
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse() {
+        assert_eq!(
+            single(b"\x1b[<0;10;5M"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Down(MouseButton::Left),
+                9,
+                4,
+                KeyModifiers::NONE
+            ))
+        );
+        assert_eq!(
+            single(b"\x1b[<2;1;1m"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Up(MouseButton::Right),
+                0,
+                0,
+                KeyModifiers::NONE
+            ))
+        );
+        assert_eq!(
+            single(b"\x1b[<32;3;4M"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Drag(MouseButton::Left),
+                2,
+                3,
+                KeyModifiers::NONE
+            ))
+        );
+        assert_eq!(
+            single(b"\x1b[<35;3;4M"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Moved,
+                2,
+                3,
+                KeyModifiers::NONE
+            ))
+        );
+        assert_eq!(
+            single(b"\x1b[<81;1;1M"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::ScrollDown,
+                0,
+                0,
+                KeyModifiers::CONTROL
+            ))
+        );
+    }
+
+    #[test]
+    fn test_x10_mouse() {
+        assert_eq!(
+            single(b"\x1b[M !!"),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Down(MouseButton::Left),
+                0,
+                0,
+                KeyModifiers::NONE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_paste_and_focus() {
+        assert_eq!(
+            decode(b"\x1b[I\x1b[200~one\r\x1b[Atwo\x1b[201~\x1b[O"),
+            vec![
+                Event::Focus,
+                Event::Paste("one\n\x1b[Atwo".to_string()),
+                Event::Blur,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sequences_split_across_reads() {
+        let input: &[u8] = b"\x1b[1;5A\x1b[<0;10;5M\x1b[200~hi\x1b[201~\xc3\xa9";
+        let whole = decode(input);
+        assert_eq!(whole.len(), 4);
+
+        // Byte at a time must give the same events
+        let mut decoder = InputDecoder::new();
+        let mut split = Vec::new();
+        for byte in input {
+            split.extend(decoder.feed::<()>(&[*byte]));
+        }
+        assert_eq!(split, whole);
+        assert!(!decoder.is_waiting());
+    }
+
+    #[test]
+    fn test_lone_escape_waits_for_flush() {
+        let mut decoder = InputDecoder::new();
+        assert!(decoder.feed::<()>(b"\x1b").is_empty());
+        assert!(decoder.is_waiting());
+        assert_eq!(
+            decoder.flush::<()>(),
+            vec![key_event(Key::Esc, KeyModifiers::NONE)]
+        );
+        assert!(!decoder.is_waiting());
+
+        // An unfinished sequence is Escape followed by ordinary keys
+        assert!(decoder.feed::<()>(b"\x1b[").is_empty());
+        assert_eq!(
+            decoder.flush::<()>(),
+            vec![
+                key_event(Key::Esc, KeyModifiers::NONE),
+                key_event(Key::Char('['), KeyModifiers::NONE),
+            ]
+        );
+
+        // A paste in progress isn't subject to the timeout
+        assert!(decoder.feed::<()>(b"\x1b[200~abc").is_empty());
+        assert!(!decoder.is_waiting());
+        assert_eq!(decoder.flush::<()>(), vec![Event::Paste("abc".to_string())]);
+    }
+
+    #[test]
+    fn test_esc_timeout_is_configurable() {
+        assert_eq!(InputDecoder::new().esc_timeout(), DEFAULT_ESC_TIMEOUT);
+        let decoder = InputDecoder::with_esc_timeout(Duration::from_millis(5));
+        assert_eq!(decoder.esc_timeout(), Duration::from_millis(5));
+    }
+}
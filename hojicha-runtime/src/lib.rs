@@ -11,6 +11,7 @@
 //! - **Event Processing**: Priority-based event handling with backpressure
 //! - **Async Support**: Tokio-based async command execution
 //! - **Subscriptions**: Stream-based event sources
+//! - **Middleware**: Events before `update` and commands after it
 //! - **Error Resilience**: Panic recovery and error handling
 //!
 //! ## Features
@@ -20,6 +21,7 @@
 //! - Safe mutex operations that recover from poison
 //! - Metrics and monitoring support
 //! - Terminal management and restoration
+//! - OS signal handling (SIGTERM, SIGHUP, SIGINT, SIGWINCH)
 
 #![warn(missing_docs)]
 
@@ -27,7 +29,11 @@
 
 // Program and runtime
 pub mod program;
-pub use program::{MouseMode, Program, ProgramOptions};
+pub use program::{MouseMode, Program, ProgramHandle, ProgramOptions, SendError};
+
+// Middleware around update
+pub mod middleware;
+pub use middleware::Middleware;
 
 // Async support
 pub mod async_handle;
@@ -44,12 +50,14 @@ pub mod safe_priority;
 pub mod testing;
 
 // Error resilience
+pub mod input_decoder;
 pub mod panic_handler;
 pub mod panic_recovery;
 pub mod panic_utils;
 pub mod resilient_input;
 pub mod resource_limits;
 pub mod safe_mutex;
+pub mod signals;
 
 // Re-export from core
 pub use hojicha_core::event::{Event, Key, KeyEvent, KeyModifiers, MouseEvent, WindowSize};
@@ -60,8 +68,8 @@ pub use subscription::Subscription;
 
 // Re-export program components
 pub use program::{
-    CommandExecutor, EventProcessor, EventStats, FpsLimiter, PriorityConfig,
-    PriorityEventProcessor, TerminalConfig, TerminalManager,
+    CommandExecutor, DynBackend, EventProcessor, EventStats, FpsLimiter, ManagedBackend,
+    PriorityConfig, PriorityEventProcessor, RenderScheduler, TerminalConfig, TerminalManager,
 };
 
 /// Prelude for convenient imports
@@ -79,6 +87,7 @@ pub use program::{
 /// - [`Program`] - The main application runtime
 /// - [`ProgramOptions`] - Configuration for the program
 /// - [`MouseMode`] - Mouse tracking options
+/// - [`ProgramHandle`] - Send messages to a running program from anywhere
 /// 
 /// ### Async Support
 /// - [`AsyncHandle`] - Handle for cancellable async operations
@@ -90,7 +99,7 @@ pub use program::{
 /// - [`delayed_stream()`] - Create delayed streams
 pub mod prelude {
     // Program and configuration
-    pub use crate::program::{MouseMode, Program, ProgramOptions};
+    pub use crate::program::{MouseMode, Program, ProgramHandle, ProgramOptions};
     
     // Async support
     pub use crate::async_handle::AsyncHandle;
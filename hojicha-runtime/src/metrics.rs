@@ -28,6 +28,10 @@ pub struct AdvancedEventStats {
 
     /// Time-windowed statistics
     pub windows: WindowedStats,
+
+    /// Frame rendering statistics
+    #[serde(default)]
+    pub render: RenderStats,
 }
 
 /// Basic event statistics
@@ -47,6 +51,18 @@ pub struct BasicStats {
     pub backpressure_activations: usize,
 }
 
+/// Frame rendering statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RenderStats {
+    /// Number of frames drawn
+    pub frames_rendered: usize,
+    /// Number of times the loop woke up but skipped drawing because nothing
+    /// had changed
+    pub frames_skipped: usize,
+    /// Frames drawn during the last second
+    pub current_fps: f64,
+}
+
 /// Latency statistics with percentiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LatencyStats {
@@ -298,6 +314,10 @@ pub struct MetricsCollector {
     // Windowed stats
     minute_buckets: Arc<Mutex<Vec<BucketStats>>>,
     hour_buckets: Arc<Mutex<Vec<BucketStats>>>,
+
+    // Render tracking
+    render: Arc<Mutex<RenderStats>>,
+    frame_times: Arc<Mutex<Vec<Instant>>>,
 }
 
 impl MetricsCollector {
@@ -323,6 +343,8 @@ impl MetricsCollector {
             last_capacity_check: Arc::new(Mutex::new(Instant::now())),
             minute_buckets: Arc::new(Mutex::new(Vec::new())),
             hour_buckets: Arc::new(Mutex::new(Vec::new())),
+            render: Arc::new(Mutex::new(RenderStats::default())),
+            frame_times: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -430,6 +452,21 @@ impl MetricsCollector {
         self.basic.lock().unwrap().backpressure_activations += 1;
     }
 
+    /// Record a frame being drawn
+    pub fn record_frame(&self) {
+        let now = Instant::now();
+        self.render.lock().unwrap().frames_rendered += 1;
+
+        let mut frame_times = self.frame_times.lock().unwrap();
+        frame_times.push(now);
+        frame_times.retain(|t| now.duration_since(*t) < Duration::from_secs(1));
+    }
+
+    /// Record a frame skipped because nothing had changed
+    pub fn record_skipped_frame(&self) {
+        self.render.lock().unwrap().frames_skipped += 1;
+    }
+
     /// Update queue depth
     pub fn update_queue_depth(&self, depth: usize, capacity: usize) {
         let now = Instant::now();
@@ -557,12 +594,24 @@ impl MetricsCollector {
             }
         };
 
+        let render = {
+            let frame_times = self.frame_times.lock().unwrap();
+            RenderStats {
+                current_fps: frame_times
+                    .iter()
+                    .filter(|t| now.duration_since(**t) < Duration::from_secs(1))
+                    .count() as f64,
+                ..self.render.lock().unwrap().clone()
+            }
+        };
+
         AdvancedEventStats {
             basic: self.basic.lock().unwrap().clone(),
             latency,
             throughput,
             queue,
             windows: WindowedStats::default(), // Simplified for now
+            render,
         }
     }
 
@@ -582,6 +631,8 @@ impl MetricsCollector {
         *self.time_at_capacity.lock().unwrap() = Duration::ZERO;
         self.minute_buckets.lock().unwrap().clear();
         self.hour_buckets.lock().unwrap().clear();
+        *self.render.lock().unwrap() = RenderStats::default();
+        self.frame_times.lock().unwrap().clear();
     }
 
     /// Export metrics in JSON format
@@ -718,6 +769,18 @@ impl AdvancedEventStats {
             self.queue.saturation_percentage
         ));
 
+        // Render metrics
+        output.push_str("# HELP hojicha_frames_total Frames drawn or skipped\n");
+        output.push_str("# TYPE hojicha_frames_total counter\n");
+        output.push_str(&format!(
+            "hojicha_frames_total {{state=\"rendered\"}} {}\n",
+            self.render.frames_rendered
+        ));
+        output.push_str(&format!(
+            "hojicha_frames_total {{state=\"skipped\"}} {}\n",
+            self.render.frames_skipped
+        ));
+
         output
     }
 
@@ -742,7 +805,11 @@ impl AdvancedEventStats {
             - Current Depth: {}\n\
             - Max Depth: {}\n\
             - Saturation: {:.1}%\n\
-            - Growth Rate: {:.1} events/sec",
+            - Growth Rate: {:.1} events/sec\n\n\
+            Rendering:\n\
+            - Frames Rendered: {}\n\
+            - Frames Skipped: {}\n\
+            - Current FPS: {:.1}",
             self.basic.total_events,
             self.basic.high_priority_events,
             self.basic.normal_priority_events,
@@ -767,6 +834,9 @@ impl AdvancedEventStats {
             self.queue.max_depth,
             self.queue.saturation_percentage,
             self.queue.growth_rate,
+            self.render.frames_rendered,
+            self.render.frames_skipped,
+            self.render.current_fps,
         )
     }
 }
@@ -0,0 +1,709 @@
+//! Middleware that sees events before `update` and commands after it
+//!
+//! A [`Program`](crate::Program) runs an ordered stack of [`Middleware`],
+//! added with [`Program::with_middleware`](crate::Program::with_middleware).
+//! Every event that would reach the model's `update` goes through the stack
+//! first, in the order the middleware was added, and each layer can pass it
+//! on, change it or drop it. The command `update` returns then goes back
+//! through the stack in reverse order, so the first middleware added is the
+//! outermost one and sees the final command.
+//!
+//! Runtime events (quit, exec, terminal control and command errors) are
+//! handled by the program and never reach the middleware. A panic in a
+//! middleware is handled like one in `update`, following the program's
+//! [`PanicRecoveryStrategy`](crate::panic_recovery::PanicRecoveryStrategy);
+//! the event it was handling is dropped.
+//!
+//! # Built-in middleware
+//!
+//! - [`Filter`] - a closure that can change or drop events, the same as
+//!   [`Program::with_filter`](crate::Program::with_filter)
+//! - [`KeyMap`] - remap keys before the model sees them
+//! - [`Logger`] - log events and commands for auditing
+//! - [`Recorder`] - record user messages in development
+//! - [`Throttle`] - rate-limit noisy messages
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use hojicha_core::{Cmd, Event, Key, Model};
+//! # use hojicha_runtime::middleware::{KeyMap, Logger, Middleware, Context};
+//! # use hojicha_runtime::Program;
+//! # struct App { count: u32 }
+//! # #[derive(Clone, Debug)]
+//! # enum Msg { Reset }
+//! # impl Model for App {
+//! #     type Message = Msg;
+//! #     fn update(&mut self, _: Event<Msg>) -> Cmd<Msg> { Cmd::none() }
+//! #     fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+//! # }
+//! /// Turn the `r` key into a reset message
+//! struct ResetKey;
+//!
+//! impl Middleware<App> for ResetKey {
+//!     fn on_event(
+//!         &mut self,
+//!         event: Event<Msg>,
+//!         _ctx: &mut Context<'_, App>,
+//!     ) -> Option<Event<Msg>> {
+//!         if event.is_key_press(Key::Char('r')) {
+//!             return Some(Event::User(Msg::Reset));
+//!         }
+//!         Some(event)
+//!     }
+//! }
+//!
+//! # fn main() -> hojicha_core::Result<()> {
+//! let program = Program::new(App { count: 0 })?
+//!     .with_middleware(Logger::new())
+//!     .with_middleware(KeyMap::new().bind_key(Key::Char('j'), Key::Down))
+//!     .with_middleware(ResetKey);
+//! # Ok(())
+//! # }
+//! ```
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::{Event, Key, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::{self, Discriminant};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A layer in the program's middleware stack
+///
+/// Both methods pass their input through unchanged by default, so a
+/// middleware only implements the side it cares about.
+pub trait Middleware<M: Model>: Send {
+    /// Handle an event on its way to `update`
+    ///
+    /// Return the event, changed or not, to pass it to the next layer, or
+    /// `None` to drop it.
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        let _ = ctx;
+        Some(event)
+    }
+
+    /// Handle the command `update` returned for an event
+    ///
+    /// Called even when the command is `Cmd::none()`, so a middleware can
+    /// add commands of its own. The model has already been updated.
+    fn on_command(&mut self, cmd: Cmd<M::Message>, ctx: &mut Context<'_, M>) -> Cmd<M::Message> {
+        let _ = ctx;
+        cmd
+    }
+}
+
+/// What a middleware can see and do besides the event or command it handles
+pub struct Context<'a, M: Model> {
+    model: &'a M,
+    injected: &'a mut Vec<Event<M::Message>>,
+    commands: &'a mut Vec<Cmd<M::Message>>,
+}
+
+impl<'a, M: Model> Context<'a, M> {
+    /// The model, as it is before the event or after the update
+    pub fn model(&self) -> &M {
+        self.model
+    }
+
+    /// Queue a new event
+    ///
+    /// Injected events are queued once the current event has been handled
+    /// and go through the whole stack like any other event, so a middleware
+    /// must not inject one for every event it sees.
+    pub fn inject(&mut self, event: Event<M::Message>) {
+        self.injected.push(event);
+    }
+
+    /// Run a command of the middleware's own
+    ///
+    /// Unlike a command returned from `on_command`, it runs even when the
+    /// event is dropped, e.g. to deliver a message later.
+    pub fn command(&mut self, cmd: Cmd<M::Message>) {
+        self.commands.push(cmd);
+    }
+}
+
+/// The ordered middleware of a program
+pub(crate) struct MiddlewareStack<M: Model> {
+    layers: Vec<Box<dyn Middleware<M>>>,
+    injected: Vec<Event<M::Message>>,
+    commands: Vec<Cmd<M::Message>>,
+}
+
+impl<M: Model> MiddlewareStack<M> {
+    pub(crate) fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            injected: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, middleware: Box<dyn Middleware<M>>) {
+        self.layers.push(middleware);
+    }
+
+    /// Run an event through every layer in order, stopping if one drops it
+    pub(crate) fn on_event(
+        &mut self,
+        model: &M,
+        event: Event<M::Message>,
+    ) -> Option<Event<M::Message>> {
+        let mut ctx = Context {
+            model,
+            injected: &mut self.injected,
+            commands: &mut self.commands,
+        };
+        self.layers
+            .iter_mut()
+            .try_fold(event, |event, layer| layer.on_event(event, &mut ctx))
+    }
+
+    /// Run a command through every layer in reverse order
+    pub(crate) fn on_command(&mut self, model: &M, cmd: Cmd<M::Message>) -> Cmd<M::Message> {
+        let mut ctx = Context {
+            model,
+            injected: &mut self.injected,
+            commands: &mut self.commands,
+        };
+        self.layers
+            .iter_mut()
+            .rev()
+            .fold(cmd, |cmd, layer| layer.on_command(cmd, &mut ctx))
+    }
+
+    /// Take the events injected since the last call
+    pub(crate) fn take_injected(&mut self) -> Vec<Event<M::Message>> {
+        mem::take(&mut self.injected)
+    }
+
+    /// Take the commands the middleware asked to run since the last call
+    pub(crate) fn take_commands(&mut self) -> Vec<Cmd<M::Message>> {
+        mem::take(&mut self.commands)
+    }
+}
+
+/// Type alias for the closure of a [`Filter`]
+type FilterFn<M> =
+    dyn Fn(&M, Event<<M as Model>::Message>) -> Option<Event<<M as Model>::Message>> + Send + Sync;
+
+/// Change or drop events with a closure
+///
+/// This is what [`Program::with_filter`](crate::Program::with_filter) adds.
+pub struct Filter<M: Model> {
+    filter: Box<FilterFn<M>>,
+}
+
+impl<M: Model> Filter<M> {
+    /// Create a filter from a closure that gets the model and the event
+    pub fn new<F>(filter: F) -> Self
+    where
+        F: Fn(&M, Event<M::Message>) -> Option<Event<M::Message>> + Send + Sync + 'static,
+    {
+        Self {
+            filter: Box::new(filter),
+        }
+    }
+}
+
+impl<M: Model> Middleware<M> for Filter<M> {
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        (self.filter)(ctx.model(), event)
+    }
+}
+
+/// Remap keys before the model sees them
+///
+/// A binding matches the key and its exact modifiers. Keys without a binding
+/// pass through unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: HashMap<(Key, KeyModifiers), KeyEvent>,
+}
+
+impl KeyMap {
+    /// Create an empty key map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver `to` whenever `from` is pressed
+    pub fn bind(mut self, from: KeyEvent, to: KeyEvent) -> Self {
+        self.bindings.insert((from.key, from.modifiers), to);
+        self
+    }
+
+    /// Deliver `to` whenever `from` is pressed without modifiers
+    pub fn bind_key(self, from: Key, to: Key) -> Self {
+        self.bind(
+            KeyEvent::new(from, KeyModifiers::empty()),
+            KeyEvent::new(to, KeyModifiers::empty()),
+        )
+    }
+
+    /// Get the key a key event is remapped to, if it has a binding
+    pub fn get(&self, key: &KeyEvent) -> Option<KeyEvent> {
+        self.bindings.get(&(key.key, key.modifiers)).copied()
+    }
+}
+
+impl<M: Model> Middleware<M> for KeyMap {
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        _ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        match event {
+            Event::Key(key) => Some(Event::Key(self.get(&key).unwrap_or(key))),
+            event => Some(event),
+        }
+    }
+}
+
+/// Log every event and command
+///
+/// Events are logged with their `Debug` output and commands with
+/// [`Cmd::debug_name`], under the `hojicha::middleware` target. Add it first
+/// to log events as they arrive and commands as they leave the stack.
+#[derive(Debug, Clone)]
+pub struct Logger {
+    level: log::Level,
+}
+
+impl Logger {
+    /// Log at the debug level
+    pub fn new() -> Self {
+        Self::with_level(log::Level::Debug)
+    }
+
+    /// Log at the given level
+    pub fn with_level(level: log::Level) -> Self {
+        Self { level }
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Model> Middleware<M> for Logger
+where
+    M::Message: fmt::Debug,
+{
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        _ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        log::log!(target: "hojicha::middleware", self.level, "event: {:?}", event);
+        Some(event)
+    }
+
+    fn on_command(&mut self, cmd: Cmd<M::Message>, _ctx: &mut Context<'_, M>) -> Cmd<M::Message> {
+        if !cmd.is_noop() {
+            log::log!(target: "hojicha::middleware", self.level, "command: {}", cmd.debug_name());
+        }
+        cmd
+    }
+}
+
+/// Type alias for the messages kept by a [`Recorder`]
+type RecordedMessages<T> = Arc<Mutex<Vec<(Duration, T)>>>;
+
+/// Record the user messages the model receives
+///
+/// Each message is stored with the time since the recorder was created. Get
+/// a [`Recording`] before adding the recorder to the program to read them.
+pub struct Recorder<T> {
+    start: Instant,
+    messages: RecordedMessages<T>,
+}
+
+impl<T> Recorder<T> {
+    /// Create a recorder that starts its clock now
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            messages: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Get a handle to the recorded messages
+    pub fn recording(&self) -> Recording<T> {
+        Recording {
+            messages: Arc::clone(&self.messages),
+        }
+    }
+}
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Model> Middleware<M> for Recorder<M::Message>
+where
+    M::Message: Clone,
+{
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        _ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        if let Event::User(msg) = &event {
+            let mut messages = self.messages.lock().unwrap_or_else(|e| e.into_inner());
+            messages.push((self.start.elapsed(), msg.clone()));
+        }
+        Some(event)
+    }
+}
+
+/// The messages seen by a [`Recorder`], readable while the program runs
+pub struct Recording<T> {
+    messages: RecordedMessages<T>,
+}
+
+impl<T: Clone> Recording<T> {
+    /// Get the recorded messages with the time each one arrived
+    pub fn messages(&self) -> Vec<(Duration, T)> {
+        self.lock().clone()
+    }
+
+    /// Take the recorded messages, leaving the recording empty
+    pub fn take(&self) -> Vec<(Duration, T)> {
+        mem::take(&mut *self.lock())
+    }
+
+    /// Get the number of recorded messages
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no messages have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(Duration, T)>> {
+        self.messages.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T> Clone for Recording<T> {
+    fn clone(&self) -> Self {
+        Self {
+            messages: Arc::clone(&self.messages),
+        }
+    }
+}
+
+/// Type alias for the message selector of a [`Throttle`]
+type ThrottleSelector<T> = dyn Fn(&T) -> bool + Send;
+
+/// Type alias for the messages a [`Throttle`] holds back, by variant
+type HeldBack<T> = Arc<Mutex<HashMap<Discriminant<T>, T>>>;
+
+/// Let through at most one message of each variant per interval
+///
+/// Only user messages for which the selector returns `true` are throttled.
+/// Each enum variant is limited on its own: the first message passes and the
+/// same variant is held back until `interval` has passed. The last message
+/// held back is then delivered, so the model always ends up with the latest
+/// one.
+pub struct Throttle<T> {
+    interval: Duration,
+    selector: Box<ThrottleSelector<T>>,
+    last_sent: HashMap<Discriminant<T>, Instant>,
+    held_back: HeldBack<T>,
+}
+
+impl<T> Throttle<T> {
+    /// Throttle the messages matched by `selector`
+    pub fn new<F>(interval: Duration, selector: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + 'static,
+    {
+        Self {
+            interval,
+            selector: Box::new(selector),
+            last_sent: HashMap::new(),
+            held_back: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Check whether a message may pass now, recording it if so
+    ///
+    /// Returns how long is left of the interval if it may not.
+    fn allow(&mut self, msg: &T) -> Result<(), Duration> {
+        if !(self.selector)(msg) {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let variant = mem::discriminant(msg);
+        match self.last_sent.get(&variant) {
+            Some(last) if now.duration_since(*last) < self.interval => {
+                Err(self.interval - now.duration_since(*last))
+            }
+            _ => {
+                self.last_sent.insert(variant, now);
+                // Anything held back is older than this message
+                self.lock().remove(&variant);
+                Ok(())
+            }
+        }
+    }
+
+    /// Hold a message back, returning whether it is the first one of its
+    /// variant since the last that passed
+    fn hold_back(&self, msg: T) -> bool {
+        self.lock().insert(mem::discriminant(&msg), msg).is_none()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Discriminant<T>, T>> {
+        self.held_back.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<M: Model> Middleware<M> for Throttle<M::Message> {
+    fn on_event(
+        &mut self,
+        event: Event<M::Message>,
+        ctx: &mut Context<'_, M>,
+    ) -> Option<Event<M::Message>> {
+        let Event::User(msg) = event else {
+            return Some(event);
+        };
+        let Err(left) = self.allow(&msg) else {
+            return Some(Event::User(msg));
+        };
+
+        // Deliver the latest held back message once the interval is over
+        let variant = mem::discriminant(&msg);
+        if self.hold_back(msg) {
+            let held_back = Arc::clone(&self.held_back);
+            ctx.command(commands::spawn(async move {
+                tokio::time::sleep(left).await;
+                let mut held_back = held_back.lock().unwrap_or_else(|e| e.into_inner());
+                held_back.remove(&variant)
+            }));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, Frame};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Progress(u32),
+        Done,
+    }
+
+    struct App {
+        count: u32,
+    }
+
+    impl Model for App {
+        type Message = Msg;
+
+        fn update(&mut self, _event: Event<Msg>) -> Cmd<Msg> {
+            Cmd::none()
+        }
+
+        fn view(&self, _frame: &mut Frame, _area: Rect) {}
+    }
+
+    /// Logs the events and commands that pass through it
+    struct Tag {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware<App> for Tag {
+        fn on_event(
+            &mut self,
+            event: Event<Msg>,
+            ctx: &mut Context<'_, App>,
+        ) -> Option<Event<Msg>> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("event {} {}", self.name, ctx.model().count));
+            Some(event)
+        }
+
+        fn on_command(&mut self, cmd: Cmd<Msg>, _ctx: &mut Context<'_, App>) -> Cmd<Msg> {
+            self.log.lock().unwrap().push(format!("cmd {}", self.name));
+            cmd
+        }
+    }
+
+    fn key(c: char) -> Event<Msg> {
+        Event::Key(KeyEvent::new(Key::Char(c), KeyModifiers::empty()))
+    }
+
+    #[test]
+    fn test_stack_runs_events_in_order_and_commands_in_reverse() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut stack = MiddlewareStack::new();
+        for name in ["a", "b"] {
+            stack.push(Box::new(Tag {
+                name,
+                log: Arc::clone(&log),
+            }));
+        }
+        let app = App { count: 7 };
+
+        assert_eq!(stack.on_event(&app, Event::Tick), Some(Event::Tick));
+        assert!(stack.on_command(&app, Cmd::none()).is_noop());
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["event a 7", "event b 7", "cmd b", "cmd a"]
+        );
+    }
+
+    #[test]
+    fn test_dropped_events_skip_later_layers() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(Filter::new(|_: &App, event: Event<Msg>| {
+            (!event.is_key_press(Key::Char('x'))).then_some(event)
+        })));
+        stack.push(Box::new(Tag {
+            name: "a",
+            log: Arc::clone(&log),
+        }));
+
+        assert_eq!(stack.on_event(&App { count: 0 }, key('x')), None);
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_injected_events_are_collected() {
+        struct Echo;
+        impl Middleware<App> for Echo {
+            fn on_event(
+                &mut self,
+                event: Event<Msg>,
+                ctx: &mut Context<'_, App>,
+            ) -> Option<Event<Msg>> {
+                if event == Event::User(Msg::Done) {
+                    ctx.inject(Event::Tick);
+                }
+                Some(event)
+            }
+        }
+
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(Echo));
+        let app = App { count: 0 };
+        stack.on_event(&app, Event::User(Msg::Done));
+        stack.on_event(&app, Event::User(Msg::Progress(1)));
+
+        assert_eq!(stack.take_injected(), vec![Event::Tick]);
+        assert!(stack.take_injected().is_empty());
+    }
+
+    #[test]
+    fn test_key_map_remaps_exact_bindings() {
+        let ctrl_n = KeyEvent::new(Key::Char('n'), KeyModifiers::CONTROL);
+        let down = KeyEvent::new(Key::Down, KeyModifiers::empty());
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(
+            KeyMap::new()
+                .bind_key(Key::Char('j'), Key::Down)
+                .bind(ctrl_n, down),
+        ));
+        let app = App { count: 0 };
+
+        assert_eq!(stack.on_event(&app, key('j')), Some(Event::Key(down)));
+        assert_eq!(
+            stack.on_event(&app, Event::Key(ctrl_n)),
+            Some(Event::Key(down))
+        );
+        assert_eq!(stack.on_event(&app, key('n')), Some(key('n')));
+        assert_eq!(stack.on_event(&app, Event::Tick), Some(Event::Tick));
+    }
+
+    #[test]
+    fn test_recorder_keeps_user_messages() {
+        let recorder = Recorder::new();
+        let recording = recorder.recording();
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(recorder));
+        let app = App { count: 0 };
+
+        stack.on_event(&app, Event::User(Msg::Progress(1)));
+        stack.on_event(&app, Event::Tick);
+        stack.on_event(&app, Event::User(Msg::Done));
+
+        let messages: Vec<Msg> = recording.messages().into_iter().map(|(_, m)| m).collect();
+        assert_eq!(messages, vec![Msg::Progress(1), Msg::Done]);
+        assert_eq!(recording.take().len(), 2);
+        assert!(recording.is_empty());
+    }
+
+    #[test]
+    fn test_throttle_delivers_the_last_held_back_message() {
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(Throttle::new(Duration::from_millis(50), |msg| {
+            matches!(msg, Msg::Progress(_))
+        })));
+        let app = App { count: 0 };
+
+        for n in 1..=3 {
+            stack.on_event(&app, Event::User(Msg::Progress(n)));
+        }
+        let mut delayed = stack.take_commands();
+        assert_eq!(delayed.len(), 1);
+
+        let executor = crate::program::CommandExecutor::<Msg>::new().unwrap();
+        let (tx, rx) = std::sync::mpsc::sync_channel(10);
+        executor.execute(delayed.remove(0), tx);
+        let event = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(event, Event::User(Msg::Progress(3)));
+
+        // Once delivered it passes, since the interval is over
+        assert_eq!(stack.on_event(&app, event.clone()), Some(event));
+        assert!(stack.take_commands().is_empty());
+    }
+
+    #[test]
+    fn test_throttle_limits_each_variant() {
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(Throttle::new(Duration::from_secs(60), |msg| {
+            matches!(msg, Msg::Progress(_))
+        })));
+        let app = App { count: 0 };
+        let pass = |stack: &mut MiddlewareStack<App>, msg| {
+            stack.on_event(&app, Event::User(msg)).is_some()
+        };
+
+        assert!(pass(&mut stack, Msg::Progress(1)));
+        assert!(!pass(&mut stack, Msg::Progress(2)));
+        assert!(pass(&mut stack, Msg::Done));
+        assert!(pass(&mut stack, Msg::Done));
+
+        let mut stack = MiddlewareStack::new();
+        stack.push(Box::new(Throttle::new(Duration::ZERO, |_: &Msg| true)));
+        assert!(pass(&mut stack, Msg::Progress(1)));
+        assert!(pass(&mut stack, Msg::Progress(2)));
+    }
+}
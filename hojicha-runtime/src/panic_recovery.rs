@@ -2,21 +2,31 @@
 //!
 //! This module provides safe wrappers for Model trait methods that catch
 //! panics and allow the application to continue running.
+//!
+//! [`Program`](crate::program::Program) keeps a [`PanicRecovery`] that applies
+//! the configured [`PanicRecoveryStrategy`]: `Reset` rebuilds the model with
+//! the factory passed to `Program::with_reset`, and `ShowError` replaces the
+//! view with an error screen until the user dismisses it with Esc or Enter,
+//! or quits with `q`.
 
 use hojicha_core::core::{Cmd, Model};
-use hojicha_core::event::Event;
+use hojicha_core::event::{Event, Key, KeyEvent};
 use ratatui::{Frame, layout::Rect};
+use std::cell::RefCell;
+use std::fmt;
 use std::panic::{self, AssertUnwindSafe};
-use log::error;
+use std::sync::Once;
+use log::{error, warn};
 
 /// Strategy for handling panics in Model methods
 #[derive(Debug, Clone, Copy)]
 pub enum PanicRecoveryStrategy {
     /// Continue with current state (default)
     Continue,
-    /// Reset model to initial state
+    /// Rebuild the model with the factory given to `Program::with_reset`
+    /// and run its `init` again
     Reset,
-    /// Show error screen
+    /// Show an error screen until the user dismisses it
     ShowError,
     /// Quit the application
     Quit,
@@ -28,59 +38,219 @@ impl Default for PanicRecoveryStrategy {
     }
 }
 
-/// Safely call Model::init with panic recovery
-pub fn safe_init<M: Model>(model: &mut M, strategy: PanicRecoveryStrategy) -> Cmd<M::Message> {
-    let result = panic::catch_unwind(AssertUnwindSafe(|| model.init()));
-    
-    match result {
-        Ok(cmd) => cmd,
-        Err(panic_info) => {
-            let msg = format_panic_message(&panic_info, "Model::init");
-            error!("Panic in Model::init: {}", msg);
-            
-            match strategy {
-                PanicRecoveryStrategy::Continue => Cmd::none(),
-                PanicRecoveryStrategy::Reset => Cmd::none(),
-                PanicRecoveryStrategy::ShowError => {
-                    // Could return a command to show error screen
-                    Cmd::none()
-                }
-                PanicRecoveryStrategy::Quit => Cmd::quit(),
+/// A panic caught in one of the model's methods
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+    /// The method that panicked, e.g. `Model::update`
+    pub context: &'static str,
+    /// The panic message
+    pub message: String,
+    /// Where the panic happened, as `file:line:column`
+    pub location: Option<String>,
+}
+
+impl fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{} panicked at {}: {}", self.context, location, self.message),
+            None => write!(f, "{} panicked: {}", self.context, self.message),
+        }
+    }
+}
+
+/// Builds a fresh model for [`PanicRecoveryStrategy::Reset`]
+type ModelFactory<M> = Box<dyn Fn() -> M + Send + Sync>;
+
+/// Applies a [`PanicRecoveryStrategy`] across calls to the model
+///
+/// Resetting needs the model factory and the error screen has to stay up
+/// between frames, so unlike [`safe_update`] this keeps state.
+pub struct PanicRecovery<M: Model> {
+    strategy: PanicRecoveryStrategy,
+    reset: Option<ModelFactory<M>>,
+    /// The panic shown on the error screen
+    error: Option<PanicReport>,
+    /// A panic in `view` that is handled after the frame is drawn
+    view_panic: Option<PanicReport>,
+}
+
+impl<M: Model> PanicRecovery<M> {
+    /// Create a recovery for the given strategy
+    pub fn new(strategy: PanicRecoveryStrategy) -> Self {
+        Self {
+            strategy,
+            reset: None,
+            error: None,
+            view_panic: None,
+        }
+    }
+
+    /// Set the factory used to rebuild the model for [`PanicRecoveryStrategy::Reset`]
+    pub fn set_reset<F>(&mut self, factory: F)
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.reset = Some(Box::new(factory));
+    }
+
+    /// The panic shown on the error screen, if any
+    pub fn error(&self) -> Option<&PanicReport> {
+        self.error.as_ref()
+    }
+
+    /// Close the error screen and show the model's view again
+    pub fn dismiss(&mut self) {
+        self.error = None;
+    }
+
+    /// Call `Model::init`, recovering from a panic
+    pub fn init(&mut self, model: &mut M) -> Cmd<M::Message> {
+        match catch_panic("Model::init", || model.init()) {
+            Ok(cmd) => cmd,
+            Err(report) => self.recover(model, report),
+        }
+    }
+
+    /// Call `Model::update`, recovering from a panic
+    ///
+    /// While the error screen is up it takes the key events: Esc and Enter
+    /// dismiss it, `q` and Ctrl+C quit. Other events still reach the model.
+    pub fn update(&mut self, model: &mut M, event: Event<M::Message>) -> Cmd<M::Message> {
+        self.update_with(model, event, M::update)
+    }
+
+    /// Like [`update`](Self::update), but handle the event with `update`
+    /// instead of `Model::update`
+    pub fn update_with<F>(&mut self, model: &mut M, event: Event<M::Message>, update: F) -> Cmd<M::Message>
+    where
+        F: FnOnce(&mut M, Event<M::Message>) -> Cmd<M::Message>,
+    {
+        if self.error.is_some() {
+            if let Event::Key(key) = &event {
+                return self.error_screen_key(key);
+            }
+        }
+
+        // The event is moved into the closure, so it can't be logged on panic
+        self.call("Model::update", model, |model| update(model, event))
+    }
+
+    /// Call `f` on the model, recovering from a panic
+    ///
+    /// `context` names the method in the panic report.
+    pub fn call<F>(&mut self, context: &'static str, model: &mut M, f: F) -> Cmd<M::Message>
+    where
+        F: FnOnce(&mut M) -> Cmd<M::Message>,
+    {
+        match catch_panic(context, || f(model)) {
+            Ok(cmd) => cmd,
+            Err(report) => self.recover(model, report),
+        }
+    }
+
+    /// Call `Model::view`, or draw the error screen while it is up
+    ///
+    /// A panic that resets or quits is acted on by [`recover_view`](Self::recover_view)
+    /// once the frame is drawn, since the model can't be changed while drawing.
+    pub fn view(&mut self, model: &M, frame: &mut Frame, area: Rect) {
+        if let Some(report) = &self.error {
+            render_panic_error(frame, area, report);
+            return;
+        }
+
+        let Err(report) = catch_panic("Model::view", || model.view(frame, area)) else {
+            return;
+        };
+        error!("{}", report);
+
+        match self.strategy {
+            PanicRecoveryStrategy::ShowError => {
+                render_panic_error(frame, area, &report);
+                self.error = Some(report);
+            }
+            PanicRecoveryStrategy::Continue => render_minimal_error(frame, area),
+            PanicRecoveryStrategy::Reset | PanicRecoveryStrategy::Quit => {
+                render_minimal_error(frame, area);
+                self.view_panic = Some(report);
+            }
+        }
+    }
+
+    /// Reset or quit after a panic in the last [`view`](Self::view)
+    ///
+    /// Returns `None` if there is nothing to do, otherwise the command to run.
+    pub fn recover_view(&mut self, model: &mut M) -> Option<Cmd<M::Message>> {
+        self.view_panic.take()?;
+        match self.strategy {
+            PanicRecoveryStrategy::Reset => Some(self.reset(model)),
+            _ => Some(Cmd::quit()),
+        }
+    }
+
+    /// Apply the strategy to a panic in `init` or `update`
+    fn recover(&mut self, model: &mut M, report: PanicReport) -> Cmd<M::Message> {
+        error!("{}", report);
+
+        match self.strategy {
+            PanicRecoveryStrategy::Continue => Cmd::none(),
+            PanicRecoveryStrategy::Reset => self.reset(model),
+            PanicRecoveryStrategy::ShowError => {
+                self.error = Some(report);
+                Cmd::none()
+            }
+            PanicRecoveryStrategy::Quit => Cmd::quit(),
+        }
+    }
+
+    /// Replace the model with a fresh one and run its init
+    fn reset(&mut self, model: &mut M) -> Cmd<M::Message> {
+        let Some(factory) = &self.reset else {
+            warn!("PanicRecoveryStrategy::Reset needs a model factory, see Program::with_reset");
+            return Cmd::none();
+        };
+
+        *model = factory();
+        // A fresh model that panics right away isn't reset again
+        match catch_panic("Model::init", || model.init()) {
+            Ok(cmd) => cmd,
+            Err(report) => {
+                error!("{} after reset", report);
+                Cmd::none()
+            }
+        }
+    }
+
+    fn error_screen_key(&mut self, key: &KeyEvent) -> Cmd<M::Message> {
+        match key.key {
+            Key::Esc | Key::Enter => {
+                self.dismiss();
+                Cmd::none()
             }
+            Key::Char('q') => Cmd::quit(),
+            Key::Char('c') if key.is_ctrl() => Cmd::quit(),
+            _ => Cmd::none(),
         }
     }
 }
 
+/// Safely call Model::init with panic recovery
+///
+/// This doesn't keep state between calls, so `Reset` and `ShowError` behave
+/// like `Continue`; use [`PanicRecovery`] for those.
+pub fn safe_init<M: Model>(model: &mut M, strategy: PanicRecoveryStrategy) -> Cmd<M::Message> {
+    PanicRecovery::new(strategy).init(model)
+}
+
 /// Safely call Model::update with panic recovery
+///
+/// This doesn't keep state between calls, so `Reset` and `ShowError` behave
+/// like `Continue`; use [`PanicRecovery`] for those.
 pub fn safe_update<M: Model>(
     model: &mut M,
     event: Event<M::Message>,
     strategy: PanicRecoveryStrategy,
 ) -> Cmd<M::Message> {
-    // We can't clone the event, so we need to move it into the closure
-    // This means we can't log the specific event on panic
-    let result = panic::catch_unwind(AssertUnwindSafe(move || model.update(event)));
-    
-    match result {
-        Ok(cmd) => cmd,
-        Err(panic_info) => {
-            let msg = format_panic_message(&panic_info, "Model::update");
-            error!("Panic in Model::update: {}", msg);
-            
-            match strategy {
-                PanicRecoveryStrategy::Continue => Cmd::none(),
-                PanicRecoveryStrategy::Reset => {
-                    // Could trigger a reset command
-                    Cmd::none()
-                }
-                PanicRecoveryStrategy::ShowError => {
-                    // Could return a command to show error screen
-                    Cmd::none()
-                }
-                PanicRecoveryStrategy::Quit => Cmd::quit(),
-            }
-        }
-    }
+    PanicRecovery::new(strategy).update(model, event)
 }
 
 /// Safely call Model::view with panic recovery
@@ -92,61 +262,78 @@ pub fn safe_view<M: Model>(
     area: Rect,
     strategy: PanicRecoveryStrategy,
 ) -> bool {
-    let result = panic::catch_unwind(AssertUnwindSafe(|| model.view(frame, area)));
-    
-    match result {
-        Ok(()) => false, // Success, don't quit
-        Err(panic_info) => {
-            let msg = format_panic_message(&panic_info, "Model::view");
-            error!("Panic in Model::view: {}", msg);
-            
-            // Try to render an error message
-            match strategy {
-                PanicRecoveryStrategy::ShowError => {
-                    render_panic_error(frame, area, &msg);
-                }
-                _ => {
-                    // At minimum, try to render something
-                    render_minimal_error(frame, area);
-                }
+    let mut recovery = PanicRecovery::new(strategy);
+    recovery.view(model, frame, area);
+    recovery.view_panic.is_some() && matches!(strategy, PanicRecoveryStrategy::Quit)
+}
+
+thread_local! {
+    /// Location of the last panic on this thread, recorded by the panic hook
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, turning a panic into a [`PanicReport`]
+fn catch_panic<T>(context: &'static str, f: impl FnOnce() -> T) -> Result<T, PanicReport> {
+    record_panic_locations();
+    PANIC_LOCATION.with(|location| location.borrow_mut().take());
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| PanicReport {
+        context,
+        message: panic_message(payload.as_ref()),
+        location: PANIC_LOCATION.with(|location| location.borrow_mut().take()),
+    })
+}
+
+/// Chain a panic hook that remembers where panics happen
+///
+/// The payload caught by `catch_unwind` has no location, so the hook saves it
+/// for [`catch_panic`]. The previous hook still runs afterwards.
+fn record_panic_locations() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                let location = location.to_string();
+                PANIC_LOCATION.with(|cell| *cell.borrow_mut() = Some(location));
             }
-            
-            // Return true if strategy says to quit
-            matches!(strategy, PanicRecoveryStrategy::Quit)
-        }
-    }
+            previous(info);
+        }));
+    });
 }
 
-/// Format panic information into a readable message
-fn format_panic_message(panic_info: &dyn std::any::Any, context: &str) -> String {
-    if let Some(s) = panic_info.downcast_ref::<String>() {
-        format!("{} panicked: {}", context, s)
-    } else if let Some(s) = panic_info.downcast_ref::<&str>() {
-        format!("{} panicked: {}", context, s)
+/// Get the message out of a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
     } else {
-        format!("{} panicked with unknown error", context)
+        "unknown error".to_string()
     }
 }
 
 /// Render a detailed panic error screen
-fn render_panic_error(frame: &mut Frame, area: Rect, message: &str) {
-    use ratatui::widgets::{Block, Borders, Paragraph};
+fn render_panic_error(frame: &mut Frame, area: Rect, report: &PanicReport) {
+    use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
     use ratatui::style::{Color, Style};
     
+    let location = report.location.as_deref().unwrap_or("unknown location");
     let error_text = format!(
-        "╔══════════════════════════════════════╗\n\
-         ║           PANIC DETECTED             ║\n\
-         ╚══════════════════════════════════════╝\n\n\
+        "{} panicked\n\n\
          {}\n\n\
+         at {}\n\n\
          The application recovered from this error.\n\
-         Press 'q' to quit or continue using the app.",
-        message
+         Press Esc to go back to the app or 'q' to quit.",
+        report.context, report.message, location
     );
     
     let widget = Paragraph::new(error_text)
         .style(Style::default().fg(Color::Red))
+        .wrap(Wrap { trim: false })
         .block(Block::default().borders(Borders::ALL).title("Error"));
     
+    frame.render_widget(Clear, area);
     frame.render_widget(widget, area);
 }
 
@@ -219,4 +406,82 @@ mod tests {
         let cmd = safe_update(&mut model, Event::Tick, PanicRecoveryStrategy::Continue);
         assert!(cmd.is_noop());
     }
-}
\ No newline at end of file
+    
+    fn calm() -> PanickyModel {
+        PanickyModel {
+            should_panic_init: false,
+            should_panic_update: false,
+            should_panic_view: false,
+        }
+    }
+    
+    fn key(key: Key) -> Event<()> {
+        Event::Key(KeyEvent::new(key, hojicha_core::event::KeyModifiers::empty()))
+    }
+    
+    #[test]
+    fn test_show_error_until_dismissed() {
+        let mut model = PanickyModel {
+            should_panic_update: true,
+            ..calm()
+        };
+        let mut recovery = PanicRecovery::new(PanicRecoveryStrategy::ShowError);
+        
+        assert!(recovery.update(&mut model, Event::Tick).is_noop());
+        let report = recovery.error().expect("error screen should be up");
+        assert_eq!(report.context, "Model::update");
+        assert_eq!(report.message, "Update panic!");
+        // Other tests may replace the panic hook that records the location
+        if let Some(location) = &report.location {
+            assert!(location.contains("panic_recovery.rs"));
+        }
+        
+        // Keys go to the error screen, not the model
+        model.should_panic_update = false;
+        assert!(recovery.update(&mut model, key(Key::Char('q'))).is_quit());
+        assert!(recovery.update(&mut model, key(Key::Esc)).is_noop());
+        assert!(recovery.error().is_none());
+    }
+    
+    #[test]
+    fn test_show_error_replaces_view() {
+        use ratatui::{backend::TestBackend, Terminal};
+        
+        let mut model = PanickyModel {
+            should_panic_view: true,
+            ..calm()
+        };
+        let mut recovery = PanicRecovery::new(PanicRecoveryStrategy::ShowError);
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        
+        terminal.draw(|f| recovery.view(&model, f, f.area())).unwrap();
+        assert!(recovery.recover_view(&mut model).is_none());
+        
+        // The error screen stays up without calling the model's view again
+        model.should_panic_view = false;
+        terminal.draw(|f| recovery.view(&model, f, f.area())).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("Model::view panicked"));
+        assert!(screen.contains("View panic!"));
+    }
+    
+    #[test]
+    fn test_reset_rebuilds_model() {
+        let mut model = PanickyModel {
+            should_panic_update: true,
+            ..calm()
+        };
+        let mut recovery = PanicRecovery::new(PanicRecoveryStrategy::Reset);
+        recovery.set_reset(calm);
+        
+        recovery.update(&mut model, Event::Tick);
+        assert!(!model.should_panic_update);
+        
+        // A panic in view resets once the frame is done
+        model.should_panic_view = true;
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(10, 3)).unwrap();
+        terminal.draw(|f| recovery.view(&model, f, f.area())).unwrap();
+        assert!(recovery.recover_view(&mut model).is_some());
+        assert!(!model.should_panic_view);
+    }
+}
@@ -8,7 +8,7 @@
 //! # Priority Levels
 //!
 //! Events are automatically assigned priorities:
-//! - **High**: Quit, Key events, Suspend/Resume, Process execution
+//! - **High**: Quit, Key events, Suspend/Resume, Interrupt, Process execution, Terminal control
 //! - **Normal**: Mouse events, User messages, Paste events
 //! - **Low**: Tick, Resize, Focus/Blur events
 //!
@@ -59,20 +59,23 @@ impl Priority {
     /// Determine the priority level for a given event
     ///
     /// This method automatically assigns priority levels based on event type:
-    /// - High: Quit, Key events, Suspend/Resume, Process execution
-    /// - Normal: Mouse events, User messages, Paste events  
-    /// - Low: Tick, Resize, Focus/Blur events
+    /// - High: Quit, input (Key, Mouse, Paste, Focus/Blur), Suspend/Resume,
+    ///   Process execution. Input shares one level so it is handled in the
+    ///   order it was typed
+    /// - Normal: User messages, Command errors and terminal control commands,
+    ///   which keep their order with the messages from the same update
+    /// - Low: Tick, Resize events
     pub fn from_event<M: Message>(event: &Event<M>) -> Self {
         match event {
             Event::Quit => Priority::High,
-            Event::Key(_) => Priority::High,
-            Event::Mouse(_) => Priority::Normal,
-            Event::User(_) => Priority::Normal,
+            Event::Key(_) | Event::Mouse(_) | Event::Paste(_) => Priority::High,
+            Event::Focus | Event::Blur => Priority::High,
+            Event::User(_) | Event::Error(_) | Event::TerminalControl(_) => Priority::Normal,
             Event::Resize { .. } => Priority::Low,
             Event::Tick => Priority::Low,
-            Event::Paste(_) => Priority::Normal,
-            Event::Focus | Event::Blur => Priority::Low,
             Event::Suspend | Event::Resume | Event::ExecProcess => Priority::High,
+            Event::Interrupt => Priority::High,
+            _ => Priority::Normal,
         }
     }
 }
@@ -95,9 +98,10 @@ impl<M: Message> Eq for PriorityEvent<M> {}
 impl<M: Message> Ord for PriorityEvent<M> {
     fn cmp(&self, other: &Self) -> Ordering {
         // BinaryHeap is a max-heap, so we want High (0) to be greater than Low (2)
-        // Therefore we reverse the comparison
+        // Therefore we reverse the comparison. Within a priority the older event
+        // (lower sequence) wins so events come out in the order they arrived.
         match other.priority.cmp(&self.priority) {
-            Ordering::Equal => self.sequence.cmp(&other.sequence),
+            Ordering::Equal => other.sequence.cmp(&self.sequence),
             other => other,
         }
     }
@@ -458,6 +462,60 @@ mod tests {
         assert_eq!(queue.pop(), None);
     }
 
+    #[test]
+    fn test_same_priority_is_fifo() {
+        let mut queue: PriorityEventQueue<TestMsg> = PriorityEventQueue::new(10);
+
+        for i in 0..5 {
+            queue.push(Event::User(TestMsg(i))).unwrap();
+        }
+
+        for i in 0..5 {
+            assert_eq!(queue.pop(), Some(Event::User(TestMsg(i))));
+        }
+    }
+
+    #[test]
+    fn test_input_keeps_its_order() {
+        let mut queue: PriorityEventQueue<TestMsg> = PriorityEventQueue::new(10);
+        let key = Event::Key(KeyEvent {
+            key: Key::Char('a'),
+            modifiers: crossterm::event::KeyModifiers::empty(),
+        });
+        let input = vec![
+            Event::Focus,
+            key.clone(),
+            Event::Paste("text".to_string()),
+            Event::Blur,
+            key,
+        ];
+        queue.push(Event::User(TestMsg(1))).unwrap();
+        for event in &input {
+            queue.push(event.clone()).unwrap();
+        }
+
+        for event in input {
+            assert_eq!(queue.pop(), Some(event));
+        }
+        assert_eq!(queue.pop(), Some(Event::User(TestMsg(1))));
+    }
+
+    #[test]
+    fn test_terminal_control_keeps_order_with_messages() {
+        use hojicha_core::commands::TerminalControlMsg;
+
+        let mut queue: PriorityEventQueue<TestMsg> = PriorityEventQueue::new(10);
+        queue.push(Event::User(TestMsg(1))).unwrap();
+        queue
+            .push(Event::TerminalControl(TerminalControlMsg::HideCursor))
+            .unwrap();
+        queue.push(Event::User(TestMsg(2))).unwrap();
+
+        assert_eq!(queue.pop(), Some(Event::User(TestMsg(1))));
+        assert!(matches!(queue.pop(), Some(Event::TerminalControl(_))));
+        assert_eq!(queue.pop(), Some(Event::User(TestMsg(2))));
+    }
+
     #[test]
     fn test_backpressure() {
         let mut queue: PriorityEventQueue<TestMsg> = PriorityEventQueue::new(5);
@@ -5,27 +5,38 @@ mod command_executor;
 pub mod error_handler;
 mod event_processor;
 mod fps_limiter;
+mod handle;
 mod priority_event_processor;
+mod render_scheduler;
 mod terminal_manager;
 
-pub use command_executor::CommandExecutor;
+pub use command_executor::{CommandExecutor, ExecRequest};
+use error_handler::{ErrorHandler, ModelErrorHandler};
 pub use event_processor::EventProcessor;
 pub use fps_limiter::FpsLimiter;
+use handle::MessageRoom;
+pub use handle::{ProgramHandle, SendError};
 pub use priority_event_processor::{
     get_event_stats, EventStats, PriorityConfig, PriorityEventProcessor,
 };
-pub use terminal_manager::{TerminalConfig, TerminalManager};
+pub use render_scheduler::RenderScheduler;
+pub use terminal_manager::{DynBackend, ManagedBackend, TerminalConfig, TerminalManager};
 
 // Re-export the main types from program_old.rs for backward compatibility
 // We'll gradually migrate the implementation to use the extracted components
 
 use crate::async_handle::AsyncHandle;
-use crate::panic_recovery::{self, PanicRecoveryStrategy};
+use crate::input_decoder::{InputDecoder, DEFAULT_ESC_TIMEOUT};
+use crate::middleware::{Filter, Middleware, MiddlewareStack};
+use crate::panic_recovery::{PanicRecovery, PanicRecoveryStrategy};
 use crate::resource_limits::ResourceLimits;
-use crate::subscription::Subscription;
+use crate::signals::{SignalHandler, SizeQuery};
+use crate::subscription::{self, Subscription, SubscriptionSet};
 use crossterm::event::{self};
-use hojicha_core::core::Model;
+use hojicha_core::commands::TerminalControlMsg;
+use hojicha_core::core::{Cmd, Model};
 use hojicha_core::error::{Error, Result};
+use hojicha_core::fallible::{FallibleModel, FallibleModelExt};
 use hojicha_core::event::Event;
 use std::io::{self, Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -33,14 +44,35 @@ use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
-/// Type alias for message filter function
-type MessageFilter<M> = Box<
-    dyn Fn(&M, Event<<M as Model>::Message>) -> Option<Event<<M as Model>::Message>> + Send + Sync,
->;
-
 /// Type alias for condition check function
 type ConditionCheck<M> = Box<dyn FnMut(&M) -> bool>;
 
+/// Type alias for the update function of a fallible model
+type FallibleUpdate<M> = fn(&mut M, Event<<M as Model>::Message>) -> Cmd<<M as Model>::Message>;
+
+/// How a [`FallibleModel`] is driven, set by [`Program::with_fallible_model`]
+struct FallibleHooks<M: Model> {
+    update: FallibleUpdate<M>,
+    handle_error: fn(&mut M, Error) -> Cmd<M::Message>,
+}
+
+/// Type alias for both ends of the program's message channel
+type MessageChannel<T> = (mpsc::SyncSender<Event<T>>, mpsc::Receiver<Event<T>>);
+
+/// Default capacity of the message channel
+const DEFAULT_MESSAGE_CAPACITY: usize = 100;
+
+/// How long the input thread gets to stop reading the terminal
+///
+/// It polls with a 100ms timeout, so it normally stops well within this.
+const INPUT_PAUSE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Whether the program loop keeps going after handling an event
+enum Flow {
+    Continue,
+    Quit,
+}
+
 /// Mouse tracking mode
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum MouseMode {
@@ -57,6 +89,8 @@ pub enum MouseMode {
 pub struct ProgramOptions {
     /// Whether to use alternate screen
     pub alt_screen: bool,
+    /// Render inline in at most this many lines instead of full screen
+    pub inline_height: Option<u16>,
     /// Mouse tracking mode
     pub mouse_mode: MouseMode,
     /// Enable bracketed paste mode
@@ -67,7 +101,7 @@ pub struct ProgramOptions {
     pub fps: u16,
     /// Run in headless mode without rendering
     pub headless: bool,
-    /// Disable signal handlers
+    /// Turn SIGTERM/SIGHUP/SIGINT/SIGWINCH into events (see [`crate::signals`])
     pub install_signal_handler: bool,
     /// Disable renderer
     pub without_renderer: bool,
@@ -75,10 +109,18 @@ pub struct ProgramOptions {
     pub output: Option<Box<dyn Write + Send + Sync>>,
     /// Custom input reader
     pub input: Option<Box<dyn Read + Send + Sync>>,
+    /// How long custom input waits after a lone ESC before reporting the
+    /// Escape key (see [`crate::input_decoder`])
+    pub esc_timeout: Duration,
     /// Panic recovery strategy for Model methods
     pub panic_recovery_strategy: PanicRecoveryStrategy,
     /// Resource limits for async task execution
     pub resource_limits: ResourceLimits,
+    /// How many events the message channel holds before senders have to wait
+    message_capacity: usize,
+    /// Whether `Event::Tick` redraws the view even when its update returns no
+    /// command
+    tick_redraws: bool,
 }
 
 impl ProgramOptions {
@@ -86,6 +128,7 @@ impl ProgramOptions {
     pub fn new() -> Self {
         Self {
             alt_screen: true,
+            inline_height: None,
             mouse_mode: MouseMode::None,
             bracketed_paste: false,
             focus_reporting: false,
@@ -95,8 +138,11 @@ impl ProgramOptions {
             without_renderer: false,
             output: None,
             input: None,
+            esc_timeout: DEFAULT_ESC_TIMEOUT,
             panic_recovery_strategy: PanicRecoveryStrategy::default(),
             resource_limits: ResourceLimits::default(),
+            message_capacity: DEFAULT_MESSAGE_CAPACITY,
+            tick_redraws: false,
         }
     }
 
@@ -106,6 +152,19 @@ impl ProgramOptions {
         self
     }
 
+    /// Render inline below the cursor instead of taking over the screen
+    ///
+    /// The live region grows and shrinks with the view's content, up to
+    /// `max_height` lines. The view is given an area that tall and the region
+    /// ends after the last non-empty row, so lay content out from the top.
+    /// Lines printed with [`Program::println`] or `commands::println` go above
+    /// it, and the final frame stays in the scrollback when the program exits.
+    pub fn with_inline(mut self, max_height: u16) -> Self {
+        self.alt_screen = false;
+        self.inline_height = Some(max_height);
+        self
+    }
+
     /// Set mouse tracking mode
     pub fn with_mouse_mode(mut self, mode: MouseMode) -> Self {
         self.mouse_mode = mode;
@@ -147,6 +206,17 @@ impl ProgramOptions {
         self.without_renderer = true;
         self
     }
+
+    /// Redraw after every `Event::Tick`, even when its update returns no command
+    ///
+    /// The loop sends itself a tick whenever it has been idle for 250ms. By
+    /// default these ticks are only drawn when their update returns a command,
+    /// so idle programs stop drawing. Use this for a model that changes on a
+    /// tick without returning `commands::invalidate()`.
+    pub fn with_tick_redraws(mut self) -> Self {
+        self.tick_redraws = true;
+        self
+    }
     
     /// Set panic recovery strategy for Model methods
     pub fn with_panic_recovery(mut self, strategy: PanicRecoveryStrategy) -> Self {
@@ -173,11 +243,29 @@ impl ProgramOptions {
         self
     }
     
+    /// Set how long custom input waits for the rest of an escape sequence
+    ///
+    /// Shorter timeouts make the Escape key more responsive, longer ones help
+    /// over slow connections where sequences arrive in pieces.
+    pub fn with_esc_timeout(mut self, timeout: Duration) -> Self {
+        self.esc_timeout = timeout;
+        self
+    }
+
     /// Set resource limits for async task execution
     pub fn with_resource_limits(mut self, limits: ResourceLimits) -> Self {
         self.resource_limits = limits;
         self
     }
+
+    /// Set how many events the message channel holds before senders wait
+    ///
+    /// Commands, subscriptions, input and [`ProgramHandle`]s all send through
+    /// this channel. The default is 100.
+    pub fn with_message_capacity(mut self, capacity: usize) -> Self {
+        self.message_capacity = capacity.max(1);
+        self
+    }
 }
 
 impl Default for ProgramOptions {
@@ -190,16 +278,25 @@ impl Default for ProgramOptions {
 pub struct Program<M: Model> {
     model: M,
     options: ProgramOptions,
-    terminal_manager: TerminalManager,
+    terminal_manager: TerminalManager<DynBackend>,
     command_executor: CommandExecutor<M::Message>,
-    fps_limiter: FpsLimiter,
+    render_scheduler: RenderScheduler,
+    subscriptions: SubscriptionSet,
+    panic_recovery: PanicRecovery<M>,
+    fallible: Option<FallibleHooks<M>>,
+    custom_error_handler: bool,
     message_tx: Option<mpsc::SyncSender<Event<M::Message>>>,
     message_rx: Option<mpsc::Receiver<Event<M::Message>>>,
     priority_processor: PriorityEventProcessor<M::Message>,
-    filter: Option<MessageFilter<M>>,
+    middleware: MiddlewareStack<M>,
     running: Arc<AtomicBool>,
     force_quit: Arc<AtomicBool>,
+    /// Set by a `ProgramHandle::quit` that found the message channel full
+    quit_requested: Arc<AtomicBool>,
+    input_paused: Arc<AtomicBool>,
+    input_idle: Arc<AtomicBool>,
     input_thread: Option<thread::JoinHandle<()>>,
+    stop_process: fn(),
 }
 
 impl<M: Model> Program<M>
@@ -212,26 +309,63 @@ where
     }
 
     /// Create a new program with custom options
-    pub fn with_options(model: M, options: ProgramOptions) -> Result<Self> {
-        // Create terminal manager
-        let terminal_config = TerminalConfig {
+    ///
+    /// Output goes to `options.output` if it is set, stdout otherwise.
+    pub fn with_options(model: M, mut options: ProgramOptions) -> Result<Self> {
+        let terminal_config = Self::terminal_config(&options);
+        let terminal_manager = match options.output.take() {
+            Some(output) => TerminalManager::with_writer(output, terminal_config)?,
+            None => TerminalManager::with_writer(io::stdout(), terminal_config)?,
+        };
+        Self::with_terminal_manager(model, options, terminal_manager)
+    }
+
+    /// Create a program that draws to any ratatui backend
+    ///
+    /// Use this to render into ratatui's `TestBackend` or a backend of your
+    /// own. The backend only draws frames, so alternate screen, mouse, paste
+    /// and focus settings have no effect, and `options.output` is ignored.
+    pub fn with_backend<B>(model: M, backend: B, options: ProgramOptions) -> Result<Self>
+    where
+        B: ratatui::backend::Backend + Send + 'static,
+    {
+        let terminal_config = Self::terminal_config(&options);
+        let terminal_manager = TerminalManager::with_backend(backend, terminal_config)?;
+        Self::with_terminal_manager(model, options, terminal_manager)
+    }
+
+    fn terminal_config(options: &ProgramOptions) -> TerminalConfig {
+        TerminalConfig {
             alt_screen: options.alt_screen,
             mouse_mode: options.mouse_mode,
             bracketed_paste: options.bracketed_paste,
             focus_reporting: options.focus_reporting,
+            inline_height: options.inline_height,
             headless: options.headless || options.without_renderer,
-        };
-        let terminal_manager = TerminalManager::new(terminal_config)?;
+            ..TerminalConfig::default()
+        }
+        // Leave the tty alone when input comes from elsewhere
+        .with_raw_mode(options.input.is_none())
+    }
+
+    fn with_terminal_manager(
+        model: M,
+        options: ProgramOptions,
+        terminal_manager: TerminalManager<DynBackend>,
+    ) -> Result<Self> {
 
         // Create command executor with resource limits
         let command_executor = CommandExecutor::with_resource_limits(options.resource_limits.clone())?;
 
-        // Create FPS limiter
-        let fps_limiter = FpsLimiter::new(options.fps);
-
         // Create priority event processor with default config
         let priority_processor = PriorityEventProcessor::new();
 
+        // Redraw only when something changed, at most `fps` times a second
+        let render_scheduler = RenderScheduler::new(options.fps)
+            .with_metrics(priority_processor.metrics_collector());
+
+        let panic_recovery = PanicRecovery::new(options.panic_recovery_strategy);
+
         log::info!("Hojicha program initialized with priority event processing");
 
         Ok(Self {
@@ -239,29 +373,98 @@ where
             options,
             terminal_manager,
             command_executor,
-            fps_limiter,
+            render_scheduler,
+            subscriptions: SubscriptionSet::new(),
+            panic_recovery,
+            fallible: None,
+            custom_error_handler: false,
             message_tx: None,
             message_rx: None,
             priority_processor,
-            filter: None,
+            middleware: MiddlewareStack::new(),
             running: Arc::new(AtomicBool::new(false)),
             force_quit: Arc::new(AtomicBool::new(false)),
+            quit_requested: Arc::new(AtomicBool::new(false)),
+            input_paused: Arc::new(AtomicBool::new(false)),
+            input_idle: Arc::new(AtomicBool::new(false)),
             input_thread: None,
+            stop_process,
         })
     }
 
+    /// Replace how the process is stopped on suspend (for testing only)
+    ///
+    /// Lets tests run the whole suspend path without stopping the test binary.
+    #[doc(hidden)]
+    pub fn with_stop_process(mut self, stop: fn()) -> Self {
+        self.stop_process = stop;
+        self
+    }
+
     /// Set a message filter function
-    pub fn with_filter<F>(mut self, filter: F) -> Self
+    ///
+    /// The filter is added to the middleware stack as a [`Filter`], so it
+    /// runs in order with the middleware added before and after it. Calling
+    /// this again adds another filter rather than replacing the first.
+    pub fn with_filter<F>(self, filter: F) -> Self
     where
+        M: 'static,
         F: Fn(&M, Event<M::Message>) -> Option<Event<M::Message>> + Send + Sync + 'static,
     {
-        self.filter = Some(Box::new(filter));
+        self.with_middleware(Filter::new(filter))
+    }
+
+    /// Add a middleware to the end of the stack
+    ///
+    /// Events go through the middleware in the order it was added before they
+    /// reach `update`, and the command `update` returns goes back through it
+    /// in reverse order. See the [`middleware`](crate::middleware) module.
+    pub fn with_middleware<W>(mut self, middleware: W) -> Self
+    where
+        M: 'static,
+        W: Middleware<M> + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Set how the model is rebuilt after a panic
+    ///
+    /// Used by [`PanicRecoveryStrategy::Reset`]: the model is replaced with a
+    /// new one from `factory` and its `init` runs again.
+    pub fn with_reset<F>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.panic_recovery.set_reset(factory);
+        self
+    }
+
+    /// Set how errors returned by commands are handled
+    ///
+    /// By default they are logged, or passed to the model's `handle_error`
+    /// after [`with_fallible_model`](Self::with_fallible_model). Use
+    /// [`EventErrorHandler`] to turn them into messages, [`ModelErrorHandler`]
+    /// to pass them to the model, or [`CompositeErrorHandler`] to do several
+    /// of these.
+    ///
+    /// [`EventErrorHandler`]: error_handler::EventErrorHandler
+    /// [`ModelErrorHandler`]: error_handler::ModelErrorHandler
+    /// [`CompositeErrorHandler`]: error_handler::CompositeErrorHandler
+    pub fn with_error_handler<H>(mut self, handler: H) -> Self
+    where
+        H: ErrorHandler<M::Message> + 'static,
+    {
+        self.command_executor.set_error_handler(handler);
+        self.custom_error_handler = true;
         self
     }
 
     /// Configure the priority event processor
     pub fn with_priority_config(mut self, config: PriorityConfig) -> Self {
         self.priority_processor = PriorityEventProcessor::with_config(config);
+        self.render_scheduler = RenderScheduler::new(self.options.fps)
+            .with_metrics(self.priority_processor.metrics_collector());
         log::debug!("Priority processor configured with custom settings");
         self
     }
@@ -276,16 +479,32 @@ where
         get_event_stats(&self.priority_processor)
     }
 
-    /// Print formatted text to stderr
+    /// Print formatted text above the program
+    ///
+    /// See [`Program::println`]. Outside inline mode the text goes to stderr
+    /// as is, without a newline added.
     pub fn printf(&self, args: std::fmt::Arguments) {
-        eprint!("{args}");
-        let _ = io::stderr().flush();
+        if self.options.inline_height.is_some() {
+            self.println(&args.to_string());
+        } else {
+            eprint!("{args}");
+            let _ = io::stderr().flush();
+        }
     }
 
-    /// Print a line to stderr
+    /// Print a line above the program's inline viewport
+    ///
+    /// The line is printed by the program loop between frames, so it never
+    /// tears the display. Only inline programs ([`ProgramOptions::with_inline`])
+    /// have room for it; other programs print the line to stderr, as they do
+    /// for [`commands::println`](hojicha_core::commands::println).
     pub fn println(&self, text: &str) {
-        eprintln!("{text}");
-        let _ = io::stderr().flush();
+        if self.options.inline_height.is_some() {
+            let msg = TerminalControlMsg::PrintLine(text.to_string());
+            let _ = self.priority_processor.push(Event::TerminalControl(msg));
+        } else {
+            print_to_stderr(text);
+        }
     }
 
     /// Send a quit message to the running program
@@ -414,6 +633,24 @@ where
         self.message_tx.clone()
     }
 
+    /// Get a cloneable handle for sending messages to the program and
+    /// stopping it from any thread or task
+    ///
+    /// The handle stays usable after `run` has taken the program, and its
+    /// `send` waits asynchronously instead of blocking while the message
+    /// channel is full. Get it after [`with_priority_config`](Self::with_priority_config),
+    /// which replaces the queue the handle reports on. See [`ProgramHandle`].
+    pub fn handle(&mut self) -> ProgramHandle<M::Message> {
+        ProgramHandle::new(
+            self.init_async_bridge(),
+            self.command_executor.message_room().clone(),
+            self.priority_processor.clone(),
+            Arc::clone(&self.running),
+            Arc::clone(&self.force_quit),
+            Arc::clone(&self.quit_requested),
+        )
+    }
+
     /// Send a user message to the program
     ///
     /// Convenience method that wraps the message in Event::User.
@@ -477,7 +714,9 @@ where
     /// # Thread Safety
     ///
     /// The returned sender can be cloned and shared across multiple threads safely.
-    /// Messages are queued with a capacity of 100 by default.
+    /// Messages are queued with a capacity of 100 by default
+    /// (see [`ProgramOptions::with_message_capacity`]). `send` blocks while the
+    /// channel is full; from async tasks use [`handle`](Self::handle) instead.
     ///
     /// # Example
     ///
@@ -507,7 +746,7 @@ where
     /// ```
     pub fn init_async_bridge(&mut self) -> mpsc::SyncSender<Event<M::Message>> {
         if self.message_tx.is_none() {
-            let (message_tx, message_rx) = mpsc::sync_channel(100);
+            let (message_tx, message_rx) = mpsc::sync_channel(self.options.message_capacity);
             self.message_tx = Some(message_tx.clone());
             self.message_rx = Some(message_rx);
             message_tx
@@ -571,9 +810,6 @@ where
         S: futures::Stream<Item = M::Message> + Send + 'static,
         M::Message: Send + 'static,
     {
-        use futures::StreamExt;
-        use tokio_util::sync::CancellationToken;
-
         // Ensure we have a message channel
         if self.message_tx.is_none() {
             self.init_async_bridge();
@@ -584,35 +820,7 @@ where
             .as_ref()
             .expect("message_tx should be Some after init_async_bridge")
             .clone();
-        let cancel_token = CancellationToken::new();
-        let cancel_clone = cancel_token.clone();
-
-        // Spawn task to poll the stream
-        let handle = self.command_executor.spawn(async move {
-            tokio::pin!(stream);
-
-            loop {
-                tokio::select! {
-                    _ = cancel_clone.cancelled() => {
-                        break; // Subscription was cancelled
-                    }
-                    item = stream.next() => {
-                        match item {
-                            Some(msg) => {
-                                if sender.send(Event::User(msg)).is_err() {
-                                    break; // Program has shut down
-                                }
-                            }
-                            None => {
-                                break; // Stream completed
-                            }
-                        }
-                    }
-                }
-            }
-        });
-
-        Subscription::new(handle, cancel_token)
+        subscription::spawn_stream(&self.command_executor, stream, sender)
     }
 
     /// Spawn a cancellable async operation
@@ -850,6 +1058,51 @@ where
         self.run_internal(None, None, condition)
     }
 
+    /// Run the program on the caller's Tokio runtime
+    ///
+    /// Unlike [`run`](Self::run), this doesn't block a thread: the loop sleeps
+    /// until a message, terminal event or tick arrives. Commands are spawned on
+    /// the ambient runtime instead of one created by the program, and terminal
+    /// input is read through crossterm's async event stream.
+    ///
+    /// Exec commands and suspending still block while the child or the
+    /// suspended process owns the terminal; on a multi-threaded runtime other
+    /// tasks are moved off the worker in the meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use hojicha_core::core::{Cmd, Model};
+    /// # use hojicha_core::event::Event;
+    /// # use hojicha_runtime::program::Program;
+    /// # use ratatui::{layout::Rect, Frame};
+    /// # struct App;
+    /// # impl Model for App {
+    /// #     type Message = ();
+    /// #     fn update(&mut self, _: Event<()>) -> Cmd<()> { Cmd::none() }
+    /// #     fn view(&self, _: &mut Frame, _: Rect) {}
+    /// # }
+    /// #[tokio::main]
+    /// async fn main() -> hojicha_core::Result<()> {
+    ///     Program::new(App)?.run_async().await
+    /// }
+    /// ```
+    pub async fn run_async(mut self) -> Result<()> {
+        let owned_runtime = self
+            .command_executor
+            .use_runtime_handle(tokio::runtime::Handle::current());
+
+        let result = self.run_async_internal().await;
+
+        // Tasks spawned before the program started ran on the program's own
+        // runtime, which shuts down once nothing uses it anymore
+        drop(owned_runtime);
+        result
+    }
+
     /// Internal run implementation with optional timeout and condition
     fn run_internal(
         mut self,
@@ -860,56 +1113,63 @@ where
         // Mark as running
         self.running.store(true, Ordering::SeqCst);
 
-        let (crossterm_tx, crossterm_rx) = mpsc::sync_channel(100);
-
-        // Use existing message channel if initialized, otherwise create new one
-        let (message_tx, message_rx) = if let Some(rx) = self.message_rx.take() {
-            // Channel was already initialized via init_async_bridge()
-            let tx = self
-                .message_tx
-                .as_ref()
-                .expect("message_tx should be Some when message_rx is Some")
-                .clone();
-            (tx, rx)
-        } else {
-            let (tx, rx) = mpsc::sync_channel(100);
-            self.message_tx = Some(tx.clone());
-            (tx, rx)
-        };
-
-        // Spawn input thread if not headless
-        if !self.options.headless && !self.options.without_renderer {
+        let reads_tty = self.reads_tty();
+        // Declared first so it is dropped after the receiver
+        let _room = RoomGuard(self.command_executor.message_room().clone());
+        let (message_tx, message_rx) = self.message_channel();
+        let _signals = self.install_signal_handler(&message_tx, reads_tty);
+
+        if let Some(input) = self.options.input.take() {
+            // Custom input is read even when headless. The thread is left
+            // detached because a blocking read can't be interrupted.
+            spawn_input_reader(input, message_tx.clone(), self.options.esc_timeout);
+        } else if reads_tty {
+            // Read the terminal through crossterm. Events go into the message
+            // channel, so the loop wakes for input and messages alike.
             let running = Arc::clone(&self.running);
             let force_quit = Arc::clone(&self.force_quit);
+            let input_paused = Arc::clone(&self.input_paused);
+            let input_idle = Arc::clone(&self.input_idle);
+            let input_tx = message_tx.clone();
 
             let input_thread = thread::spawn(move || loop {
                 if !running.load(Ordering::SeqCst) || force_quit.load(Ordering::SeqCst) {
                     break;
                 }
 
+                // Stay off the tty while another process owns the terminal
+                input_idle.store(false, Ordering::SeqCst);
+                if input_paused.load(Ordering::SeqCst) {
+                    input_idle.store(true, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+
                 if event::poll(Duration::from_millis(100)).unwrap_or(false) {
                     if let Ok(event) = event::read() {
-                        let _ = crossterm_tx.send(event);
+                        let (event, next) = coalesce_resize(event, || {
+                            event::poll(Duration::ZERO)
+                                .unwrap_or(false)
+                                .then(event::read)
+                                .and_then(io::Result::ok)
+                        });
+                        for event in std::iter::once(event).chain(next) {
+                            if let Some(event) = EventProcessor::convert_event(event) {
+                                let _ = input_tx.send(event);
+                            }
+                        }
                     }
                 }
             });
             self.input_thread = Some(input_thread);
         }
 
-        // Run initial command with panic recovery
-        let init_cmd = panic_recovery::safe_init(
-            &mut self.model,
-            self.options.panic_recovery_strategy,
-        );
-        if init_cmd.is_quit() {
+        if let Flow::Quit = self.start(&message_tx) {
             // If init returns quit due to panic recovery, exit early
             self.running.store(false, Ordering::SeqCst);
             self.terminal_manager.cleanup().map_err(Error::from)?;
             return Ok(());
         }
-        if !init_cmd.is_noop() {
-            self.command_executor.execute(init_cmd, message_tx.clone());
-        }
 
         // Main event loop
         let tick_rate = Duration::from_millis(250);
@@ -918,6 +1178,10 @@ where
                 break;
             }
 
+            if let Flow::Quit = self.requested_quit(&message_tx)? {
+                break;
+            }
+
             // Check timeout if specified
             if let (Some(timeout), Some(start)) = (timeout, start_time) {
                 if start.elapsed() >= timeout {
@@ -925,51 +1189,28 @@ where
                 }
             }
 
-            // Process events with shorter timeout to allow checking conditions
+            // Wake up more often when a timeout has to be checked
             let event_timeout = if timeout.is_some() {
-                Duration::from_millis(10) // Check more frequently when timeout is set
+                Duration::from_millis(10)
             } else {
                 tick_rate
             };
-            let event = if self.options.headless {
-                self.priority_processor
-                    .process_events_headless(&message_rx, event_timeout)
-            } else {
-                self.priority_processor
-                    .process_events(&message_rx, &crossterm_rx, event_timeout)
+            // Wake up early for a frame held back by the FPS cap
+            let wait = self
+                .frame_wait()
+                .map_or(event_timeout, |frame| frame.min(event_timeout));
+            let event = match self.priority_processor.next_event(&message_rx, wait) {
+                Ok(event) => Some(event),
+                Err(mpsc::RecvTimeoutError::Timeout) if wait < event_timeout => None,
+                Err(mpsc::RecvTimeoutError::Timeout) => Some(Event::Tick),
+                Err(mpsc::RecvTimeoutError::Disconnected) => None,
             };
+            self.command_executor.message_room().drained();
 
             if let Some(event) = event {
-                // Check for quit
-                if matches!(event, Event::Quit) {
+                if let Flow::Quit = self.handle_event(event, &message_tx)? {
                     break;
                 }
-
-                // Apply filter if present
-                let event = if let Some(ref filter) = self.filter {
-                    filter(&self.model, event)
-                } else {
-                    Some(event)
-                };
-
-                // Update model with panic recovery
-                if let Some(event) = event {
-                    let cmd = panic_recovery::safe_update(
-                        &mut self.model,
-                        event,
-                        self.options.panic_recovery_strategy,
-                    );
-
-                    // Check if command is quit
-                    if cmd.is_quit() {
-                        break;
-                    }
-
-                    // Execute the command if it's not a no-op
-                    if !cmd.is_noop() {
-                        self.command_executor.execute(cmd, message_tx.clone());
-                    }
-                }
             }
 
             // Check condition if specified
@@ -979,31 +1220,400 @@ where
                 }
             }
 
-            // Render if needed and FPS allows
-            if !self.options.without_renderer && self.fps_limiter.should_render() {
-                // Capture the panic recovery flag
-                let mut should_quit = false;
-                
-                self.terminal_manager
-                    .draw(|f| {
-                        let area = f.area();
-                        should_quit = panic_recovery::safe_view(
-                            &self.model,
-                            f,
-                            area,
-                            self.options.panic_recovery_strategy,
-                        );
-                    })
-                    .map_err(Error::from)?;
-                
-                if should_quit {
-                    // View panic requested quit
+            if let Flow::Quit = self.render()? {
+                break;
+            }
+        }
+
+        self.shutdown()
+    }
+
+    /// The async counterpart of [`run_internal`](Self::run_internal)
+    async fn run_async_internal(&mut self) -> Result<()> {
+        use futures::{FutureExt, StreamExt};
+
+        self.running.store(true, Ordering::SeqCst);
+
+        let reads_tty = self.reads_tty();
+        let (message_tx, message_rx) = self.message_channel();
+        let _signals = self.install_signal_handler(&message_tx, reads_tty);
+
+        let mut terminal_events = None;
+        if let Some(input) = self.options.input.take() {
+            spawn_input_reader(input, message_tx.clone(), self.options.esc_timeout);
+        } else if reads_tty {
+            terminal_events = Some(event::EventStream::new());
+        }
+
+        // Messages are sent on a std channel from plain threads too, so a
+        // bridge thread forwards them to a channel the loop can await
+        let mut messages = MessageBridge::spawn(
+            message_rx,
+            message_tx.clone(),
+            self.command_executor.message_room().clone(),
+            self.options.message_capacity,
+        );
+
+        if let Flow::Quit = self.start(&message_tx) {
+            self.running.store(false, Ordering::SeqCst);
+            self.terminal_manager.cleanup().map_err(Error::from)?;
+            return Ok(());
+        }
+
+        let tick_rate = Duration::from_millis(250);
+        loop {
+            if self.force_quit.load(Ordering::SeqCst) {
+                break;
+            }
+
+            if let Flow::Quit = self.requested_quit(&message_tx)? {
+                break;
+            }
+
+            self.priority_processor.fill(|| messages.rx.try_recv().ok());
+
+            let frame_wait = self.frame_wait();
+            let event = match self.priority_processor.pop() {
+                Some(event) => Some(event),
+                None => tokio::select! {
+                    message = messages.rx.recv() => match message {
+                        Some(event) => Some(event),
+                        None => break, // Every sender is gone
+                    },
+                    input = next_terminal_event(&mut terminal_events) => input.and_then(|input| {
+                        let (input, next) = coalesce_resize(input, || {
+                            let ready = terminal_events.as_mut()?.next().now_or_never();
+                            ready.flatten()?.ok()
+                        });
+                        if let Some(next) = next.and_then(EventProcessor::convert_event) {
+                            let _ = self.priority_processor.push(next);
+                        }
+                        EventProcessor::convert_event(input)
+                    }),
+                    // A frame held back by the FPS cap is due
+                    _ = tokio::time::sleep(frame_wait.unwrap_or_default()),
+                        if frame_wait.is_some() => None,
+                    _ = tokio::time::sleep(tick_rate) => Some(Event::Tick),
+                },
+            };
+
+            if let Some(event) = event {
+                // Stop reading the tty while a child or the shell owns it
+                let releases = terminal_events.is_some() && releases_terminal(&event);
+                if releases {
+                    terminal_events = None;
+                }
+                let flow = self.handle_event(event, &message_tx)?;
+                if releases {
+                    terminal_events = Some(event::EventStream::new());
+                }
+                if let Flow::Quit = flow {
                     break;
                 }
-                
-                self.fps_limiter.mark_rendered();
+            }
+
+            if let Flow::Quit = self.render()? {
+                break;
+            }
+        }
+
+        self.shutdown()
+    }
+
+    /// Handle a quit that `ProgramHandle::quit` couldn't queue
+    fn requested_quit(&mut self, message_tx: &mpsc::SyncSender<Event<M::Message>>) -> Result<Flow> {
+        if self.quit_requested.swap(false, Ordering::SeqCst) {
+            self.handle_event(Event::Quit, message_tx)
+        } else {
+            Ok(Flow::Continue)
+        }
+    }
+
+    /// Whether the program reads input from the terminal itself
+    fn reads_tty(&self) -> bool {
+        !self.options.headless && !self.options.without_renderer && self.options.input.is_none()
+    }
+
+    /// Take the message channel, creating it unless `init_async_bridge` already did
+    fn message_channel(&mut self) -> MessageChannel<M::Message> {
+        if let Some(rx) = self.message_rx.take() {
+            let tx = self
+                .message_tx
+                .as_ref()
+                .expect("message_tx should be Some when message_rx is Some")
+                .clone();
+            (tx, rx)
+        } else {
+            let (tx, rx) = mpsc::sync_channel(self.options.message_capacity);
+            self.message_tx = Some(tx.clone());
+            (tx, rx)
+        }
+    }
+
+    /// Forward OS signals until the returned handler is dropped
+    fn install_signal_handler(
+        &self,
+        message_tx: &mpsc::SyncSender<Event<M::Message>>,
+        reads_tty: bool,
+    ) -> Option<SignalHandler> {
+        if !self.options.install_signal_handler {
+            return None;
+        }
+
+        // crossterm reports resizes itself while it reads the terminal
+        let resize: Option<SizeQuery> = if !reads_tty {
+            Some(Box::new(crossterm::terminal::size))
+        } else {
+            None
+        };
+        match SignalHandler::install(
+            &self.command_executor.runtime_handle(),
+            message_tx.clone(),
+            resize,
+        ) {
+            Ok(handler) => Some(handler),
+            Err(e) => {
+                log::warn!("Failed to install signal handlers: {}", e);
+                None
             }
         }
+    }
+
+    /// Run the model's init command with panic recovery
+    fn start(&mut self, message_tx: &mpsc::SyncSender<Event<M::Message>>) -> Flow {
+        let init_cmd = self.panic_recovery.init(&mut self.model);
+        if init_cmd.is_quit() {
+            return Flow::Quit;
+        }
+        if !init_cmd.is_noop() {
+            self.command_executor.execute(init_cmd, message_tx.clone());
+        }
+        self.sync_subscriptions(message_tx);
+        Flow::Continue
+    }
+
+    /// Start and cancel subscriptions to match the model's current state
+    fn sync_subscriptions(&mut self, message_tx: &mpsc::SyncSender<Event<M::Message>>) {
+        let subs = self.model.subscriptions();
+        self.subscriptions.sync(subs, &self.command_executor, message_tx);
+    }
+
+    /// Handle one event from the queue
+    ///
+    /// Runtime events (quit, exec, suspend and terminal control) are handled
+    /// here; everything else goes through the middleware to the model's update.
+    fn handle_event(
+        &mut self,
+        event: Event<M::Message>,
+        message_tx: &mpsc::SyncSender<Event<M::Message>>,
+    ) -> Result<Flow> {
+        // Check for quit
+        if matches!(event, Event::Quit) {
+            return Ok(Flow::Quit);
+        }
+
+        // A command failed and its error was passed on to the model
+        if let Event::Error(error) = event {
+            let Some(error) = error.take() else {
+                return Ok(Flow::Continue);
+            };
+            let cmd = self.handle_command_error(error);
+            self.render_scheduler.invalidate();
+            self.sync_subscriptions(message_tx);
+            if cmd.is_quit() {
+                return Ok(Flow::Quit);
+            }
+            if !cmd.is_noop() {
+                self.command_executor.execute(cmd, message_tx.clone());
+            }
+            return Ok(Flow::Continue);
+        }
+
+        // Exec commands queued a process that needs the terminal
+        if matches!(event, Event::ExecProcess) {
+            self.run_pending_exec()?;
+            self.render_scheduler.invalidate();
+            return Ok(Flow::Continue);
+        }
+
+        if matches!(event, Event::Resize { .. }) {
+            self.render_scheduler.invalidate();
+        }
+
+        // Ctrl+Z goes through the same path as commands::suspend()
+        let event = if EventProcessor::is_suspend_event(&event) {
+            Event::Suspend
+        } else {
+            event
+        };
+
+        // Terminal control commands are applied here, between frames,
+        // and never reach the model. Everything else goes through the middleware
+        let event = if let Event::TerminalControl(msg) = event {
+            match msg {
+                TerminalControlMsg::Animate(duration) => self.render_scheduler.animate(duration),
+                // Without a viewport to print above, go where Program::println goes
+                TerminalControlMsg::PrintLine(text) if !self.terminal_manager.is_inline() => {
+                    print_to_stderr(&text);
+                }
+                _ => {
+                    if let Err(e) = self.terminal_manager.apply(&msg) {
+                        log::warn!("Failed to apply {:?}: {}", msg, e);
+                    }
+                    self.render_scheduler.invalidate();
+                }
+            }
+            None
+        } else {
+            // A panic in the middleware is recovered from like one in update
+            let mut passed = None;
+            let mut completed = false;
+            let on_event = |model: &mut M| {
+                passed = self.middleware.on_event(model, event);
+                completed = true;
+                Cmd::none()
+            };
+            let recovered = self
+                .panic_recovery
+                .call("Middleware::on_event", &mut self.model, on_event);
+            self.queue_middleware_output(message_tx);
+            if !completed {
+                self.render_scheduler.invalidate();
+                if recovered.is_quit() {
+                    return Ok(Flow::Quit);
+                }
+                if !recovered.is_noop() {
+                    self.command_executor.execute(recovered, message_tx.clone());
+                }
+            }
+            passed
+        };
+
+        // Update model with panic recovery
+        let Some(event) = event else {
+            return Ok(Flow::Continue);
+        };
+        let suspending = matches!(event, Event::Suspend);
+        // The loop's own idle ticks only redraw if they lead to a command
+        let idle_tick = matches!(event, Event::Tick) && !self.options.tick_redraws;
+        if !idle_tick {
+            self.render_scheduler.invalidate();
+        }
+        let cmd = match &self.fallible {
+            Some(hooks) => {
+                self.panic_recovery
+                    .update_with(&mut self.model, event, hooks.update)
+            }
+            None => self.panic_recovery.update(&mut self.model, event),
+        };
+        let cmd = self
+            .panic_recovery
+            .call("Middleware::on_command", &mut self.model, |model| {
+                self.middleware.on_command(model, cmd)
+            });
+        self.queue_middleware_output(message_tx);
+        self.sync_subscriptions(message_tx);
+
+        // The model can veto a suspend while handling Event::Suspend, also
+        // from inside a batch or sequence
+        let suspending = if suspending && cmd.cancels_suspend() {
+            log::debug!("Suspend cancelled by the model");
+            false
+        } else {
+            suspending
+        };
+
+        // Check if command is quit
+        if cmd.is_quit() {
+            return Ok(Flow::Quit);
+        }
+
+        // Execute the command if it's not a no-op
+        if !cmd.is_noop() {
+            if idle_tick {
+                self.render_scheduler.invalidate();
+            }
+            self.command_executor.execute(cmd, message_tx.clone());
+        }
+
+        if suspending {
+            self.suspend()?;
+            self.render_scheduler.invalidate();
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Queue the events injected by middleware and run its commands
+    fn queue_middleware_output(&mut self, message_tx: &mpsc::SyncSender<Event<M::Message>>) {
+        for event in self.middleware.take_injected() {
+            if self.priority_processor.push(event).is_err() {
+                log::warn!("Event queue is full, dropping an event injected by middleware");
+            }
+        }
+        for cmd in self.middleware.take_commands() {
+            self.command_executor.execute(cmd, message_tx.clone());
+        }
+    }
+
+    /// Give an error from a command to a fallible model, or log it
+    fn handle_command_error(&mut self, error: Error) -> Cmd<M::Message> {
+        let Some(hooks) = &self.fallible else {
+            log::error!("Command execution error: {}", error);
+            return Cmd::none();
+        };
+        let handle_error = hooks.handle_error;
+        self.panic_recovery
+            .call("FallibleModel::handle_error", &mut self.model, |model| {
+                handle_error(model, error)
+            })
+    }
+
+    /// Render if something changed and the FPS cap allows
+    fn render(&mut self) -> Result<Flow> {
+        if self.options.without_renderer {
+            return Ok(Flow::Continue);
+        }
+        if !self.render_scheduler.should_render() {
+            if self.render_scheduler.time_until_next_frame().is_none() {
+                self.render_scheduler.skipped();
+            }
+            return Ok(Flow::Continue);
+        }
+
+        self.draw_frame()?;
+        self.render_scheduler.rendered();
+
+        // A panic in view can only reset or quit once the frame is drawn
+        if let Some(cmd) = self.panic_recovery.recover_view(&mut self.model) {
+            if cmd.is_quit() {
+                return Ok(Flow::Quit);
+            }
+            self.render_scheduler.invalidate();
+            if let Some(message_tx) = self.message_tx.clone() {
+                if !cmd.is_noop() {
+                    self.command_executor.execute(cmd, message_tx.clone());
+                }
+                self.sync_subscriptions(&message_tx);
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// How long until a pending frame is due, or `None` if nothing needs drawing
+    fn frame_wait(&self) -> Option<Duration> {
+        if self.options.without_renderer {
+            None
+        } else {
+            self.render_scheduler.time_until_next_frame()
+        }
+    }
+
+    /// Draw the final frame, log statistics and restore the terminal
+    fn shutdown(&mut self) -> Result<()> {
+        // An inline program leaves its final frame behind, so make it current
+        if self.terminal_manager.is_inline() && !self.options.without_renderer {
+            self.draw_frame()?;
+        }
 
         // Log final statistics before cleanup
         log::info!(
@@ -1013,16 +1623,356 @@ where
 
         // Cleanup
         self.running.store(false, Ordering::SeqCst);
+        self.subscriptions.clear();
         self.terminal_manager.cleanup().map_err(Error::from)?;
 
         Ok(())
     }
+
+    /// Run every queued exec request with the terminal handed over to the child
+    ///
+    /// The input thread is paused so it doesn't steal keystrokes from the child,
+    /// the terminal leaves raw mode and the alternate screen, and once the child
+    /// exits everything is restored and the next frame is a full redraw. The
+    /// callback's message is queued for the model with the child's exit code.
+    fn run_pending_exec(&mut self) -> Result<()> {
+        while let Some(request) = self.command_executor.take_exec_request() {
+            self.pause_input()?;
+            if let Err(e) = self.terminal_manager.release() {
+                self.resume_input();
+                return Err(Error::from(e));
+            }
+
+            let exit_code = block_in_place(|| run_process(&request.program, &request.args));
+
+            let restored = self.terminal_manager.restore();
+            self.resume_input();
+            restored.map_err(Error::from)?;
+
+            // Queue the message before a waiting sequence moves on, so the
+            // model sees the exit code before anything that follows it
+            let _ = self
+                .priority_processor
+                .push(Event::User(request.message(exit_code)));
+            request.finish();
+        }
+        Ok(())
+    }
+
+    /// Hand the terminal back to the shell and stop the process until it is continued
+    ///
+    /// Once the process gets SIGCONT (e.g. from `fg`) the terminal is put back
+    /// into raw mode and the alternate screen, the next frame is a full redraw,
+    /// and the model receives `Event::Resume`.
+    fn suspend(&mut self) -> Result<()> {
+        self.pause_input()?;
+        if let Err(e) = self.terminal_manager.release() {
+            self.resume_input();
+            return Err(Error::from(e));
+        }
+
+        block_in_place(self.stop_process);
+
+        let restored = self.terminal_manager.restore();
+        self.resume_input();
+        restored.map_err(Error::from)?;
+
+        let _ = self.priority_processor.push(Event::Resume);
+        Ok(())
+    }
+
+    /// Draw the model's view, or the error screen after a panic
+    fn draw_frame(&mut self) -> Result<()> {
+        let recovery = &mut self.panic_recovery;
+        let model = &self.model;
+        self.terminal_manager
+            .draw(|f| {
+                let area = f.area();
+                recovery.view(model, f, area);
+            })
+            .map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Stop the input thread from reading the terminal and wait until it is idle
+    ///
+    /// Fails, leaving the input running, if the thread is still reading after
+    /// [`INPUT_PAUSE_TIMEOUT`], since another process would then compete with
+    /// it for keystrokes.
+    fn pause_input(&self) -> Result<()> {
+        self.input_paused.store(true, Ordering::SeqCst);
+        let Some(input_thread) = &self.input_thread else {
+            return Ok(());
+        };
+
+        let deadline = Instant::now() + INPUT_PAUSE_TIMEOUT;
+        while !self.input_idle.load(Ordering::SeqCst) && !input_thread.is_finished() {
+            if Instant::now() >= deadline {
+                self.resume_input();
+                return Err(Error::Terminal(
+                    "the input thread didn't stop reading the terminal".to_string(),
+                ));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+
+    /// Let the input thread read the terminal again
+    fn resume_input(&self) {
+        self.input_paused.store(false, Ordering::SeqCst);
+    }
+}
+
+impl<M: FallibleModel> Program<M>
+where
+    M::Message: Clone,
+{
+    /// Drive the model through [`FallibleModel`]
+    ///
+    /// Events go to `try_update`, and its errors to `handle_error`. Errors
+    /// returned by commands are passed to `handle_error` too, labeled with
+    /// the command's [`Cmd::labeled`] name, unless
+    /// [`with_error_handler`](Self::with_error_handler) set another handler.
+    pub fn with_fallible_model(mut self) -> Self {
+        self.fallible = Some(FallibleHooks {
+            update: M::update_with_error_handling,
+            handle_error: M::handle_error,
+        });
+        if !self.custom_error_handler {
+            self.command_executor.set_error_handler(ModelErrorHandler);
+        }
+        self
+    }
+}
+
+/// Decode events from a custom input stream until it ends
+///
+/// One thread does the blocking reads and hands chunks to a second one that
+/// decodes them, so a lone ESC can be resolved once the ESC timeout passes.
+fn spawn_input_reader<M: Send + 'static>(
+    mut input: Box<dyn Read + Send + Sync>,
+    tx: mpsc::SyncSender<Event<M>>,
+    esc_timeout: Duration,
+) {
+    let (chunk_tx, chunk_rx) = mpsc::channel::<Vec<u8>>();
+
+    let reader = thread::Builder::new()
+        .name("hojicha-input".to_string())
+        .spawn(move || {
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = match input.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        log::warn!("Stopped reading input: {}", e);
+                        break;
+                    }
+                };
+                if chunk_tx.send(buf[..n].to_vec()).is_err() {
+                    break; // Decoder has stopped
+                }
+            }
+        });
+    if let Err(e) = reader {
+        log::error!("Failed to start input reader: {}", e);
+        return;
+    }
+
+    let decoder = thread::Builder::new()
+        .name("hojicha-input-decoder".to_string())
+        .spawn(move || {
+            let mut decoder = InputDecoder::with_esc_timeout(esc_timeout);
+            loop {
+                let received = if decoder.is_waiting() {
+                    chunk_rx.recv_timeout(decoder.esc_timeout())
+                } else {
+                    chunk_rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+                };
+                let (events, done) = match received {
+                    Ok(chunk) => (decoder.feed(&chunk), false),
+                    Err(mpsc::RecvTimeoutError::Timeout) => (decoder.flush(), false),
+                    // The input has ended, nothing else can complete a sequence
+                    Err(mpsc::RecvTimeoutError::Disconnected) => (decoder.flush(), true),
+                };
+                for event in events {
+                    if tx.send(event).is_err() {
+                        return; // Program has shut down
+                    }
+                }
+                if done {
+                    break;
+                }
+            }
+        });
+    if let Err(e) = decoder {
+        log::error!("Failed to start input decoder: {}", e);
+    }
+}
+
+/// Forwards messages from the std channel to one an async loop can await
+///
+/// A thread blocks on the std channel and hands each message on, waiting
+/// while the loop is behind so senders feel backpressure. Dropping the bridge
+/// closes the async channel and wakes the thread, which then stops.
+struct MessageBridge<M> {
+    rx: tokio::sync::mpsc::Receiver<Event<M>>,
+    wake: mpsc::SyncSender<Event<M>>,
+}
+
+impl<M: Send + 'static> MessageBridge<M> {
+    fn spawn(
+        rx: mpsc::Receiver<Event<M>>,
+        wake: mpsc::SyncSender<Event<M>>,
+        room: MessageRoom,
+        capacity: usize,
+    ) -> Self {
+        let (tx, bridged) = tokio::sync::mpsc::channel(capacity.max(1));
+        let thread = thread::Builder::new()
+            .name("hojicha-messages".to_string())
+            .spawn(move || {
+                while let Ok(event) = rx.recv() {
+                    room.drained();
+                    if tx.blocking_send(event).is_err() {
+                        break; // The loop has gone
+                    }
+                }
+                drop(rx);
+                room.drained();
+            });
+        if let Err(e) = thread {
+            log::error!("Failed to start message bridge: {}", e);
+        }
+        Self { rx: bridged, wake }
+    }
+}
+
+impl<M> Drop for MessageBridge<M> {
+    fn drop(&mut self) {
+        self.rx.close();
+        // The thread stops on the first message it can't forward. If the
+        // channel is full, there is already one waiting for it.
+        let _ = self.wake.try_send(Event::Tick);
+    }
+}
+
+/// Wakes the senders waiting for room once the message receiver is gone
+///
+/// Declare it before the receiver, so it is dropped after it.
+struct RoomGuard(MessageRoom);
+
+impl Drop for RoomGuard {
+    fn drop(&mut self) {
+        self.0.drained();
+    }
+}
+
+/// Report a burst of terminal resizes as the last one
+///
+/// `ready` returns the terminal events already waiting. The first one that
+/// isn't a resize is returned as well, to be handled after the resize.
+fn coalesce_resize(
+    event: event::Event,
+    ready: impl FnMut() -> Option<event::Event>,
+) -> (event::Event, Option<event::Event>) {
+    match event {
+        event::Event::Resize(width, height) => {
+            let ((width, height), next) = EventProcessor::coalesce_resizes(width, height, ready);
+            (event::Event::Resize(width, height), next)
+        }
+        event => (event, None),
+    }
+}
+
+/// Wait for the next event from the terminal, or forever if it isn't read
+///
+/// The stream is dropped once it ends or fails, so the loop doesn't spin.
+async fn next_terminal_event(stream: &mut Option<event::EventStream>) -> Option<event::Event> {
+    use futures::StreamExt;
+
+    let Some(events) = stream else {
+        return std::future::pending().await;
+    };
+    match events.next().await {
+        Some(Ok(event)) => Some(event),
+        Some(Err(e)) => {
+            log::warn!("Stopped reading the terminal: {}", e);
+            *stream = None;
+            None
+        }
+        None => {
+            *stream = None;
+            None
+        }
+    }
+}
+
+/// Whether handling the event hands the terminal to another process
+fn releases_terminal<M>(event: &Event<M>) -> bool {
+    matches!(event, Event::ExecProcess | Event::Suspend) || EventProcessor::is_suspend_event(event)
+}
+
+/// Run blocking work, moving other tasks off the worker if this is one
+///
+/// Outside a multi-threaded Tokio runtime the work simply runs in place.
+fn block_in_place<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// Run an external process with inherited stdio and return its exit code
+///
+/// Returns `None` if the process couldn't be started or was killed by a signal.
+fn run_process(program: &str, args: &[String]) -> Option<i32> {
+    match std::process::Command::new(program).args(args).status() {
+        Ok(status) => status.code(),
+        Err(e) => {
+            log::error!("Failed to run {}: {}", program, e);
+            None
+        }
+    }
+}
+
+/// Print a line that has no inline viewport to go above
+fn print_to_stderr(text: &str) {
+    eprintln!("{text}");
+    let _ = io::stderr().flush();
+}
+
+/// Stop the process with SIGTSTP, returning once it has been continued
+///
+/// If SIGTSTP is ignored (e.g. the process group is orphaned) this returns
+/// immediately.
+#[cfg(unix)]
+fn stop_process() {
+    // SAFETY: raise has no preconditions; the default SIGTSTP action stops the
+    // whole process and execution resumes here after SIGCONT
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+}
+
+/// Job control isn't available, so suspending is a no-op
+#[cfg(not(unix))]
+fn stop_process() {
+    log::warn!("Suspend is not supported on this platform");
 }
 
 impl<M: Model> Drop for Program<M> {
     fn drop(&mut self) {
         let _ = self.terminal_manager.cleanup();
 
+        // Senders waiting on a program that never ran find it gone
+        if self.message_rx.take().is_some() {
+            self.command_executor.message_room().drained();
+        }
+
         // Stop the input thread
         self.running.store(false, Ordering::SeqCst);
         self.force_quit.store(true, Ordering::SeqCst);
@@ -1059,6 +2009,39 @@ mod tests {
         assert!(options.without_renderer);
     }
 
+    #[test]
+    fn test_pause_input_waits_for_the_input_thread() {
+        struct TestModel;
+        impl Model for TestModel {
+            type Message = ();
+            fn update(&mut self, _: Event<Self::Message>) -> hojicha_core::core::Cmd<()> {
+                hojicha_core::core::Cmd::none()
+            }
+            fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+        }
+
+        let options = ProgramOptions::default().headless();
+        let mut program = Program::with_options(TestModel, options).unwrap();
+        let stop = Arc::new(AtomicBool::new(false));
+        let busy = Arc::clone(&stop);
+        program.input_thread = Some(thread::spawn(move || {
+            while !busy.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }));
+
+        // A thread that never goes idle isn't waited for forever
+        assert!(matches!(program.pause_input(), Err(Error::Terminal(_))));
+        assert!(!program.input_paused.load(Ordering::SeqCst));
+
+        program.input_idle.store(true, Ordering::SeqCst);
+        assert!(program.pause_input().is_ok());
+        assert!(program.input_paused.load(Ordering::SeqCst));
+
+        stop.store(true, Ordering::SeqCst);
+        program.input_thread.take().unwrap().join().unwrap();
+    }
+
     #[test]
     fn test_mouse_mode_default() {
         assert_eq!(MouseMode::default(), MouseMode::None);
@@ -1134,4 +2117,74 @@ mod tests {
             _ => Some(event),
         });
     }
+
+    #[test]
+    fn test_every_stops_when_program_quits() {
+        use hojicha_core::commands;
+        use hojicha_core::core::Cmd;
+        use std::sync::atomic::AtomicUsize;
+
+        struct TimerModel {
+            ticks: Arc<AtomicUsize>,
+        }
+        impl Model for TimerModel {
+            type Message = ();
+            fn init(&mut self) -> Cmd<Self::Message> {
+                let ticks = self.ticks.clone();
+                commands::every(Duration::from_millis(5), move |_| {
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                })
+            }
+            fn update(&mut self, _: Event<Self::Message>) -> Cmd<Self::Message> {
+                Cmd::none()
+            }
+            fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+        }
+
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let model = TimerModel {
+            ticks: ticks.clone(),
+        };
+        let program = Program::with_options(model, ProgramOptions::default().headless()).unwrap();
+        program
+            .run_until(|model: &TimerModel| model.ticks.load(Ordering::SeqCst) >= 3)
+            .unwrap();
+
+        let after_quit = ticks.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(ticks.load(Ordering::SeqCst), after_quit);
+    }
+
+    #[test]
+    fn test_terminal_control_is_not_delivered_to_model() {
+        use hojicha_core::commands;
+        use hojicha_core::core::Cmd;
+
+        struct ControlModel {
+            done: bool,
+        }
+        impl Model for ControlModel {
+            type Message = ();
+            fn init(&mut self) -> Cmd<Self::Message> {
+                commands::sequence(vec![
+                    commands::enter_alt_screen(),
+                    commands::enable_mouse_cell_motion(),
+                    commands::set_window_title("test"),
+                    commands::custom(|| Some(())),
+                ])
+            }
+            fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+                assert!(!matches!(event, Event::TerminalControl(_)));
+                if event.is_user() {
+                    self.done = true;
+                }
+                Cmd::none()
+            }
+            fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+        }
+
+        let model = ControlModel { done: false };
+        let program = Program::with_options(model, ProgramOptions::default().headless()).unwrap();
+        program.run_until(|model: &ControlModel| model.done).unwrap();
+    }
 }
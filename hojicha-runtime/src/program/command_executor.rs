@@ -1,23 +1,189 @@
 //! Command execution logic extracted from Program for testability
 
 use super::error_handler::{DefaultErrorHandler, ErrorHandler};
+use super::handle::MessageRoom;
 use crate::resource_limits::{ResourceMonitor, ResourceLimits};
 use crate::panic_utils;
-use hojicha_core::core::Cmd;
+use futures::future::{self, BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+use hojicha_core::core::{Cmd, KeyPolicy};
 use hojicha_core::event::Event;
+use std::collections::{HashMap, VecDeque};
 use std::panic::{self, AssertUnwindSafe};
-use std::sync::{Arc, mpsc};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::runtime::Runtime;
-use log::{warn, error};
+use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use tokio::time::MissedTickBehavior;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+use log::{debug, warn, error};
+
+/// Callback that turns a child's exit code into a message
+type ExecCallback<M> = Box<dyn Fn(Option<i32>) -> M + Send>;
+
+/// An external process waiting to be run by the program loop
+///
+/// Exec commands can't run on the async runtime because the child needs the
+/// terminal. The executor queues them here and wakes the program loop with
+/// `Event::ExecProcess`; the loop releases the terminal, runs the child, queues
+/// [`ExecRequest::message`] for the model and then calls [`ExecRequest::finish`].
+pub struct ExecRequest<M> {
+    /// Program to run
+    pub program: String,
+    /// Arguments passed to the program
+    pub args: Vec<String>,
+    callback: ExecCallback<M>,
+    done: Option<oneshot::Sender<()>>,
+}
+
+impl<M> ExecRequest<M> {
+    /// Build the callback message for the given exit code
+    pub fn message(&self, exit_code: Option<i32>) -> M {
+        (self.callback)(exit_code)
+    }
+
+    /// Signal that the process has finished
+    ///
+    /// A sequence waiting on this process continues with its next command.
+    pub fn finish(mut self) {
+        if let Some(done) = self.done.take() {
+            let _ = done.send(());
+        }
+    }
+}
+
+impl<M> std::fmt::Debug for ExecRequest<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecRequest")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+/// A keyed command that is running, with the commands queued behind it
+struct KeyedTask {
+    id: u64,
+    cancel_token: CancellationToken,
+    queued: VecDeque<BoxFuture<'static, ()>>,
+}
+
+/// Keyed commands that are running, by key
+struct KeyedTasks {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<String, KeyedTask>>,
+}
+
+impl KeyedTasks {
+    fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a new running command under `key`
+    fn start(&self, key: String, tasks: &mut HashMap<String, KeyedTask>) -> (u64, CancellationToken) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancel_token = CancellationToken::new();
+        let task = KeyedTask {
+            id,
+            cancel_token: cancel_token.clone(),
+            queued: tasks.remove(&key).map(|task| task.queued).unwrap_or_default(),
+        };
+        tasks.insert(key, task);
+        (id, cancel_token)
+    }
+}
+
+/// A runtime created by the executor
+///
+/// Running tasks hold on to the executor, so the last reference can go away
+/// inside one of the runtime's own tasks, where a runtime can't be dropped
+/// normally. It is shut down in the background from async contexts instead.
+pub(crate) struct OwnedRuntime(Option<Runtime>);
+
+impl OwnedRuntime {
+    fn new() -> std::io::Result<Self> {
+        Ok(Self(Some(Runtime::new()?)))
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.0.as_ref().expect("runtime is only taken when dropped")
+    }
+}
+
+impl Drop for OwnedRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            if Handle::try_current().is_ok() {
+                runtime.shutdown_background();
+            }
+        }
+    }
+}
+
+/// The Tokio runtime commands are spawned on
+#[derive(Clone)]
+enum RuntimeRef {
+    /// A runtime created and owned by the executor
+    Owned(Arc<OwnedRuntime>),
+    /// A runtime owned by the caller, such as the one running `Program::run_async`
+    Shared(Handle),
+}
+
+impl RuntimeRef {
+    /// Use the caller's runtime if there is one, otherwise create one
+    ///
+    /// A current-thread runtime only runs tasks while something blocks on
+    /// it, so a program run synchronously from one gets a runtime of its own.
+    fn current_or_new() -> std::io::Result<Self> {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                Ok(RuntimeRef::Shared(handle))
+            }
+            _ => Ok(RuntimeRef::Owned(Arc::new(OwnedRuntime::new()?))),
+        }
+    }
+
+    fn handle(&self) -> &Handle {
+        match self {
+            RuntimeRef::Owned(runtime) => runtime.runtime().handle(),
+            RuntimeRef::Shared(handle) => handle,
+        }
+    }
+}
 
 /// Executes commands and sends resulting messages
+///
+/// Commands are spawned on the caller's Tokio runtime when the executor is
+/// created inside a multi-threaded one, and on a runtime of its own otherwise.
 #[derive(Clone)]
 pub struct CommandExecutor<M = ()> {
-    runtime: Arc<Runtime>,
+    runtime: RuntimeRef,
     error_handler: Arc<dyn ErrorHandler<M> + Send + Sync>,
     resource_monitor: Arc<ResourceMonitor>,
     recursion_depth: Arc<AtomicUsize>,
+    pending_exec: Arc<Mutex<VecDeque<ExecRequest<M>>>>,
+    keyed: Arc<KeyedTasks>,
+    message_room: MessageRoom,
+}
+
+impl<M> CommandExecutor<M> {
+    /// Tell commands waiting for room in a full channel to try again
+    ///
+    /// Call this after receiving from the channel given to
+    /// [`execute`](Self::execute), and once more after dropping its receiver.
+    /// The program loop does this itself.
+    pub fn message_drained(&self) {
+        self.message_room.drained();
+    }
+
+    /// The room commands wait on, shared with `ProgramHandle`
+    pub(crate) fn message_room(&self) -> &MessageRoom {
+        &self.message_room
+    }
 }
 
 impl<M> CommandExecutor<M>
@@ -27,10 +193,13 @@ where
     /// Create a new command executor with default error handler
     pub fn new() -> std::io::Result<Self> {
         Ok(Self {
-            runtime: Arc::new(Runtime::new()?),
+            runtime: RuntimeRef::current_or_new()?,
             error_handler: Arc::new(DefaultErrorHandler),
             resource_monitor: Arc::new(ResourceMonitor::new()),
             recursion_depth: Arc::new(AtomicUsize::new(0)),
+            pending_exec: Arc::new(Mutex::new(VecDeque::new())),
+            keyed: Arc::new(KeyedTasks::new()),
+            message_room: MessageRoom::default(),
         })
     }
 
@@ -40,27 +209,93 @@ where
         H: ErrorHandler<M> + Send + Sync + 'static,
     {
         Ok(Self {
-            runtime: Arc::new(Runtime::new()?),
+            runtime: RuntimeRef::current_or_new()?,
             error_handler: Arc::new(error_handler),
             resource_monitor: Arc::new(ResourceMonitor::new()),
             recursion_depth: Arc::new(AtomicUsize::new(0)),
+            pending_exec: Arc::new(Mutex::new(VecDeque::new())),
+            keyed: Arc::new(KeyedTasks::new()),
+            message_room: MessageRoom::default(),
         })
     }
     
     /// Create a new command executor with custom resource limits
     pub fn with_resource_limits(limits: ResourceLimits) -> std::io::Result<Self> {
         Ok(Self {
-            runtime: Arc::new(Runtime::new()?),
+            runtime: RuntimeRef::current_or_new()?,
             error_handler: Arc::new(DefaultErrorHandler),
             resource_monitor: Arc::new(ResourceMonitor::with_limits(limits)),
             recursion_depth: Arc::new(AtomicUsize::new(0)),
+            pending_exec: Arc::new(Mutex::new(VecDeque::new())),
+            keyed: Arc::new(KeyedTasks::new()),
+            message_room: MessageRoom::default(),
         })
     }
     
+    /// Replace the error handler for commands executed from now on
+    pub fn set_error_handler<H>(&mut self, error_handler: H)
+    where
+        H: ErrorHandler<M> + Send + Sync + 'static,
+    {
+        self.error_handler = Arc::new(error_handler);
+    }
+
     /// Get current resource statistics
     pub fn resource_stats(&self) -> crate::resource_limits::ResourceStats {
         self.resource_monitor.stats()
     }
+
+    /// Get a handle to the executor's Tokio runtime
+    pub fn runtime_handle(&self) -> tokio::runtime::Handle {
+        self.runtime.handle().clone()
+    }
+
+    /// Spawn commands on a runtime owned by the caller from now on
+    ///
+    /// Returns the runtime the executor created, if any. Tasks already running
+    /// on it keep running until the caller drops it.
+    pub(crate) fn use_runtime_handle(&mut self, handle: Handle) -> Option<Arc<OwnedRuntime>> {
+        match std::mem::replace(&mut self.runtime, RuntimeRef::Shared(handle)) {
+            RuntimeRef::Owned(runtime) => Some(runtime),
+            RuntimeRef::Shared(_) => None,
+        }
+    }
+
+    /// A copy of the executor for its own tasks to hold
+    ///
+    /// It refers to the runtime by handle, so running tasks don't keep a
+    /// runtime the executor created alive after the program lets go of it.
+    fn for_tasks(&self) -> Self {
+        Self {
+            runtime: RuntimeRef::Shared(self.runtime.handle().clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Take the next external process queued by an exec command
+    ///
+    /// The program loop calls this whenever it receives `Event::ExecProcess`.
+    pub fn take_exec_request(&self) -> Option<ExecRequest<M>> {
+        self.pending_exec.lock().unwrap().pop_front()
+    }
+
+    /// Queue an exec request and wake the program loop
+    fn queue_exec(
+        &self,
+        cmd: Cmd<M>,
+        done: Option<oneshot::Sender<()>>,
+        tx: &mpsc::SyncSender<Event<M>>,
+    ) {
+        if let Some((program, args, callback)) = cmd.take_exec_process() {
+            self.pending_exec.lock().unwrap().push_back(ExecRequest {
+                program,
+                args,
+                callback,
+                done,
+            });
+            let _ = tx.send(Event::ExecProcess);
+        }
+    }
     
     /// Spawn a task with resource limit checking
     fn spawn_with_limits<F>(&self, f: F)
@@ -68,10 +303,8 @@ where
         F: std::future::Future<Output = ()> + Send + 'static,
     {
         let monitor = self.resource_monitor.clone();
-        let runtime = self.runtime.clone();
-        
         // Try to spawn with resource checking
-        runtime.spawn(async move {
+        self.runtime.handle().spawn(async move {
             match monitor.try_acquire_task_permit().await {
                 Ok(_permit) => {
                     // Permit will be dropped when task completes
@@ -86,113 +319,107 @@ where
 
     /// Execute a command and send the result through the channel
     pub fn execute(&self, cmd: Cmd<M>, tx: mpsc::SyncSender<Event<M>>) {
-        if cmd.is_noop() {
-            // NoOp command - do nothing
-        } else if cmd.is_quit() {
-            // Handle quit command by sending a special quit event
-            let _ = tx.send(Event::Quit);
-        } else if cmd.is_exec_process() {
-            // Handle exec process commands specially
-            if let Some((_program, _args, _callback)) = cmd.take_exec_process() {
-                // For testing, we just send an ExecProcess event
-                // The actual process execution would be handled by the Program
-                let _ = tx.send(Event::ExecProcess);
-            }
+        if cmd.is_exec_process() {
+            // The child needs the terminal, so hand it to the program loop
+            self.queue_exec(cmd, None, &tx);
         } else if cmd.is_batch() {
             // Handle batch commands by executing them concurrently
             if let Some(cmds) = cmd.take_batch() {
                 self.execute_batch(cmds, tx);
             }
-        } else if cmd.is_sequence() {
-            // Handle sequence commands by executing them in order
-            if let Some(cmds) = cmd.take_sequence() {
-                self.execute_sequence(cmds, tx);
-            }
-        } else if cmd.is_tick() {
-            // Handle tick command with async delay
-            if let Some((duration, callback)) = cmd.take_tick() {
-                let tx_clone = tx.clone();
-                self.spawn_with_limits(async move {
-                    tokio::time::sleep(duration).await;
-                    // Wrap callback execution in panic recovery
-                    let result = panic::catch_unwind(AssertUnwindSafe(|| callback()));
-                    match result {
-                        Ok(msg) => {
-                            let _ = tx_clone.send(Event::User(msg));
-                        }
-                        Err(panic) => {
-                            let panic_msg = panic_utils::format_panic_message(panic, "Tick callback panicked");
-                            eprintln!("{}", panic_msg);
-                            // Continue running - don't crash the application
-                        }
-                    }
-                });
+        } else if let Some(cmd) = self.dispatch(cmd, &tx) {
+            // Everything else runs in a task of its own (like Bubbletea's goroutines)
+            self.spawn_with_limits(self.drive(cmd, tx));
+        }
+    }
+
+    /// Handle the commands that only tell the program loop or the executor
+    /// something, and hand back the ones that have to run
+    fn dispatch(&self, cmd: Cmd<M>, tx: &mpsc::SyncSender<Event<M>>) -> Option<Cmd<M>> {
+        if cmd.is_noop() || cmd.is_cancel_suspend() {
+            // Nothing to do; the program loop looks for suspend vetoes itself
+        } else if cmd.is_quit() {
+            // Handle quit command by sending a special quit event
+            let _ = tx.send(Event::Quit);
+        } else if cmd.is_suspend() {
+            // The program loop asks the model and then suspends
+            let _ = tx.send(Event::Suspend);
+        } else if cmd.is_terminal_control() {
+            // Only the program loop may touch the terminal
+            if let Some(msg) = cmd.take_terminal_control() {
+                let _ = tx.send(Event::TerminalControl(msg));
             }
-        } else if cmd.is_every() {
-            // Handle every command with recurring async timer
-            if let Some((duration, callback)) = cmd.take_every() {
-                let tx_clone = tx.clone();
-                self.spawn_with_limits(async move {
-                    // Since callback is FnOnce, we can only call it once
-                    // For now, just execute once after delay
-                    tokio::time::sleep(duration).await;
-                    // Wrap callback execution in panic recovery
-                    let result = panic::catch_unwind(AssertUnwindSafe(|| callback(std::time::Instant::now())));
-                    match result {
-                        Ok(msg) => {
-                            let _ = tx_clone.send(Event::User(msg));
-                        }
-                        Err(panic) => {
-                            let panic_msg = panic_utils::format_panic_message(panic, "Every callback panicked");
-                            eprintln!("{}", panic_msg);
-                            // Continue running - don't crash the application
-                        }
-                    }
-                });
+        } else if cmd.is_keyed() {
+            // Track the command under its key so it can be superseded or cancelled
+            if let Some((key, policy, cmd)) = cmd.take_keyed() {
+                self.execute_keyed(key, policy, cmd, tx.clone());
             }
-        } else if cmd.is_async() {
-            // Handle async command using shared runtime
-            if let Some(future) = cmd.take_async() {
-                let tx_clone = tx.clone();
-                self.spawn_with_limits(async move {
-                    // The future is already boxed, just need to pin it
-                    use std::pin::Pin;
-                    let mut future = future;
-                    let future = unsafe { Pin::new_unchecked(&mut *future) };
-                    
-                    // Actually await the future
-                    if let Some(msg) = future.await {
-                        let _ = tx_clone.send(Event::User(msg));
-                    }
-                });
+        } else if cmd.is_cancel() {
+            if let Some(key) = cmd.take_cancel() {
+                self.cancel_keyed(&key);
             }
         } else {
-            // Spawn async task for regular command execution (like Bubbletea's goroutines)
-            let tx_clone = tx.clone();
-            let error_handler = self.error_handler.clone();
-            self.spawn_with_limits(async move {
-                // Wrap command execution in panic recovery
-                let result = panic::catch_unwind(AssertUnwindSafe(|| cmd.execute()));
-                
-                match result {
-                    Ok(Ok(Some(msg))) => {
-                        let _ = tx_clone.send(Event::User(msg));
-                    }
-                    Ok(Ok(None)) => {
-                        // Command executed successfully but produced no message
-                    }
-                    Ok(Err(error)) => {
-                        // Use the configured error handler
-                        error_handler.handle_error(error, &tx_clone);
-                    }
-                    Err(panic) => {
-                        // Command panicked - log and recover
-                        let panic_msg = panic_utils::format_panic_message(panic, "Command execution panicked");
-                        eprintln!("{}", panic_msg);
-                        // Continue running - don't crash the application
-                    }
+            return Some(cmd);
+        }
+        None
+    }
+
+    /// A future that runs a synchronous command on the blocking pool and
+    /// sends what it returns
+    ///
+    /// It waits for a blocking permit first, so at most `max_blocking_tasks`
+    /// commands occupy blocking threads while the async workers stay free for
+    /// timers and streams. Dropping the future discards the result, which is
+    /// how keyed commands are cancelled.
+    fn run_blocking(
+        &self,
+        cmd: Cmd<M>,
+        tx: mpsc::SyncSender<Event<M>>,
+    ) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let monitor = self.resource_monitor.clone();
+        let error_handler = self.error_handler.clone();
+        let room = self.message_room().clone();
+        async move {
+            let permit = match monitor.acquire_blocking_permit().await {
+                Ok(permit) => permit,
+                Err(e) => {
+                    error!("Failed to run blocking command: {}", e);
+                    return;
                 }
+            };
+            let task = tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                // Wrap command execution in panic recovery
+                panic::catch_unwind(AssertUnwindSafe(|| cmd.execute()))
             });
+
+            // Nothing is sent once the command is cancelled, so the result is
+            // only handled here, without blocking an async worker
+            match task.await {
+                Ok(Ok(Ok(Some(msg)))) => {
+                    let _ = room.send(&tx, Event::User(msg), None).await;
+                }
+                Ok(Ok(Ok(None))) => {
+                    // Command executed successfully but produced no message
+                }
+                Ok(Ok(Err(error))) => {
+                    // Use the configured error handler
+                    let _ = tokio::task::spawn_blocking(move || {
+                        error_handler.handle_error(error, &tx);
+                    })
+                    .await;
+                }
+                Ok(Err(panic)) => {
+                    // Command panicked - log and recover
+                    let panic_msg =
+                        panic_utils::format_panic_message(panic, "Command execution panicked");
+                    eprintln!("{}", panic_msg);
+                    // Continue running - don't crash the application
+                }
+                Err(e) => {
+                    error!("Blocking command did not finish: {}", e);
+                }
+            }
         }
     }
 
@@ -206,80 +433,192 @@ where
     }
 
     /// Execute a sequence of commands (one after another)
+    ///
+    /// Each command runs to completion before the next one starts.
     pub fn execute_sequence(&self, commands: Vec<Cmd<M>>, tx: mpsc::SyncSender<Event<M>>) {
-        // Spawn async task to execute commands in sequence
-        let tx_clone = tx.clone();
-        let error_handler = self.error_handler.clone();
+        let executor = self.for_tasks();
         self.spawn_with_limits(async move {
             for cmd in commands {
-                let tx_inner = tx_clone.clone();
-
-                // Execute the command through the regular execution path
-                if cmd.is_tick() {
-                    if let Some((duration, callback)) = cmd.take_tick() {
-                        tokio::time::sleep(duration).await;
-                        // Wrap callback execution in panic recovery
-                        let result = panic::catch_unwind(AssertUnwindSafe(|| callback()));
-                        match result {
-                            Ok(msg) => {
-                                let _ = tx_inner.send(Event::User(msg));
-                            }
-                            Err(panic) => {
-                                let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
-                                    s.clone()
-                                } else if let Some(s) = panic.downcast_ref::<&str>() {
-                                    s.to_string()
-                                } else {
-                                    "Unknown panic in tick callback".to_string()
-                                };
-                                eprintln!("Tick callback panicked: {}", panic_msg);
-                                // Continue running - don't crash the application
-                            }
-                        }
+                executor.drive(cmd, tx.clone()).await;
+            }
+        });
+    }
+
+    /// Start a keyed command, applying `policy` if its key is still running
+    fn execute_keyed(
+        &self,
+        key: String,
+        policy: KeyPolicy,
+        cmd: Cmd<M>,
+        tx: mpsc::SyncSender<Event<M>>,
+    ) {
+        let mut running = self.keyed.running.lock().unwrap();
+        if let Some(task) = running.get_mut(&key) {
+            match policy {
+                KeyPolicy::Supersede => {
+                    debug!("Superseding keyed command {:?}", key);
+                    task.cancel_token.cancel();
+                    task.queued.clear();
+                }
+                KeyPolicy::Queue => {
+                    task.queued.push_back(self.drive(cmd, tx));
+                    return;
+                }
+                KeyPolicy::Drop => {
+                    debug!("Keyed command {:?} is still running, dropping the new one", key);
+                    return;
+                }
+            }
+        }
+
+        let (id, cancel_token) = self.keyed.start(key.clone(), &mut running);
+        drop(running);
+        let run = self.drive(cmd, tx.clone());
+        self.spawn_keyed(key, id, cancel_token, run, tx);
+    }
+
+    /// Run a keyed command until it finishes or its token is cancelled, then
+    /// start the next command queued under its key
+    ///
+    /// A command rejected by the task limit counts as finished, so its key is
+    /// released and the commands queued behind it still run. The task holds on
+    /// to `tx` until then, so a receiver never sees every sender gone while the
+    /// key still looks busy.
+    fn spawn_keyed(
+        &self,
+        key: String,
+        id: u64,
+        cancel_token: CancellationToken,
+        run: BoxFuture<'static, ()>,
+        tx: mpsc::SyncSender<Event<M>>,
+    ) {
+        let executor = self.for_tasks();
+        let monitor = self.resource_monitor.clone();
+        self.runtime.handle().spawn(async move {
+            match monitor.try_acquire_task_permit().await {
+                Ok(_permit) => {
+                    tokio::select! {
+                        biased;
+                        _ = cancel_token.cancelled() => return,
+                        _ = run => {}
                     }
-                } else if cmd.is_every() {
-                    if let Some((duration, callback)) = cmd.take_every() {
-                        tokio::time::sleep(duration).await;
-                        // Wrap callback execution in panic recovery
-                        let result = panic::catch_unwind(AssertUnwindSafe(|| callback(std::time::Instant::now())));
-                        match result {
-                            Ok(msg) => {
-                                let _ = tx_inner.send(Event::User(msg));
-                            }
-                            Err(panic) => {
-                                let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
-                                    s.clone()
-                                } else if let Some(s) = panic.downcast_ref::<&str>() {
-                                    s.to_string()
-                                } else {
-                                    "Unknown panic in every callback".to_string()
-                                };
-                                eprintln!("Every callback panicked: {}", panic_msg);
-                                // Continue running - don't crash the application
-                            }
-                        }
+                }
+                Err(e) => {
+                    error!("Failed to spawn keyed command {:?}: {}", key, e);
+                }
+            }
+            executor.finish_keyed(key, id, tx);
+        });
+    }
+
+    /// Start the next command queued under `key`, or release the key
+    fn finish_keyed(&self, key: String, id: u64, tx: mpsc::SyncSender<Event<M>>) {
+        let mut running = self.keyed.running.lock().unwrap();
+        let next = match running.get_mut(&key) {
+            Some(task) if task.id == id => task.queued.pop_front(),
+            _ => return, // Superseded or cancelled meanwhile
+        };
+        match next {
+            Some(next) => {
+                let (id, cancel_token) = self.keyed.start(key.clone(), &mut running);
+                drop(running);
+                self.spawn_keyed(key, id, cancel_token, next, tx);
+            }
+            None => {
+                running.remove(&key);
+            }
+        }
+    }
+
+    /// Cancel the keyed command running under `key` and everything queued behind it
+    fn cancel_keyed(&self, key: &str) {
+        if let Some(task) = self.keyed.running.lock().unwrap().remove(key) {
+            debug!("Cancelling keyed command {:?}", key);
+            task.cancel_token.cancel();
+        }
+    }
+
+    /// Whether a keyed command is running under `key`
+    pub fn is_key_running(&self, key: &str) -> bool {
+        self.keyed.running.lock().unwrap().contains_key(key)
+    }
+
+    /// A future that runs `cmd` to completion and sends its messages
+    ///
+    /// Every way of running a command goes through here: `execute` spawns the
+    /// future, sequences await it for each step and keyed commands race it
+    /// against their cancellation token. Dropping the future cancels whatever
+    /// the command is still waiting on.
+    fn drive(&self, cmd: Cmd<M>, tx: mpsc::SyncSender<Event<M>>) -> BoxFuture<'static, ()> {
+        let executor = self.for_tasks();
+        async move {
+            let Some(cmd) = executor.dispatch(cmd, &tx) else {
+                return;
+            };
+            if cmd.is_batch() {
+                if let Some(cmds) = cmd.take_batch() {
+                    let runs = cmds.into_iter().map(|cmd| executor.drive(cmd, tx.clone()));
+                    future::join_all(runs).await;
+                }
+            } else if cmd.is_sequence() {
+                if let Some(cmds) = cmd.take_sequence() {
+                    for cmd in cmds {
+                        executor.drive(cmd, tx.clone()).await;
                     }
-                } else {
-                    // Regular command execution with panic recovery
-                    let result = panic::catch_unwind(AssertUnwindSafe(|| cmd.execute()));
-                    match result {
-                        Ok(Ok(Some(msg))) => {
-                            let _ = tx_inner.send(Event::User(msg));
-                        }
-                        Ok(Ok(None)) => {}
-                        Ok(Err(error)) => {
-                            // Use the configured error handler
-                            error_handler.handle_error(error, &tx_inner);
+                }
+            } else if cmd.is_exec_process() {
+                // Wait for the child to exit before moving on
+                let (done_tx, done_rx) = oneshot::channel();
+                executor.queue_exec(cmd, Some(done_tx), &tx);
+                let _ = done_rx.await;
+            } else if cmd.is_tick() {
+                if let Some((duration, callback)) = cmd.take_tick() {
+                    tokio::time::sleep(duration).await;
+                    // Wrap callback execution in panic recovery
+                    match panic::catch_unwind(AssertUnwindSafe(callback)) {
+                        Ok(msg) => {
+                            let _ = tx.send(Event::User(msg));
                         }
                         Err(panic) => {
-                            let panic_msg = panic_utils::format_panic_message(panic, "Sequence command panicked");
+                            let panic_msg = panic_utils::format_panic_message(panic, "Tick callback panicked");
                             eprintln!("{}", panic_msg);
                             // Continue running - don't crash the application
                         }
                     }
                 }
+            } else if cmd.is_every() {
+                // A sequence waits here until the timer stops
+                if let Some((duration, wall_clock, callback)) = cmd.take_every() {
+                    run_every(duration, wall_clock, callback, tx).await;
+                }
+            } else if cmd.is_stream() {
+                // A sequence waits here until the stream ends
+                if let Some(stream) = cmd.take_stream() {
+                    run_stream(stream, tx, &executor.message_room).await;
+                }
+            } else if cmd.is_message() {
+                if let Some(msg) = cmd.take_message() {
+                    let _ = executor
+                        .message_room
+                        .send(&tx, Event::User(msg), None)
+                        .await;
+                }
+            } else if cmd.is_async() {
+                if let Some(future) = cmd.take_async() {
+                    if let Some(msg) = Box::into_pin(future).await {
+                        let _ = executor
+                            .message_room
+                            .send(&tx, Event::User(msg), None)
+                            .await;
+                    }
+                }
+            } else if cmd.is_blocking() {
+                // Synchronous commands may block, so they get a blocking thread
+                // instead of an async worker
+                executor.run_blocking(cmd, tx).await;
             }
-        });
+        }
+        .boxed()
     }
 
     /// Spawn a future on the runtime with resource limit checking
@@ -289,10 +628,8 @@ where
         F::Output: Send + 'static,
     {
         let monitor = self.resource_monitor.clone();
-        let runtime = self.runtime.clone();
-        
         // Spawn wrapper task that acquires permit first
-        runtime.spawn(async move {
+        self.runtime.handle().spawn(async move {
             match monitor.try_acquire_task_permit().await {
                 Ok(_permit) => {
                     // Permit will be dropped when task completes
@@ -310,10 +647,88 @@ where
 
     /// Block on the runtime to ensure all tasks complete (for testing)
     pub fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
-        self.runtime.block_on(future)
+        match &self.runtime {
+            RuntimeRef::Owned(runtime) => runtime.runtime().block_on(future),
+            RuntimeRef::Shared(handle) => handle.block_on(future),
+        }
+    }
+}
+
+/// Send every message of a streaming command to the program
+///
+/// Stops when the stream ends or the program has shut down. While the event
+/// queue is full the stream waits on `room` instead of blocking the runtime, so
+/// a fast stream is slowed down to the pace of the program rather than losing
+/// messages.
+async fn run_stream<M>(
+    mut stream: BoxStream<'static, M>,
+    tx: mpsc::SyncSender<Event<M>>,
+    room: &MessageRoom,
+) {
+    while let Some(msg) = stream.next().await {
+        if room.send(&tx, Event::User(msg), None).await.is_err() {
+            return; // Program has shut down
+        }
+    }
+}
+
+/// Drive a recurring timer until its callback returns `None` or the program exits
+///
+/// Ticks are scheduled from a fixed start so they don't drift, and missed ticks
+/// are skipped. A tick is dropped if the event queue is full rather than
+/// blocking the runtime.
+async fn run_every<M, F>(
+    duration: Duration,
+    wall_clock: bool,
+    mut callback: F,
+    tx: mpsc::SyncSender<Event<M>>,
+) where
+    F: FnMut(std::time::Instant) -> Option<M>,
+{
+    if duration.is_zero() {
+        error!("Every command needs a non-zero interval");
+        return;
+    }
+
+    let first = if wall_clock {
+        until_wall_clock_boundary(duration)
+    } else {
+        duration
+    };
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + first, duration);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        let instant = interval.tick().await.into_std();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| callback(instant)));
+        match result {
+            Ok(Some(msg)) => match tx.try_send(Event::User(msg)) {
+                Ok(()) => {}
+                Err(mpsc::TrySendError::Full(_)) => {
+                    warn!("Event queue full, dropping timer tick");
+                }
+                Err(mpsc::TrySendError::Disconnected(_)) => break, // Program has shut down
+            },
+            Ok(None) => break, // The timer stopped itself
+            Err(panic) => {
+                // One bad tick shouldn't stop the timer
+                let panic_msg = panic_utils::format_panic_message(panic, "Every callback panicked");
+                error!("{}", panic_msg);
+            }
+        }
     }
 }
 
+/// Time left until the next multiple of `duration` since the Unix epoch
+fn until_wall_clock_boundary(duration: Duration) -> Duration {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let period = duration.as_nanos();
+    Duration::from_nanos((period - since_epoch % period) as u64)
+}
+
 impl<M> Default for CommandExecutor<M>
 where
     M: Clone + Send + 'static,
@@ -327,7 +742,8 @@ where
 mod tests {
     use super::*;
     use crate::testing::{AsyncTestHarness, CmdTestExt};
-    use hojicha_core::commands;
+    use hojicha_core::commands::{self, TerminalControlMsg};
+    use std::sync::atomic::AtomicBool;
     use std::time::Duration;
 
     #[derive(Debug, Clone, PartialEq)]
@@ -457,6 +873,84 @@ mod tests {
         assert_eq!(messages[1], TestMsg::Dec);
     }
 
+    #[test]
+    fn test_execute_exec_queues_request() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let cmd = commands::exec("vim", vec!["notes.txt"], |code| {
+            TestMsg::Text(format!("{code:?}"))
+        });
+        executor.execute(cmd, tx);
+
+        // The program loop is woken up and the request is waiting for it
+        assert_eq!(rx.try_recv().unwrap(), Event::ExecProcess);
+        let request = executor.take_exec_request().unwrap();
+        assert_eq!(request.program, "vim");
+        assert_eq!(request.args, vec!["notes.txt"]);
+        assert_eq!(request.message(Some(0)), TestMsg::Text("Some(0)".to_string()));
+        request.finish();
+        assert!(executor.take_exec_request().is_none());
+    }
+
+    #[test]
+    fn test_execute_suspend_wakes_program_loop() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        executor.execute(commands::suspend(), tx);
+        assert_eq!(rx.try_recv().unwrap(), Event::Suspend);
+    }
+
+    #[test]
+    fn test_execute_terminal_control_goes_to_program_loop() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        executor.execute(
+            commands::sequence(vec![commands::hide_cursor(), commands::set_window_title("t")]),
+            tx,
+        );
+
+        let timeout = Duration::from_secs(2);
+        assert_eq!(
+            rx.recv_timeout(timeout).unwrap(),
+            Event::TerminalControl(TerminalControlMsg::HideCursor)
+        );
+        assert_eq!(
+            rx.recv_timeout(timeout).unwrap(),
+            Event::TerminalControl(TerminalControlMsg::SetWindowTitle("t".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sequence_waits_for_exec() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let seq = commands::sequence(vec![
+            commands::exec("true", Vec::<String>::new(), |_| TestMsg::Inc),
+            commands::custom(|| Some(TestMsg::Dec)),
+        ]);
+        executor.execute(seq, tx);
+
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(100)).unwrap(),
+            Event::ExecProcess
+        );
+        // Nothing after the exec runs until the process has finished
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(rx.try_recv().is_err());
+
+        let request = executor.take_exec_request().unwrap();
+        assert_eq!(request.message(Some(0)), TestMsg::Inc);
+        request.finish();
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(100)).unwrap(),
+            Event::User(TestMsg::Dec)
+        );
+    }
+
     #[test]
     fn test_multiple_executors() {
         let executor1 = CommandExecutor::<TestMsg>::new().unwrap();
@@ -478,6 +972,424 @@ mod tests {
         assert!(events.contains(&TestMsg::Inc));
         assert!(events.contains(&TestMsg::Dec));
     }
-}
 
+    #[test]
+    fn test_every_keeps_firing_with_state() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let mut count = 0;
+        executor.execute(
+            commands::every(Duration::from_millis(10), move |_| {
+                count += 1;
+                TestMsg::Text(count.to_string())
+            }),
+            tx,
+        );
+
+        for expected in 1..=5 {
+            let event = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+            assert_eq!(event, Event::User(TestMsg::Text(expected.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_every_until_stops_on_none() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let mut remaining = 3;
+        executor.execute(
+            commands::every_until(Duration::from_millis(5), move |_| {
+                remaining -= 1;
+                (remaining >= 0).then_some(TestMsg::Inc)
+            }),
+            tx,
+        );
+
+        for _ in 0..3 {
+            assert_eq!(
+                rx.recv_timeout(Duration::from_secs(2)).unwrap(),
+                Event::User(TestMsg::Inc)
+            );
+        }
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn test_every_keeps_ticking_after_a_panic() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let mut count = 0;
+        executor.execute(
+            commands::every(Duration::from_millis(5), move |_| {
+                count += 1;
+                if count == 2 {
+                    panic!("bad tick");
+                }
+                TestMsg::Text(count.to_string())
+            }),
+            tx,
+        );
+
+        for expected in ["1", "3", "4"] {
+            let event = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+            assert_eq!(event, Event::User(TestMsg::Text(expected.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_every_does_not_drift() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(20);
+
+        // A slow callback must not push later ticks back
+        let start = std::time::Instant::now();
+        executor.execute(
+            commands::every(Duration::from_millis(30), |_| {
+                std::thread::sleep(Duration::from_millis(20));
+                TestMsg::Inc
+            }),
+            tx,
+        );
+        for _ in 0..10 {
+            rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        }
+
+        // Ten ticks are due by 300ms; sleeping between ticks would take 500ms
+        let elapsed = start.elapsed();
+        assert!(elapsed < Duration::from_millis(450), "took {elapsed:?}");
+    }
+
+    #[test]
+    fn test_every_in_sequence_blocks_until_stopped() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let mut fired = false;
+        let seq = commands::sequence(vec![
+            commands::every_until(Duration::from_millis(5), move |_| {
+                (!std::mem::replace(&mut fired, true)).then_some(TestMsg::Inc)
+            }),
+            commands::custom(|| Some(TestMsg::Dec)),
+        ]);
+        executor.execute(seq, tx);
+
+        let timeout = Duration::from_secs(2);
+        assert_eq!(rx.recv_timeout(timeout).unwrap(), Event::User(TestMsg::Inc));
+        assert_eq!(rx.recv_timeout(timeout).unwrap(), Event::User(TestMsg::Dec));
+    }
+
+    #[test]
+    fn test_stream_waits_for_a_full_queue() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(2);
+
+        let texts = (0..20).map(|i| TestMsg::Text(i.to_string()));
+        executor.execute(commands::stream(futures::stream::iter(texts.clone())), tx);
+
+        // Receive like the program loop does, waking the stream after each one
+        let mut messages = Vec::new();
+        while let Ok(Event::User(msg)) = rx.recv_timeout(Duration::from_millis(300)) {
+            messages.push(msg);
+            executor.message_drained();
+        }
+        assert_eq!(messages, texts.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_blocking_commands_do_not_starve_timers() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(100);
+
+        // More sleeping commands than there are async workers
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        for _ in 0..workers * 2 {
+            executor.execute(
+                commands::blocking(|| {
+                    std::thread::sleep(Duration::from_millis(300));
+                    TestMsg::Dec
+                }),
+                tx.clone(),
+            );
+        }
+        executor.execute(commands::tick(Duration::from_millis(20), || TestMsg::Inc), tx);
+
+        let first = rx.recv_timeout(Duration::from_millis(200)).unwrap();
+        assert_eq!(first, Event::User(TestMsg::Inc));
+    }
+
+    #[test]
+    fn test_blocking_commands_respect_the_limit() {
+        let limits = ResourceLimits::default().with_max_blocking_tasks(1);
+        let executor = CommandExecutor::<TestMsg>::with_resource_limits(limits).unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let start = std::time::Instant::now();
+        for _ in 0..2 {
+            executor.execute(
+                Cmd::new(|| {
+                    std::thread::sleep(Duration::from_millis(50));
+                    Some(TestMsg::Inc)
+                }),
+                tx.clone(),
+            );
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(executor.resource_stats().active_blocking_tasks, 1);
 
+        let messages = collect_messages(&rx, Duration::from_millis(300));
+        assert_eq!(messages, vec![TestMsg::Inc, TestMsg::Inc]);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(executor.resource_stats().active_blocking_tasks, 0);
+    }
+
+    #[test]
+    fn test_blocking_commands_wait_for_room_off_the_workers() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(1);
+        tx.send(Event::User(TestMsg::Dec)).unwrap();
+
+        // More waiting results than there are worker threads
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        for _ in 0..=workers {
+            executor.execute(Cmd::new(|| Some(TestMsg::Inc)), tx.clone());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = ran.clone();
+        executor.spawn(async move { flag.store(true, Ordering::SeqCst) });
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(ran.load(Ordering::SeqCst));
+
+        let mut received = 0;
+        while let Ok(Event::User(_)) = rx.recv_timeout(Duration::from_millis(300)) {
+            received += 1;
+            executor.message_drained();
+        }
+        assert_eq!(received, workers + 2);
+    }
+
+    #[test]
+    fn test_message_command_does_not_wait_for_blocking_threads() {
+        let limits = ResourceLimits::default().with_max_blocking_tasks(1);
+        let executor = CommandExecutor::<TestMsg>::with_resource_limits(limits).unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        executor.execute(
+            Cmd::new(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                Some(TestMsg::Dec)
+            }),
+            tx.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        executor.execute(commands::message(TestMsg::Inc), tx);
+
+        let messages = collect_messages(&rx, Duration::from_millis(300));
+        assert_eq!(messages, vec![TestMsg::Inc, TestMsg::Dec]);
+    }
+
+    #[test]
+    fn test_cancelled_blocking_command_sends_nothing() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let slow = commands::blocking(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            TestMsg::Dec
+        });
+        executor.execute(slow.keyed("load"), tx.clone());
+        std::thread::sleep(Duration::from_millis(10));
+        executor.execute(commands::cancel("load"), tx);
+
+        assert!(collect_messages(&rx, Duration::from_millis(150)).is_empty());
+    }
+
+    #[test]
+    fn test_stream_in_sequence_blocks_until_it_ends() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let seq = commands::sequence(vec![
+            commands::run_with_sender(|tx| async move {
+                for msg in [TestMsg::Inc, TestMsg::Inc] {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    let _ = tx.send(msg).await;
+                }
+            }),
+            commands::custom(|| Some(TestMsg::Dec)),
+        ]);
+        executor.execute(seq, tx);
+
+        let messages = collect_messages(&rx, Duration::from_millis(200));
+        assert_eq!(messages, vec![TestMsg::Inc, TestMsg::Inc, TestMsg::Dec]);
+    }
+
+    #[test]
+    fn test_cancel_keyed_stream() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let ticker = commands::run_with_sender(|tx| async move {
+            while tx.send(TestMsg::Inc).await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        executor.execute(ticker.keyed("ticker"), tx.clone());
+        assert!(!collect_messages(&rx, Duration::from_millis(50)).is_empty());
+
+        executor.execute(commands::cancel("ticker"), tx);
+        std::thread::sleep(Duration::from_millis(20));
+        while rx.try_recv().is_ok() {}
+        assert!(collect_messages(&rx, Duration::from_millis(50)).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_counts_as_a_task_until_it_exits() {
+        use hojicha_core::async_helpers::{spawn_process, ProcessEvent};
+
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        let (handle, cmd) = spawn_process("sleep", ["0.2"], |event| match event {
+            Ok(ProcessEvent::Exited(_)) => Some(TestMsg::Dec),
+            _ => None,
+        });
+        executor.execute(cmd, tx.clone());
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(handle.is_running());
+        assert_eq!(executor.resource_stats().active_tasks, 1);
+
+        let messages = collect_messages(&rx, Duration::from_millis(500));
+        assert_eq!(messages, vec![TestMsg::Dec]);
+        assert!(!handle.is_running());
+        assert_eq!(executor.resource_stats().active_tasks, 0);
+
+        // Cancelling the command kills the process
+        let (handle, cmd) = spawn_process("sleep", ["10"], |_| Some(TestMsg::Inc));
+        executor.execute(cmd.keyed("sleep"), tx.clone());
+        std::thread::sleep(Duration::from_millis(50));
+        let pid = handle.pid().unwrap();
+        executor.execute(commands::cancel("sleep"), tx);
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(executor.resource_stats().active_tasks, 0);
+        // The killed process is gone, or a zombie until it is reaped
+        #[cfg(target_os = "linux")]
+        if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            let state = stat.rsplit(") ").next().and_then(|rest| rest.chars().next());
+            assert_eq!(state, Some('Z'));
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = pid;
+    }
+
+    #[test]
+    fn test_wall_clock_boundary() {
+        let period = Duration::from_secs(1);
+        let wait = until_wall_clock_boundary(period);
+        assert!(wait > Duration::ZERO && wait <= period);
+
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() + wait;
+        let offset = at.as_millis() % 1000;
+        assert!(!(50..=950).contains(&offset), "offset {offset}ms");
+    }
+
+    /// A search that answers with its query after `delay`
+    fn search(query: &str, delay: u64) -> Cmd<TestMsg> {
+        let query = query.to_string();
+        commands::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Some(TestMsg::Text(query))
+        })
+    }
+
+    /// Collect the user messages that arrive within `timeout`
+    fn collect_messages(rx: &mpsc::Receiver<Event<TestMsg>>, timeout: Duration) -> Vec<TestMsg> {
+        let deadline = std::time::Instant::now() + timeout;
+        let mut messages = Vec::new();
+        while let Some(left) = deadline.checked_duration_since(std::time::Instant::now()) {
+            match rx.recv_timeout(left) {
+                Ok(Event::User(msg)) => messages.push(msg),
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        messages
+    }
+
+    #[test]
+    fn test_keyed_command_supersedes_running_one() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        // The slow, stale search would otherwise answer last
+        executor.execute(search("h", 100).keyed("search"), tx.clone());
+        executor.execute(search("ho", 100).keyed("search"), tx.clone());
+        executor.execute(search("hoj", 20).keyed("search"), tx);
+
+        let messages = collect_messages(&rx, Duration::from_millis(300));
+        assert_eq!(messages, vec![TestMsg::Text("hoj".to_string())]);
+        assert!(!executor.is_key_running("search"));
+    }
+
+    #[test]
+    fn test_keyed_command_queue_and_drop() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        executor.execute(search("first", 50).keyed("save"), tx.clone());
+        executor.execute(search("second", 10).keyed_with("save", KeyPolicy::Queue), tx.clone());
+        executor.execute(search("ignored", 10).keyed_with("save", KeyPolicy::Drop), tx);
+
+        let messages = collect_messages(&rx, Duration::from_millis(300));
+        assert_eq!(
+            messages,
+            vec![TestMsg::Text("first".to_string()), TestMsg::Text("second".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_keyed_command_rejected_at_the_task_limit_releases_its_key() {
+        let limits = ResourceLimits::default().with_max_tasks(1);
+        let executor = CommandExecutor::<TestMsg>::with_resource_limits(limits).unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        // Hold the only task slot, so the keyed command is rejected
+        executor.execute(search("busy", 100), tx.clone());
+        std::thread::sleep(Duration::from_millis(20));
+        executor.execute(search("rejected", 10).keyed("save"), tx.clone());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!executor.is_key_running("save"));
+
+        let messages = collect_messages(&rx, Duration::from_millis(150));
+        assert_eq!(messages, vec![TestMsg::Text("busy".to_string())]);
+
+        // The key takes new commands again once the slot is free
+        executor.execute(search("first", 20).keyed("save"), tx.clone());
+        executor.execute(search("dropped", 10).keyed_with("save", KeyPolicy::Drop), tx.clone());
+        let messages = collect_messages(&rx, Duration::from_millis(100));
+        assert_eq!(messages, vec![TestMsg::Text("first".to_string())]);
+        drop(tx);
+    }
+
+    #[test]
+    fn test_cancel_keyed_command() {
+        let executor = CommandExecutor::<TestMsg>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(10);
+
+        executor.execute(search("h", 50).keyed("search"), tx.clone());
+        executor.execute(search("ho", 10).keyed_with("search", KeyPolicy::Queue), tx.clone());
+        assert!(executor.is_key_running("search"));
+
+        executor.execute(commands::cancel("search"), tx.clone());
+        assert!(!executor.is_key_running("search"));
+        assert!(collect_messages(&rx, Duration::from_millis(150)).is_empty());
+
+        // Cancelling an unknown key does nothing
+        executor.execute(commands::cancel("search"), tx);
+    }
+}
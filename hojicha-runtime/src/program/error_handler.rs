@@ -3,7 +3,7 @@
 //! This module provides error handling strategies and utilities for
 //! fallible commands and model operations.
 
-use hojicha_core::error::Error;
+use hojicha_core::error::{Error, SharedError};
 use hojicha_core::event::Event;
 use std::sync::mpsc::SyncSender;
 
@@ -29,6 +29,19 @@ impl<M> ErrorHandler<M> for DefaultErrorHandler {
     }
 }
 
+/// Error handler that passes errors to the model's `handle_error`
+///
+/// This is the default for programs built with
+/// [`Program::with_fallible_model`](crate::Program::with_fallible_model).
+/// Without a fallible model the program logs the errors instead.
+pub struct ModelErrorHandler;
+
+impl<M> ErrorHandler<M> for ModelErrorHandler {
+    fn handle_error(&self, error: Error, tx: &SyncSender<Event<M>>) {
+        let _ = tx.send(Event::Error(SharedError::new(error)));
+    }
+}
+
 /// Error handler that converts errors to events
 pub struct EventErrorHandler<M, F>
 where
@@ -83,10 +96,22 @@ impl<M> CompositeErrorHandler<M> {
 
 impl<M> ErrorHandler<M> for CompositeErrorHandler<M> {
     fn handle_error(&self, error: Error, tx: &SyncSender<Event<M>>) {
-        // Since Error doesn't implement Clone, we convert to string and recreate
-        let error_string = error.to_string();
+        // Since Error doesn't implement Clone, we convert to string and recreate,
+        // keeping the label of the command it came from
+        let (label, error_string) = match error {
+            Error::CommandFailed { label, source } => (Some(label), source.to_string()),
+            error => (None, error.to_string()),
+        };
         for handler in &self.handlers {
-            handler.handle_error(Error::Model(error_string.clone()), tx);
+            let error = Error::Model(error_string.clone());
+            let error = match &label {
+                Some(label) => Error::CommandFailed {
+                    label: label.clone(),
+                    source: Box::new(error),
+                },
+                None => error,
+            };
+            handler.handle_error(error, tx);
         }
     }
 }
@@ -146,4 +171,39 @@ mod tests {
         let event = rx.recv().unwrap();
         assert_eq!(event, Event::User(TestMsg::Error("Handled: Model error: Model error: Test error".to_string())));
     }
+
+    #[test]
+    fn test_model_error_handler() {
+        let (tx, rx) = mpsc::sync_channel::<Event<TestMsg>>(10);
+        ModelErrorHandler.handle_error(Error::Model("Test error".to_string()), &tx);
+
+        let Event::Error(error) = rx.recv().unwrap() else {
+            panic!("expected an error event");
+        };
+        assert_eq!(error.take().unwrap().to_string(), "Model error: Test error");
+    }
+
+    #[test]
+    fn test_composite_error_handler_keeps_label() {
+        let (tx, rx) = mpsc::sync_channel(10);
+        let composite = CompositeErrorHandler::new()
+            .add_handler(Box::new(ModelErrorHandler))
+            .add_handler(Box::new(EventErrorHandler::new(|err: Error| {
+                TestMsg::Error(err.command_label().unwrap_or_default().to_string())
+            })));
+
+        let error = Error::CommandFailed {
+            label: "save".to_string(),
+            source: Box::new(Error::Model("disk full".to_string())),
+        };
+        composite.handle_error(error, &tx);
+
+        let Event::Error(error) = rx.recv().unwrap() else {
+            panic!("expected an error event");
+        };
+        let error = error.take().unwrap();
+        assert_eq!(error.command_label(), Some("save"));
+        assert_eq!(error.to_string(), "save failed: Model error: Model error: disk full");
+        assert_eq!(rx.recv().unwrap(), Event::User(TestMsg::Error("save".to_string())));
+    }
 }
\ No newline at end of file
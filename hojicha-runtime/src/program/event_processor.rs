@@ -11,6 +11,13 @@ pub struct EventProcessor;
 impl EventProcessor {
     /// Process a crossterm event into a hojicha event
     pub fn process_crossterm_event(event: CrosstermEvent) -> Option<Event<()>> {
+        Self::convert_event(event)
+    }
+
+    /// Convert a crossterm event into a hojicha event for any message type
+    ///
+    /// Key releases and repeats are dropped.
+    pub fn convert_event<M>(event: CrosstermEvent) -> Option<Event<M>> {
         match event {
             CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => {
                 Some(Event::Key(key.into()))
@@ -42,6 +49,32 @@ impl EventProcessor {
         (width, height)
     }
 
+    /// Fold the resize events waiting after one into the last of them
+    ///
+    /// `next` returns the events that are already waiting. Folding stops at
+    /// the first event that isn't a resize, which is handed back so it isn't
+    /// lost.
+    pub fn coalesce_resizes(
+        initial_width: u16,
+        initial_height: u16,
+        mut next: impl FnMut() -> Option<CrosstermEvent>,
+    ) -> ((u16, u16), Option<CrosstermEvent>) {
+        let mut width = initial_width;
+        let mut height = initial_height;
+
+        while let Some(event) = next() {
+            match event {
+                CrosstermEvent::Resize(w, h) => {
+                    width = w;
+                    height = h;
+                }
+                other => return ((width, height), Some(other)),
+            }
+        }
+
+        ((width, height), None)
+    }
+
     /// Check if an event is a quit event (Ctrl+Q)
     pub fn is_quit_event<M>(event: &Event<M>) -> bool {
         if let Event::Key(KeyEvent {
@@ -94,8 +127,7 @@ impl EventProcessor {
                 }
 
                 // Convert crossterm event to hojicha event
-                Self::process_crossterm_event(ct_event)
-                    .map(|e| unsafe { std::mem::transmute_copy(&e) })
+                Self::convert_event(ct_event)
             }
             Err(mpsc::RecvTimeoutError::Timeout) => {
                 // Check one more time for messages before generating tick
@@ -222,6 +254,27 @@ mod tests {
         assert_eq!(height, 40);
     }
 
+    #[test]
+    fn test_coalesce_resizes_keeps_the_next_event() {
+        let mut waiting = vec![
+            CrosstermEvent::Resize(100, 30),
+            CrosstermEvent::Resize(120, 40),
+            CrosstermEvent::FocusGained,
+            CrosstermEvent::Resize(90, 20),
+        ]
+        .into_iter();
+
+        let (size, next) = EventProcessor::coalesce_resizes(80, 24, || waiting.next());
+        assert_eq!(size, (120, 40));
+        assert_eq!(next, Some(CrosstermEvent::FocusGained));
+        // Events after the one handed back stay where they are
+        assert_eq!(waiting.next(), Some(CrosstermEvent::Resize(90, 20)));
+
+        let (size, next) = EventProcessor::coalesce_resizes(80, 24, || None);
+        assert_eq!(size, (80, 24));
+        assert_eq!(next, None);
+    }
+
     #[test]
     fn test_is_quit_event() {
         let quit_event: Event<()> = Event::Key(KeyEvent {
@@ -55,6 +55,11 @@ impl FpsLimiter {
         }
     }
 
+    /// Get the time since the last frame was rendered
+    pub fn time_since_last_frame(&self) -> Duration {
+        self.last_render.elapsed()
+    }
+
     /// Update the maximum FPS
     pub fn set_max_fps(&mut self, max_fps: u16) {
         self.max_fps = max_fps;
@@ -0,0 +1,394 @@
+//! A cloneable handle for talking to a running program
+//!
+//! [`Program::run`](crate::Program::run) takes the program by value, so
+//! anything that wants to reach it afterwards needs a handle obtained
+//! beforehand with [`Program::handle`](crate::Program::handle). The handle can
+//! be cloned and moved to any thread or task.
+//!
+//! Unlike the `SyncSender` from [`Program::sender`](crate::Program::sender),
+//! [`ProgramHandle::send`] never blocks the thread: while the message channel
+//! is full it waits asynchronously, so it is safe to call from Tokio tasks.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use hojicha_core::{Cmd, Event, Model};
+//! # use hojicha_runtime::Program;
+//! # struct App;
+//! # #[derive(Clone)]
+//! # enum Msg { Line(String) }
+//! # impl Model for App {
+//! #     type Message = Msg;
+//! #     fn update(&mut self, _: Event<Msg>) -> Cmd<Msg> { Cmd::none() }
+//! #     fn view(&self, _: &mut ratatui::Frame, _: ratatui::layout::Rect) {}
+//! # }
+//! # fn main() -> hojicha_core::Result<()> {
+//! let mut program = Program::new(App)?;
+//! let handle = program.handle();
+//!
+//! std::thread::spawn(move || {
+//!     let runtime = tokio::runtime::Runtime::new().unwrap();
+//!     runtime.block_on(async {
+//!         for i in 0.. {
+//!             if handle.send(Msg::Line(format!("line {i}"))).await.is_err() {
+//!                 return; // The program has exited
+//!             }
+//!         }
+//!     });
+//! });
+//!
+//! program.run()
+//! # }
+//! ```
+
+use super::PriorityEventProcessor;
+use crate::metrics::AdvancedEventStats;
+use hojicha_core::core::Message;
+use hojicha_core::event::Event;
+use std::fmt;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Lets senders wait for room in the message channel instead of polling it
+///
+/// Whatever receives from the channel calls [`MessageRoom::drained`]
+/// afterwards, and again once the receiver is gone, which wakes every waiting
+/// sender to try again.
+#[derive(Clone, Default)]
+pub(crate) struct MessageRoom(Arc<Notify>);
+
+impl MessageRoom {
+    /// Wake the senders waiting for room
+    pub(crate) fn drained(&self) {
+        self.0.notify_waiters();
+    }
+
+    /// Send `event`, waiting while the channel is full
+    ///
+    /// Gives the event back as `Full` if the channel is still full at
+    /// `deadline`.
+    pub(crate) async fn send<M>(
+        &self,
+        tx: &mpsc::SyncSender<Event<M>>,
+        event: Event<M>,
+        deadline: Option<Instant>,
+    ) -> Result<(), mpsc::TrySendError<Event<M>>> {
+        let mut event = event;
+        loop {
+            // Register before trying, so a drain in between isn't missed
+            let mut drained = pin!(self.0.notified());
+            drained.as_mut().enable();
+
+            match tx.try_send(event) {
+                Err(mpsc::TrySendError::Full(full)) => event = full,
+                result => return result,
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, drained).await.is_err() {
+                        return Err(mpsc::TrySendError::Full(event));
+                    }
+                }
+                None => drained.await,
+            }
+        }
+    }
+}
+
+/// Why a message couldn't be sent to the program
+///
+/// The message is handed back so it isn't lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendError<M> {
+    /// The message channel is full
+    Full(M),
+    /// The channel stayed full until the timeout passed
+    Timeout(M),
+    /// The program has exited
+    Closed(M),
+}
+
+impl<M> SendError<M> {
+    /// Get back the message that wasn't sent
+    pub fn into_inner(self) -> M {
+        match self {
+            SendError::Full(msg) | SendError::Timeout(msg) | SendError::Closed(msg) => msg,
+        }
+    }
+}
+
+impl<M> fmt::Display for SendError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Full(_) => write!(f, "message channel is full"),
+            SendError::Timeout(_) => write!(f, "timed out waiting for room in the message channel"),
+            SendError::Closed(_) => write!(f, "program has exited"),
+        }
+    }
+}
+
+impl<M: fmt::Debug> std::error::Error for SendError<M> {}
+
+/// A cloneable handle to a program, usable from any thread or task
+///
+/// Get one with [`Program::handle`](crate::Program::handle) before calling
+/// `run`. Messages sent through it arrive in the model's `update` as
+/// `Event::User`.
+pub struct ProgramHandle<M: Message> {
+    tx: mpsc::SyncSender<Event<M>>,
+    room: MessageRoom,
+    processor: PriorityEventProcessor<M>,
+    running: Arc<AtomicBool>,
+    force_quit: Arc<AtomicBool>,
+    quit_requested: Arc<AtomicBool>,
+}
+
+impl<M: Message + Clone> ProgramHandle<M> {
+    pub(crate) fn new(
+        tx: mpsc::SyncSender<Event<M>>,
+        room: MessageRoom,
+        processor: PriorityEventProcessor<M>,
+        running: Arc<AtomicBool>,
+        force_quit: Arc<AtomicBool>,
+        quit_requested: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            tx,
+            room,
+            processor,
+            running,
+            force_quit,
+            quit_requested,
+        }
+    }
+
+    /// Send a message, waiting while the message channel is full
+    ///
+    /// Fails only once the program has exited. Must be awaited inside a Tokio
+    /// runtime.
+    pub async fn send(&self, msg: M) -> Result<(), SendError<M>> {
+        self.room
+            .send(&self.tx, Event::User(msg), None)
+            .await
+            .map_err(|e| into_send_error(e, SendError::Full))
+    }
+
+    /// Send a message if there is room in the message channel right now
+    pub fn try_send(&self, msg: M) -> Result<(), SendError<M>> {
+        self.tx
+            .try_send(Event::User(msg))
+            .map_err(|e| into_send_error(e, SendError::Full))
+    }
+
+    /// Send a message, waiting at most `timeout` for room in the message channel
+    ///
+    /// Must be awaited inside a Tokio runtime.
+    pub async fn send_timeout(&self, msg: M, timeout: Duration) -> Result<(), SendError<M>> {
+        let deadline = Instant::now() + timeout;
+        self.room
+            .send(&self.tx, Event::User(msg), Some(deadline))
+            .await
+            .map_err(|e| into_send_error(e, SendError::Timeout))
+    }
+
+    /// Ask the program to quit
+    ///
+    /// Like `Event::Quit` from anywhere else, the quit has a high priority
+    /// and can overtake user messages that are still queued. This never
+    /// blocks; if the channel is full the program loop picks the quit up on
+    /// its next iteration instead.
+    pub fn quit(&self) {
+        if let Err(mpsc::TrySendError::Full(_)) = self.tx.try_send(Event::Quit) {
+            self.quit_requested.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Stop the program loop at its next iteration, without going through
+    /// the event queue
+    ///
+    /// The terminal is still restored.
+    pub fn kill(&self) {
+        self.force_quit.store(true, Ordering::SeqCst);
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the program loop is running
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst) && !self.force_quit.load(Ordering::SeqCst)
+    }
+
+    /// Get a snapshot of the event processing metrics
+    pub fn metrics(&self) -> AdvancedEventStats {
+        self.processor.advanced_metrics()
+    }
+
+    /// Get the capacity of the program's event queue
+    pub fn queue_capacity(&self) -> usize {
+        self.processor.queue_capacity()
+    }
+}
+
+impl<M: Message> Clone for ProgramHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            room: self.room.clone(),
+            processor: self.processor.clone(),
+            running: Arc::clone(&self.running),
+            force_quit: Arc::clone(&self.force_quit),
+            quit_requested: Arc::clone(&self.quit_requested),
+        }
+    }
+}
+
+impl<M: Message> fmt::Debug for ProgramHandle<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgramHandle")
+            .field("running", &self.running.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+/// Turn a failed send of a user message into a `SendError`, reporting a full
+/// channel as `full`
+fn into_send_error<M>(
+    error: mpsc::TrySendError<Event<M>>,
+    full: fn(M) -> SendError<M>,
+) -> SendError<M> {
+    match error {
+        mpsc::TrySendError::Full(event) => full(into_message(event)),
+        mpsc::TrySendError::Disconnected(event) => SendError::Closed(into_message(event)),
+    }
+}
+
+/// Unwrap the message from an event this handle sent
+fn into_message<M>(event: Event<M>) -> M {
+    match event {
+        Event::User(msg) => msg,
+        _ => unreachable!("ProgramHandle only sends user messages"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::PriorityConfig;
+    use std::thread;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Msg(u32);
+
+    fn handle(capacity: usize) -> (ProgramHandle<Msg>, mpsc::Receiver<Event<Msg>>) {
+        handle_with(capacity, PriorityEventProcessor::new())
+    }
+
+    fn handle_with(
+        capacity: usize,
+        processor: PriorityEventProcessor<Msg>,
+    ) -> (ProgramHandle<Msg>, mpsc::Receiver<Event<Msg>>) {
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let handle = ProgramHandle::new(
+            tx,
+            MessageRoom::default(),
+            processor,
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        );
+        (handle, rx)
+    }
+
+    #[test]
+    fn test_try_send_reports_full_and_closed() {
+        let (handle, rx) = handle(1);
+        assert_eq!(handle.try_send(Msg(1)), Ok(()));
+        assert_eq!(handle.try_send(Msg(2)), Err(SendError::Full(Msg(2))));
+
+        drop(rx);
+        assert_eq!(handle.try_send(Msg(3)), Err(SendError::Closed(Msg(3))));
+    }
+
+    #[tokio::test]
+    async fn test_send_waits_for_room() {
+        let (handle, rx) = handle(1);
+        handle.send(Msg(1)).await.unwrap();
+
+        let room = handle.room.clone();
+        let reader = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            let first = rx.recv().unwrap();
+            room.drained();
+            let second = rx.recv().unwrap();
+            (first, second)
+        });
+        handle.send(Msg(2)).await.unwrap();
+
+        let (first, second) = reader.join().unwrap();
+        assert_eq!(first, Event::User(Msg(1)));
+        assert_eq!(second, Event::User(Msg(2)));
+    }
+
+    #[tokio::test]
+    async fn test_send_wakes_up_when_the_program_exits() {
+        let (handle, rx) = handle(1);
+        handle.send(Msg(1)).await.unwrap();
+
+        let room = handle.room.clone();
+        let closer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            drop(rx);
+            room.drained();
+        });
+
+        let result = handle.send_timeout(Msg(2), Duration::from_secs(5)).await;
+        assert_eq!(result, Err(SendError::Closed(Msg(2))));
+        closer.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout_gives_message_back() {
+        let (handle, _rx) = handle(1);
+        handle.send(Msg(1)).await.unwrap();
+
+        let result = handle.send_timeout(Msg(2), Duration::from_millis(20)).await;
+        assert_eq!(result, Err(SendError::Timeout(Msg(2))));
+        assert_eq!(result.unwrap_err().into_inner(), Msg(2));
+    }
+
+    #[test]
+    fn test_quit_and_kill() {
+        let (handle, rx) = handle(2);
+        handle.quit();
+        assert_eq!(rx.try_recv().unwrap(), Event::Quit);
+        assert!(!handle.quit_requested.load(Ordering::SeqCst));
+
+        // The channel is full, so the loop is asked through the flag
+        handle.try_send(Msg(1)).unwrap();
+        handle.try_send(Msg(2)).unwrap();
+        handle.quit();
+        assert!(handle.quit_requested.load(Ordering::SeqCst));
+
+        assert!(handle.is_running());
+        handle.clone().kill();
+        assert!(!handle.is_running());
+    }
+
+    #[test]
+    fn test_queries_see_the_program_queue() {
+        let processor = PriorityEventProcessor::with_config(PriorityConfig {
+            max_queue_size: 10,
+            ..PriorityConfig::default()
+        });
+        let (handle, _rx) = handle_with(1, processor.clone());
+        assert_eq!(handle.queue_capacity(), 10);
+
+        processor.push(Event::User(Msg(1))).unwrap();
+        processor.push(Event::Tick).unwrap();
+        while processor.pop().is_some() {}
+        assert_eq!(handle.metrics().basic.total_events, 2);
+    }
+}
@@ -3,10 +3,11 @@
 //! This module implements the default event processing with built-in prioritization
 //! to ensure UI responsiveness even under heavy event load.
 
+use super::event_processor::EventProcessor;
 use crate::metrics::{AdvancedEventStats, MetricsCollector, MetricsConfig};
 use crate::priority_queue::{Priority, PriorityEventQueue, ResizeError};
 use crate::queue_scaling::{AutoScaleConfig, QueueAutoScaler, ScalingDecision};
-use crossterm::event::{Event as CrosstermEvent, KeyEventKind};
+use crossterm::event::Event as CrosstermEvent;
 use hojicha_core::core::Message;
 use hojicha_core::event::Event;
 use log::{debug, info, trace, warn};
@@ -76,6 +77,19 @@ pub struct PriorityEventProcessor<M: Message> {
     auto_scaler: Option<Arc<Mutex<QueueAutoScaler>>>,
 }
 
+/// Clones share the queue, statistics and metrics of the original
+impl<M: Message> Clone for PriorityEventProcessor<M> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: Arc::clone(&self.queue),
+            stats: Arc::clone(&self.stats),
+            config: self.config.clone(),
+            metrics: Arc::clone(&self.metrics),
+            auto_scaler: self.auto_scaler.clone(),
+        }
+    }
+}
+
 impl<M: Message> PriorityEventProcessor<M> {
     /// Create a new priority event processor with default configuration
     pub fn new() -> Self {
@@ -229,7 +243,11 @@ impl<M: Message> PriorityEventProcessor<M> {
                 Event::Blur => Some("blur"),
                 Event::Suspend => Some("suspend"),
                 Event::Resume => Some("resume"),
+                Event::Interrupt => Some("interrupt"),
                 Event::ExecProcess => Some("exec"),
+                Event::TerminalControl(_) => Some("terminal"),
+                Event::Error(_) => Some("error"),
+                _ => None,
             };
 
             self.metrics.record_event(priority, elapsed, event_type);
@@ -266,6 +284,22 @@ impl<M: Message> PriorityEventProcessor<M> {
         self.queue.lock().unwrap().len()
     }
 
+    /// Move events from `recv` into the queue while it has room
+    ///
+    /// Stops before taking an event that wouldn't fit, so whatever is left
+    /// waits in its channel instead of being dropped.
+    pub fn fill(&self, mut recv: impl FnMut() -> Option<Event<M>>) {
+        while self.has_room() {
+            let Some(event) = recv() else { break };
+            let _ = self.push(event);
+        }
+    }
+
+    fn has_room(&self) -> bool {
+        let queue = self.queue.lock().unwrap();
+        queue.len() < queue.capacity()
+    }
+
     /// Detect the priority of an event
     fn detect_priority(&self, event: &Event<M>) -> Priority {
         // Use custom mapper if provided
@@ -281,6 +315,11 @@ impl<M: Message> PriorityEventProcessor<M> {
     }
 
     /// Process events from multiple sources with priority handling
+    ///
+    /// For loops that read the terminal on a channel of their own. Bursts of
+    /// resize events are reported as the last one. The program sends terminal
+    /// input on the message channel and uses
+    /// [`process_events_headless`](Self::process_events_headless) instead.
     pub fn process_events(
         &self,
         message_rx: &mpsc::Receiver<Event<M>>,
@@ -317,27 +356,47 @@ impl<M: Message> PriorityEventProcessor<M> {
         }
     }
 
-    /// Process events in headless mode (no terminal events)
-    pub fn process_events_headless(
+    /// Take the next event, waiting up to `timeout` for one to arrive
+    ///
+    /// Everything already queued on the channel is drained into the priority
+    /// queue first, so events that arrive together are delivered by priority.
+    /// When the queue is empty this blocks on the channel and returns as soon
+    /// as an event is sent.
+    ///
+    /// Terminal input and messages share the one channel, so neither waits
+    /// on the other.
+    pub fn next_event(
         &self,
         message_rx: &mpsc::Receiver<Event<M>>,
-        tick_rate: Duration,
-    ) -> Option<Event<M>> {
-        // Drain all available messages into the priority queue
-        while let Ok(msg) = message_rx.try_recv() {
-            let _ = self.push(msg);
-        }
+        timeout: Duration,
+    ) -> Result<Event<M>, mpsc::RecvTimeoutError> {
+        trace!("Processing events, queue size: {}", self.queue_size());
 
-        // Return highest priority event if available
-        if let Some(event) = self.pop() {
-            return Some(event);
+        if let Some(event) = self.next_queued(message_rx) {
+            return Ok(event);
         }
 
-        // Wait for new messages with timeout
-        match message_rx.recv_timeout(tick_rate) {
-            Ok(msg) => Some(msg),
-            Err(mpsc::RecvTimeoutError::Timeout) => Some(Event::Tick),
-            Err(_) => None,
+        // No events available, wait for new ones
+        message_rx.recv_timeout(timeout)
+    }
+
+    /// Process events from the message channel alone
+    ///
+    /// Returns `Event::Tick` once `tick_rate` passes with nothing to do, and
+    /// `None` when every sender has been dropped. See
+    /// [`next_event`](Self::next_event) for how events are picked.
+    pub fn process_events_headless(
+        &self,
+        message_rx: &mpsc::Receiver<Event<M>>,
+        tick_rate: Duration,
+    ) -> Option<Event<M>> {
+        match self.next_event(message_rx, tick_rate) {
+            Ok(event) => Some(event),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                trace!("Generating tick event");
+                Some(Event::Tick)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => None,
         }
     }
 
@@ -356,11 +415,8 @@ impl<M: Message> PriorityEventProcessor<M> {
 
         // Drain all available terminal events
         while let Ok(ct_event) = crossterm_rx.try_recv() {
-            if let Some(event) = Self::convert_crossterm_event(ct_event) {
-                // Unsafe transmute to add the message type
-                // This is safe because Event<()> and Event<M> have the same layout
-                let typed_event = unsafe { std::mem::transmute_copy(&event) };
-                if self.push(typed_event).is_err() {
+            if let Some(event) = EventProcessor::convert_event(ct_event) {
+                if self.push(event).is_err() {
                     break; // Queue is full
                 }
             }
@@ -376,50 +432,30 @@ impl<M: Message> PriorityEventProcessor<M> {
         match ct_event {
             CrosstermEvent::Resize(width, height) => {
                 // Coalesce multiple resize events
-                let (final_width, final_height) =
-                    Self::coalesce_resize_events(width, height, crossterm_rx);
-                Some(Event::Resize {
-                    width: final_width,
-                    height: final_height,
-                })
-            }
-            _ => Self::convert_crossterm_event(ct_event)
-                .map(|e| unsafe { std::mem::transmute_copy(&e) }),
-        }
-    }
-
-    /// Convert a crossterm event to a hojicha event
-    fn convert_crossterm_event(event: CrosstermEvent) -> Option<Event<()>> {
-        match event {
-            CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => {
-                Some(Event::Key(key.into()))
+                let ((width, height), next) =
+                    EventProcessor::coalesce_resizes(width, height, || crossterm_rx.try_recv().ok());
+                debug!("Coalesced resize events to {}x{}", width, height);
+                if let Some(event) = next.and_then(EventProcessor::convert_event) {
+                    let _ = self.push(event);
+                }
+                Some(Event::Resize { width, height })
             }
-            CrosstermEvent::Mouse(mouse) => Some(Event::Mouse(mouse.into())),
-            CrosstermEvent::Resize(width, height) => Some(Event::Resize { width, height }),
-            CrosstermEvent::Paste(data) => Some(Event::Paste(data)),
-            CrosstermEvent::FocusGained => Some(Event::Focus),
-            CrosstermEvent::FocusLost => Some(Event::Blur),
-            _ => None,
+            _ => EventProcessor::convert_event(ct_event),
         }
     }
 
-    /// Coalesce multiple resize events into one
-    fn coalesce_resize_events(
-        initial_width: u16,
-        initial_height: u16,
-        rx: &mpsc::Receiver<CrosstermEvent>,
-    ) -> (u16, u16) {
-        let mut width = initial_width;
-        let mut height = initial_height;
+    /// Drain the channel into the priority queue and pop the most urgent event
+    fn next_queued(&self, message_rx: &mpsc::Receiver<Event<M>>) -> Option<Event<M>> {
+        self.fill(|| message_rx.try_recv().ok());
 
-        // Drain any additional resize events
-        while let Ok(CrosstermEvent::Resize(w, h)) = rx.try_recv() {
-            width = w;
-            height = h;
+        let event = self.pop();
+        if let Some(ref event) = event {
+            debug!(
+                "Returning event from queue: {:?}",
+                std::mem::discriminant(event)
+            );
         }
-
-        debug!("Coalesced resize events to {}x{}", width, height);
-        (width, height)
+        event
     }
 }
 
@@ -513,4 +549,30 @@ mod tests {
         assert_eq!(stats.high_priority_events, 1);
         assert_eq!(stats.low_priority_events, 0);
     }
+
+    #[test]
+    fn test_process_events_coalesces_resizes() {
+        let processor: PriorityEventProcessor<TestMsg> = PriorityEventProcessor::new();
+        let (_message_tx, message_rx) = mpsc::channel();
+        let (crossterm_tx, crossterm_rx) = mpsc::channel();
+        let timeout = Duration::from_millis(10);
+
+        crossterm_tx.send(CrosstermEvent::Resize(100, 30)).unwrap();
+        let first = processor.process_events(&message_rx, &crossterm_rx, timeout);
+        assert_eq!(first, Some(Event::Resize { width: 100, height: 30 }));
+
+        // Resizes waiting behind the one the loop woke for are folded into it
+        for (width, height) in [(110, 35), (120, 40)] {
+            crossterm_tx.send(CrosstermEvent::Resize(width, height)).unwrap();
+        }
+        crossterm_tx.send(CrosstermEvent::FocusLost).unwrap();
+        let size = processor.handle_crossterm_event(CrosstermEvent::Resize(90, 20), &crossterm_rx);
+        assert_eq!(size, Some(Event::Resize { width: 120, height: 40 }));
+        // The event that ended the burst isn't lost
+        let blur = processor.process_events(&message_rx, &crossterm_rx, timeout);
+        assert_eq!(blur, Some(Event::Blur));
+
+        let tick = processor.process_events(&message_rx, &crossterm_rx, timeout);
+        assert_eq!(tick, Some(Event::Tick));
+    }
 }
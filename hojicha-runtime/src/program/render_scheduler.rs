@@ -0,0 +1,226 @@
+//! Render scheduling: redraw only when something changed
+
+use super::fps_limiter::FpsLimiter;
+use crate::metrics::MetricsCollector;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Frame interval for animations when the FPS isn't capped
+const UNCAPPED_ANIMATION_FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Decides when the program loop redraws
+///
+/// The view is redrawn after the frame is invalidated, i.e. after an update,
+/// a resize or an explicit `commands::invalidate()`, and on every frame while
+/// an animation started by `commands::animate()` is running. The FPS limiter
+/// caps how often that happens, so the frame rate follows how busy the model
+/// is: zero while idle, up to the cap while animating.
+pub struct RenderScheduler {
+    limiter: FpsLimiter,
+    dirty: bool,
+    animating_until: Option<Instant>,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl RenderScheduler {
+    /// Create a scheduler capped at `max_fps` (0 for no cap)
+    ///
+    /// The first frame is always drawn.
+    pub fn new(max_fps: u16) -> Self {
+        Self {
+            limiter: FpsLimiter::new(max_fps),
+            dirty: true,
+            animating_until: None,
+            metrics: None,
+        }
+    }
+
+    /// Report rendered and skipped frames to a metrics collector
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Redraw on the next frame
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Redraw on every frame until `duration` has passed
+    ///
+    /// An animation that is already running is extended, never shortened.
+    pub fn animate(&mut self, duration: Duration) {
+        let until = Instant::now() + duration;
+        self.animating_until = self.animating_until.max(Some(until));
+    }
+
+    /// Whether the frame needs to be redrawn
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Whether an animation is running
+    pub fn is_animating(&self) -> bool {
+        self.animating_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Whether a frame should be drawn now
+    pub fn should_render(&self) -> bool {
+        (self.dirty || self.is_animating()) && self.frame_due()
+    }
+
+    /// How long until the next frame is due, or `None` when nothing needs drawing
+    ///
+    /// The program loop sleeps at most this long so a frame held back by the
+    /// FPS cap is still drawn on time.
+    pub fn time_until_next_frame(&self) -> Option<Duration> {
+        if self.dirty {
+            Some(self.limiter.time_until_next_frame())
+        } else if self.is_animating() {
+            let elapsed = self.limiter.time_since_last_frame();
+            Some(self.animation_frame().saturating_sub(elapsed))
+        } else {
+            None
+        }
+    }
+
+    /// Mark that a frame has been drawn
+    pub fn rendered(&mut self) {
+        self.dirty = false;
+        self.limiter.mark_rendered();
+        if let Some(metrics) = &self.metrics {
+            metrics.record_frame();
+        }
+    }
+
+    /// Note that the loop woke up without anything to draw
+    pub fn skipped(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_skipped_frame();
+        }
+    }
+
+    /// Change the frame rate cap
+    pub fn set_max_fps(&mut self, max_fps: u16) {
+        self.limiter.set_max_fps(max_fps);
+    }
+
+    /// Get the frame rate cap
+    pub fn max_fps(&self) -> u16 {
+        self.limiter.max_fps()
+    }
+
+    fn frame_due(&self) -> bool {
+        if self.dirty {
+            self.limiter.should_render()
+        } else {
+            self.limiter.time_since_last_frame() >= self.animation_frame()
+        }
+    }
+
+    fn animation_frame(&self) -> Duration {
+        if self.limiter.max_fps() == 0 {
+            UNCAPPED_ANIMATION_FRAME
+        } else {
+            self.limiter.frame_duration()
+        }
+    }
+}
+
+impl std::fmt::Debug for RenderScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderScheduler")
+            .field("limiter", &self.limiter)
+            .field("dirty", &self.dirty)
+            .field("animating_until", &self.animating_until)
+            .finish()
+    }
+}
+
+impl Default for RenderScheduler {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricsConfig;
+    use std::thread;
+
+    #[test]
+    fn test_first_frame_is_scheduled() {
+        let scheduler = RenderScheduler::new(0);
+        assert!(scheduler.is_dirty());
+        assert!(scheduler.should_render());
+        assert_eq!(scheduler.time_until_next_frame(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_idle_after_render() {
+        let mut scheduler = RenderScheduler::new(0);
+        scheduler.rendered();
+        assert!(!scheduler.should_render());
+        assert_eq!(scheduler.time_until_next_frame(), None);
+
+        scheduler.invalidate();
+        assert!(scheduler.should_render());
+    }
+
+    #[test]
+    fn test_invalidated_frame_waits_for_fps_cap() {
+        let mut scheduler = RenderScheduler::new(20);
+        scheduler.rendered();
+        scheduler.invalidate();
+
+        assert!(!scheduler.should_render());
+        let wait = scheduler.time_until_next_frame().unwrap();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(50));
+
+        thread::sleep(Duration::from_millis(60));
+        assert!(scheduler.should_render());
+    }
+
+    #[test]
+    fn test_animation_keeps_frames_coming() {
+        let mut scheduler = RenderScheduler::new(100);
+        scheduler.rendered();
+        scheduler.animate(Duration::from_millis(100));
+        assert!(scheduler.is_animating());
+
+        thread::sleep(Duration::from_millis(15));
+        assert!(scheduler.should_render());
+        scheduler.rendered();
+        assert!(scheduler.time_until_next_frame().is_some());
+
+        thread::sleep(Duration::from_millis(100));
+        assert!(!scheduler.is_animating());
+        assert!(!scheduler.should_render());
+        assert_eq!(scheduler.time_until_next_frame(), None);
+    }
+
+    #[test]
+    fn test_animation_is_only_extended() {
+        let mut scheduler = RenderScheduler::new(60);
+        scheduler.animate(Duration::from_secs(10));
+        scheduler.animate(Duration::ZERO);
+        assert!(scheduler.is_animating());
+    }
+
+    #[test]
+    fn test_frames_are_reported_to_metrics() {
+        let metrics = Arc::new(MetricsCollector::new(MetricsConfig::default()));
+        let mut scheduler = RenderScheduler::new(0).with_metrics(Arc::clone(&metrics));
+
+        scheduler.rendered();
+        scheduler.skipped();
+        scheduler.skipped();
+
+        let stats = metrics.snapshot().render;
+        assert_eq!(stats.frames_rendered, 1);
+        assert_eq!(stats.frames_skipped, 2);
+        assert_eq!(stats.current_fps, 1.0);
+    }
+}
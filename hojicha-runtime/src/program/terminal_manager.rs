@@ -3,10 +3,20 @@
 use crate::program::MouseMode;
 use crossterm::{
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen, SetTitle,
+    },
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io::{self, Stdout};
+use hojicha_core::commands::TerminalControlMsg;
+use ratatui::backend::{Backend, ClearType as BackendClearType, CrosstermBackend, WindowSize};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::{Position, Rect, Size};
+use ratatui::style::Style;
+use ratatui::text::Span;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+use std::any::Any;
+use std::io::{self, Stdout, Write};
 
 /// Configuration for terminal setup
 #[derive(Debug, Clone)]
@@ -19,6 +29,11 @@ pub struct TerminalConfig {
     pub bracketed_paste: bool,
     /// Whether to enable terminal focus reporting
     pub focus_reporting: bool,
+    /// Render in an inline viewport of at most this many lines below the
+    /// cursor instead of taking over the screen
+    pub inline_height: Option<u16>,
+    /// Whether to put the controlling terminal into raw mode
+    pub raw_mode: bool,
     /// Whether to run in headless mode (no terminal setup)
     pub headless: bool,
 }
@@ -30,24 +45,337 @@ impl Default for TerminalConfig {
             mouse_mode: MouseMode::None,
             bracketed_paste: false,
             focus_reporting: false,
+            inline_height: None,
+            raw_mode: true,
             headless: false,
         }
     }
 }
 
+impl TerminalConfig {
+    /// Set whether to put the controlling terminal into raw mode (the default)
+    ///
+    /// Turn this off when input doesn't come from the terminal.
+    pub fn with_raw_mode(mut self, raw_mode: bool) -> Self {
+        self.raw_mode = raw_mode;
+        self
+    }
+}
+
+/// Object-safe subset of ratatui's `Backend`
+trait ErasedBackend: Send {
+    fn draw(&mut self, content: &mut dyn Iterator<Item = (u16, u16, &Cell)>) -> io::Result<()>;
+    fn append_lines(&mut self, n: u16) -> io::Result<()>;
+    fn hide_cursor(&mut self) -> io::Result<()>;
+    fn show_cursor(&mut self) -> io::Result<()>;
+    fn get_cursor_position(&mut self) -> io::Result<Position>;
+    fn set_cursor_position(&mut self, position: Position) -> io::Result<()>;
+    fn clear(&mut self) -> io::Result<()>;
+    fn clear_region(&mut self, clear_type: BackendClearType) -> io::Result<()>;
+    fn size(&self) -> io::Result<Size>;
+    fn window_size(&mut self) -> io::Result<WindowSize>;
+    fn flush(&mut self) -> io::Result<()>;
+    fn writer(&mut self) -> Option<&mut dyn Write>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// A backend together with a way to reach its escape sequence output
+struct Erased<B> {
+    backend: B,
+    writer: fn(&mut B) -> Option<&mut dyn Write>,
+}
+
+impl<B: Backend + Send + 'static> ErasedBackend for Erased<B> {
+    fn draw(&mut self, content: &mut dyn Iterator<Item = (u16, u16, &Cell)>) -> io::Result<()> {
+        self.backend.draw(content)
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.backend.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.backend.get_cursor_position()
+    }
+
+    fn set_cursor_position(&mut self, position: Position) -> io::Result<()> {
+        self.backend.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.backend.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: BackendClearType) -> io::Result<()> {
+        self.backend.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.backend.size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.backend.window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut self.backend)
+    }
+
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        (self.writer)(&mut self.backend)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        &self.backend
+    }
+}
+
+fn no_writer<B>(_backend: &mut B) -> Option<&mut dyn Write> {
+    None
+}
+
+fn crossterm_writer<W: Write>(backend: &mut CrosstermBackend<W>) -> Option<&mut dyn Write> {
+    Some(backend)
+}
+
+/// A backend of a fixed size that draws nothing
+///
+/// Views render into its terminal's buffer, and it stands in for a real
+/// backend while that one is being moved.
+struct Scratch(Size);
+
+impl Backend for Scratch {
+    fn draw<'a, I>(&mut self, _content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        Ok(Position::ORIGIN)
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, _position: P) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        Ok(self.0)
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        Ok(WindowSize {
+            columns_rows: self.0,
+            pixels: Size::default(),
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A type-erased ratatui backend
+///
+/// This lets the program drive any backend without being generic over it.
+pub struct DynBackend(Box<dyn ErasedBackend>);
+
+impl DynBackend {
+    fn new<B: Backend + Send + 'static>(backend: B) -> Self {
+        Self(Box::new(Erased {
+            backend,
+            writer: no_writer,
+        }))
+    }
+
+    fn crossterm<W: Write + Send + 'static>(writer: W) -> Self {
+        Self(Box::new(Erased {
+            backend: CrosstermBackend::new(writer),
+            writer: crossterm_writer,
+        }))
+    }
+
+    /// Get the wrapped backend if it is a `B`
+    pub fn downcast_ref<B: 'static>(&self) -> Option<&B> {
+        self.0.as_any().downcast_ref()
+    }
+
+    /// The stream that terminal escape sequences are written to
+    ///
+    /// Only crossterm backends created from a writer have one.
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        self.0.writer()
+    }
+}
+
+impl Backend for DynBackend {
+    fn draw<'a, I>(&mut self, mut content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.0.draw(&mut content)
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.0.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.0.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.0.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        // Outputs that aren't the controlling terminal can't be queried.
+        // Treat them as starting at the top so inline viewports still work.
+        match self.0.get_cursor_position() {
+            Ok(position) => Ok(position),
+            Err(e) => {
+                log::debug!("Can't read cursor position ({}), assuming top left", e);
+                Ok(Position::ORIGIN)
+            }
+        }
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.0.set_cursor_position(position.into())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.0.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: BackendClearType) -> io::Result<()> {
+        self.0.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.0.size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.0.window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A backend a [`TerminalManager`] can drive
+///
+/// Implemented for crossterm on stdout, and for [`DynBackend`], which wraps
+/// any other backend or writer.
+pub trait ManagedBackend: Backend + Send + sealed::Sealed {
+    /// The stream that terminal escape sequences are written to, if any
+    #[doc(hidden)]
+    fn escape_writer(&mut self) -> Option<&mut dyn Write>;
+
+    /// A stand-in for the backend while it moves to a resized terminal
+    #[doc(hidden)]
+    fn placeholder() -> Self;
+}
+
+impl sealed::Sealed for CrosstermBackend<Stdout> {}
+
+impl ManagedBackend for CrosstermBackend<Stdout> {
+    fn escape_writer(&mut self) -> Option<&mut dyn Write> {
+        Some(self)
+    }
+
+    fn placeholder() -> Self {
+        CrosstermBackend::new(io::stdout())
+    }
+}
+
+impl sealed::Sealed for DynBackend {}
+
+impl ManagedBackend for DynBackend {
+    fn escape_writer(&mut self) -> Option<&mut dyn Write> {
+        self.writer()
+    }
+
+    fn placeholder() -> Self {
+        DynBackend::new(Scratch(Size::default()))
+    }
+}
+
 /// Manages terminal setup, teardown, and state
-pub struct TerminalManager {
-    terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
+///
+/// Draws to stdout through crossterm by default. [`TerminalManager::with_writer`]
+/// and [`TerminalManager::with_backend`] draw elsewhere through a [`DynBackend`].
+pub struct TerminalManager<B: ManagedBackend = CrosstermBackend<Stdout>> {
+    terminal: Option<Terminal<B>>,
     config: TerminalConfig,
     alt_screen_was_active: bool,
     is_released: bool,
 }
 
 impl TerminalManager {
-    /// Create a new terminal manager
+    /// Create a new terminal manager that draws to stdout
     pub fn new(config: TerminalConfig) -> io::Result<Self> {
+        Self::from_backend(CrosstermBackend::new(io::stdout()), config)
+    }
+}
+
+impl TerminalManager<DynBackend> {
+    /// Create a terminal manager that draws to any writer
+    ///
+    /// Escape sequences for the alternate screen, mouse, paste and focus modes
+    /// go to the same writer.
+    pub fn with_writer<W: Write + Send + 'static>(
+        writer: W,
+        config: TerminalConfig,
+    ) -> io::Result<Self> {
+        Self::from_backend(DynBackend::crossterm(writer), config)
+    }
+
+    /// Create a terminal manager around any ratatui backend
+    ///
+    /// Only drawing goes through the backend. It has no escape sequence
+    /// output, so the alternate screen, mouse, paste and focus settings are
+    /// tracked but have no effect.
+    pub fn with_backend<B: Backend + Send + 'static>(
+        backend: B,
+        config: TerminalConfig,
+    ) -> io::Result<Self> {
+        Self::from_backend(DynBackend::new(backend), config)
+    }
+}
+
+impl<B: ManagedBackend> TerminalManager<B> {
+    fn from_backend(backend: B, config: TerminalConfig) -> io::Result<Self> {
         let terminal = if !config.headless {
-            Some(Self::setup_terminal(&config)?)
+            Some(Self::setup_terminal(backend, &config)?)
         } else {
             None
         };
@@ -61,60 +389,96 @@ impl TerminalManager {
     }
 
     /// Set up the terminal with the given configuration
-    fn setup_terminal(config: &TerminalConfig) -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
-        let mut stdout = io::stdout();
-
+    fn setup_terminal(mut backend: B, config: &TerminalConfig) -> io::Result<Terminal<B>> {
         // Enable raw mode
-        enable_raw_mode()?;
-
-        // Enter alternate screen if requested
-        if config.alt_screen {
-            execute!(stdout, EnterAlternateScreen)?;
+        if config.raw_mode {
+            enable_raw_mode()?;
         }
 
-        // Set up mouse mode
-        match config.mouse_mode {
-            MouseMode::CellMotion => {
-                execute!(stdout, crossterm::event::EnableMouseCapture)?;
+        if let Some(mut out) = backend.escape_writer() {
+            let out = &mut out;
+            // Enter alternate screen if requested
+            if config.alt_screen {
+                execute!(out, EnterAlternateScreen)?;
             }
-            MouseMode::AllMotion => {
-                execute!(
-                    stdout,
-                    crossterm::event::EnableMouseCapture,
-                    crossterm::cursor::Show,
-                    crossterm::cursor::Hide,
-                )?;
+
+            // Set up mouse mode
+            if config.mouse_mode != MouseMode::None {
+                enable_mouse(out, config.mouse_mode)?;
             }
-            MouseMode::None => {}
-        }
 
-        // Enable bracketed paste if requested
-        if config.bracketed_paste {
-            execute!(stdout, crossterm::event::EnableBracketedPaste)?;
-        }
+            // Enable bracketed paste if requested
+            if config.bracketed_paste {
+                execute!(out, crossterm::event::EnableBracketedPaste)?;
+            }
 
-        // Enable focus reporting if requested
-        if config.focus_reporting {
-            execute!(stdout, crossterm::event::EnableFocusChange)?;
+            // Enable focus reporting if requested
+            if config.focus_reporting {
+                execute!(out, crossterm::event::EnableFocusChange)?;
+            }
         }
 
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+        // Outputs without a size (e.g. a file) get a fixed viewport
+        let mut terminal = match backend.size() {
+            Ok(_) => match config.inline_height {
+                // Start small, draw() sizes the viewport to the content
+                Some(_) => Self::inline_terminal(backend, 1)?,
+                None => Terminal::new(backend)?,
+            },
+            Err(e) => {
+                log::debug!("Backend has no size ({}), using a fixed 80x24 viewport", e);
+                Terminal::with_options(
+                    backend,
+                    TerminalOptions {
+                        viewport: Viewport::Fixed(Rect::new(0, 0, 80, 24)),
+                    },
+                )?
+            }
+        };
         terminal.hide_cursor()?;
 
         Ok(terminal)
     }
 
+    fn inline_terminal(backend: B, height: u16) -> io::Result<Terminal<B>> {
+        Terminal::with_options(
+            backend,
+            TerminalOptions {
+                viewport: Viewport::Inline(height),
+            },
+        )
+    }
+
+    /// Check if the program renders in an inline viewport
+    pub fn is_inline(&self) -> bool {
+        self.config.inline_height.is_some()
+    }
+
     /// Get a reference to the terminal
-    pub fn terminal(&self) -> Option<&Terminal<CrosstermBackend<Stdout>>> {
+    pub fn terminal(&self) -> Option<&Terminal<B>> {
         self.terminal.as_ref()
     }
 
     /// Get a mutable reference to the terminal
-    pub fn terminal_mut(&mut self) -> Option<&mut Terminal<CrosstermBackend<Stdout>>> {
+    pub fn terminal_mut(&mut self) -> Option<&mut Terminal<B>> {
         self.terminal.as_mut()
     }
 
+    /// Write escape sequences to the backend's output, if it has one
+    fn write_escapes<F>(&mut self, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut &mut dyn Write) -> io::Result<()>,
+    {
+        match self
+            .terminal
+            .as_mut()
+            .and_then(|t| t.backend_mut().escape_writer())
+        {
+            Some(mut out) => f(&mut out),
+            None => Ok(()),
+        }
+    }
+
     /// Release the terminal (for running external commands)
     pub fn release(&mut self) -> io::Result<()> {
         if self.is_released || self.config.headless {
@@ -129,13 +493,30 @@ impl TerminalManager {
             terminal.show_cursor()?;
         }
 
-        // Exit alt screen if active
-        if self.config.alt_screen {
-            execute!(io::stdout(), LeaveAlternateScreen)?;
-        }
+        // Turn off input reporting so the child doesn't receive our escape sequences
+        let config = self.config.clone();
+        self.write_escapes(|out| {
+            if config.focus_reporting {
+                execute!(out, crossterm::event::DisableFocusChange)?;
+            }
+            if config.bracketed_paste {
+                execute!(out, crossterm::event::DisableBracketedPaste)?;
+            }
+            if config.mouse_mode != MouseMode::None {
+                execute!(out, crossterm::event::DisableMouseCapture)?;
+            }
+
+            // Exit alt screen if active
+            if config.alt_screen {
+                execute!(out, LeaveAlternateScreen)?;
+            }
+            Ok(())
+        })?;
 
         // Disable raw mode
-        disable_raw_mode()?;
+        if config.raw_mode {
+            disable_raw_mode()?;
+        }
 
         self.is_released = true;
         Ok(())
@@ -148,13 +529,31 @@ impl TerminalManager {
         }
 
         // Re-enable raw mode
-        enable_raw_mode()?;
-
-        // Restore alt screen if it was active
-        if self.alt_screen_was_active {
-            execute!(io::stdout(), EnterAlternateScreen)?;
+        let config = self.config.clone();
+        if config.raw_mode {
+            enable_raw_mode()?;
         }
 
+        let alt_screen = self.alt_screen_was_active;
+        self.write_escapes(|out| {
+            // Restore alt screen if it was active
+            if alt_screen {
+                execute!(out, EnterAlternateScreen)?;
+            }
+
+            // Re-enable the input reporting turned off by release()
+            if config.mouse_mode != MouseMode::None {
+                enable_mouse(out, config.mouse_mode)?;
+            }
+            if config.bracketed_paste {
+                execute!(out, crossterm::event::EnableBracketedPaste)?;
+            }
+            if config.focus_reporting {
+                execute!(out, crossterm::event::EnableFocusChange)?;
+            }
+            Ok(())
+        })?;
+
         // Hide cursor again
         if let Some(ref mut terminal) = self.terminal {
             terminal.hide_cursor()?;
@@ -166,6 +565,219 @@ impl TerminalManager {
         Ok(())
     }
 
+    /// Get the current terminal configuration
+    ///
+    /// This reflects any changes made by [`TerminalManager::apply`].
+    pub fn config(&self) -> &TerminalConfig {
+        &self.config
+    }
+
+    /// Apply a terminal control command
+    ///
+    /// The configuration is updated too, so releasing, restoring and cleaning
+    /// up the terminal keep the new state. Nothing is written in headless mode
+    /// or while the terminal is released.
+    pub fn apply(&mut self, msg: &TerminalControlMsg) -> io::Result<()> {
+        let live = !self.config.headless && !self.is_released;
+
+        match msg {
+            TerminalControlMsg::HideCursor => {
+                if let (true, Some(terminal)) = (live, self.terminal.as_mut()) {
+                    terminal.hide_cursor()?;
+                }
+            }
+            TerminalControlMsg::ShowCursor => {
+                if let (true, Some(terminal)) = (live, self.terminal.as_mut()) {
+                    terminal.show_cursor()?;
+                }
+            }
+            TerminalControlMsg::EnterAltScreen | TerminalControlMsg::ExitAltScreen => {
+                let enter = *msg == TerminalControlMsg::EnterAltScreen;
+                if self.config.alt_screen != enter {
+                    self.config.alt_screen = enter;
+                    if self.is_released {
+                        self.alt_screen_was_active = enter;
+                    }
+                    if live {
+                        self.write_escapes(|out| {
+                            if enter {
+                                execute!(out, EnterAlternateScreen)
+                            } else {
+                                execute!(out, LeaveAlternateScreen)
+                            }
+                        })?;
+                        // The new screen needs a full redraw
+                        self.clear()?;
+                    }
+                }
+            }
+            TerminalControlMsg::SetWindowTitle(title) => {
+                if live {
+                    self.write_escapes(|out| execute!(out, SetTitle(title)))?;
+                }
+            }
+            TerminalControlMsg::EnableMouseCellMotion => {
+                self.set_mouse_mode(MouseMode::CellMotion, live)?
+            }
+            TerminalControlMsg::EnableMouseAllMotion => {
+                self.set_mouse_mode(MouseMode::AllMotion, live)?
+            }
+            TerminalControlMsg::DisableMouse => self.set_mouse_mode(MouseMode::None, live)?,
+            TerminalControlMsg::ClearScreen => {
+                if live {
+                    self.clear()?;
+                }
+            }
+            TerminalControlMsg::ClearLine => {
+                if live {
+                    self.write_escapes(|out| execute!(out, Clear(ClearType::CurrentLine)))?;
+                }
+            }
+            TerminalControlMsg::EnableBracketedPaste
+            | TerminalControlMsg::DisableBracketedPaste => {
+                let enable = *msg == TerminalControlMsg::EnableBracketedPaste;
+                if self.config.bracketed_paste != enable {
+                    self.config.bracketed_paste = enable;
+                    if live {
+                        self.write_escapes(|out| {
+                            if enable {
+                                execute!(out, crossterm::event::EnableBracketedPaste)
+                            } else {
+                                execute!(out, crossterm::event::DisableBracketedPaste)
+                            }
+                        })?;
+                    }
+                }
+            }
+            TerminalControlMsg::PrintLine(text) => {
+                if live {
+                    self.print_line(text)?;
+                }
+            }
+            // Scheduling frames is up to the program loop
+            TerminalControlMsg::Invalidate | TerminalControlMsg::Animate(_) => {}
+            TerminalControlMsg::EnableFocusChange | TerminalControlMsg::DisableFocusChange => {
+                let enable = *msg == TerminalControlMsg::EnableFocusChange;
+                if self.config.focus_reporting != enable {
+                    self.config.focus_reporting = enable;
+                    if live {
+                        self.write_escapes(|out| {
+                            if enable {
+                                execute!(out, crossterm::event::EnableFocusChange)
+                            } else {
+                                execute!(out, crossterm::event::DisableFocusChange)
+                            }
+                        })?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Print lines above an inline viewport
+    ///
+    /// The lines scroll up into the terminal's scrollback as more are printed.
+    /// Other viewports have nowhere to keep them, so they are dropped; the
+    /// program and [`Program::println`](crate::program::Program::println)
+    /// write them to stderr instead.
+    pub fn print_line(&mut self, text: &str) -> io::Result<()> {
+        let terminal = match self.terminal.as_mut() {
+            Some(terminal) if self.config.inline_height.is_some() => terminal,
+            _ => {
+                log::debug!("Not printing line outside of an inline viewport: {}", text);
+                return Ok(());
+            }
+        };
+
+        let rows = wrap_rows(text, terminal.get_frame().area().width);
+        for chunk in rows.chunks(usize::from(u16::MAX)) {
+            terminal.insert_before(chunk.len() as u16, |buf| {
+                for (y, row) in (0..).zip(chunk) {
+                    buf.set_string(0, y, row, Style::default());
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Draw into an inline viewport sized to fit the content
+    ///
+    /// The view renders into a scratch buffer as tall as the maximum height,
+    /// without drawing anything. The viewport then grows or shrinks to the
+    /// last non-empty row and the rows are copied over.
+    fn draw_inline<F>(&mut self, max_height: u16, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut ratatui::Frame),
+    {
+        let Some(terminal) = self.terminal.as_mut() else {
+            return Ok(());
+        };
+        let screen = terminal.size()?;
+        let width = screen.width;
+        let max_height = max_height.min(screen.height).max(1);
+
+        let mut scratch = Terminal::new(Scratch(Size::new(width, max_height)))?;
+        f(&mut scratch.get_frame());
+        let content = scratch.current_buffer_mut();
+        let height = content_height(content).max(1);
+
+        if height != terminal.get_frame().area().height {
+            // Clear the old region and reserve a new one from the same top row
+            let top = terminal.get_frame().area().as_position();
+            terminal.set_cursor_position(top)?;
+            terminal
+                .backend_mut()
+                .clear_region(BackendClearType::AfterCursor)?;
+            let backend = std::mem::replace(terminal.backend_mut(), B::placeholder());
+            let mut resized = Self::inline_terminal(backend, height)?;
+            resized.hide_cursor()?;
+            *terminal = resized;
+        }
+
+        terminal.draw(|frame| {
+            let area = frame.area();
+            let buf = frame.buffer_mut();
+            for y in 0..area.height.min(height) {
+                for x in 0..area.width.min(width) {
+                    buf[(area.x + x, area.y + y)] = content[(x, y)].clone();
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Leave the last inline frame in the scrollback
+    ///
+    /// Moves the cursor to a fresh line below the viewport and lets go of the
+    /// terminal, so nothing more is drawn over it.
+    fn finish_inline(&mut self) -> io::Result<()> {
+        if let Some(mut terminal) = self.terminal.take() {
+            let area = terminal.get_frame().area();
+            terminal.set_cursor_position((0, area.bottom().saturating_sub(1)))?;
+            terminal.backend_mut().append_lines(1)?;
+            terminal.show_cursor()?;
+            Backend::flush(terminal.backend_mut())?;
+        }
+        Ok(())
+    }
+
+    /// Switch mouse tracking to a new mode
+    fn set_mouse_mode(&mut self, mode: MouseMode, live: bool) -> io::Result<()> {
+        if self.config.mouse_mode == mode {
+            return Ok(());
+        }
+        self.config.mouse_mode = mode;
+
+        if live {
+            self.write_escapes(|out| match mode {
+                MouseMode::None => execute!(out, crossterm::event::DisableMouseCapture),
+                _ => enable_mouse(out, mode),
+            })?;
+        }
+        Ok(())
+    }
+
     /// Check if the terminal is currently released
     pub fn is_released(&self) -> bool {
         self.is_released
@@ -177,33 +789,40 @@ impl TerminalManager {
             return Ok(());
         }
 
-        // Show cursor
-        if let Some(ref mut terminal) = self.terminal {
-            let _ = terminal.show_cursor();
-        }
+        // Disable various terminal features while the terminal is still
+        // ours; finishing an inline viewport lets go of it
+        let config = self.config.clone();
+        let _ = self.write_escapes(|out| {
+            if config.focus_reporting {
+                let _ = execute!(out, crossterm::event::DisableFocusChange);
+            }
 
-        // Disable various terminal features
-        let mut stdout = io::stdout();
+            if config.bracketed_paste {
+                let _ = execute!(out, crossterm::event::DisableBracketedPaste);
+            }
 
-        if self.config.focus_reporting {
-            let _ = execute!(stdout, crossterm::event::DisableFocusChange);
-        }
+            if config.mouse_mode != MouseMode::None {
+                let _ = execute!(out, crossterm::event::DisableMouseCapture);
+            }
+            Ok(())
+        });
 
-        if self.config.bracketed_paste {
-            let _ = execute!(stdout, crossterm::event::DisableBracketedPaste);
+        // Show cursor
+        if self.config.inline_height.is_some() && !self.is_released {
+            let _ = self.finish_inline();
+        } else if let Some(ref mut terminal) = self.terminal {
+            let _ = terminal.show_cursor();
         }
 
-        if self.config.mouse_mode != MouseMode::None {
-            let _ = execute!(stdout, crossterm::event::DisableMouseCapture);
+        if config.alt_screen && !self.is_released {
+            let _ = self.write_escapes(|out| execute!(out, LeaveAlternateScreen));
         }
 
-        if self.config.alt_screen && !self.is_released {
-            let _ = execute!(stdout, LeaveAlternateScreen);
+        // Always try to disable raw mode if we enabled it
+        if config.raw_mode {
+            let _ = disable_raw_mode();
         }
 
-        // Always try to disable raw mode
-        let _ = disable_raw_mode();
-
         Ok(())
     }
 
@@ -212,6 +831,9 @@ impl TerminalManager {
     where
         F: FnOnce(&mut ratatui::Frame),
     {
+        if let Some(max_height) = self.config.inline_height {
+            return self.draw_inline(max_height, f);
+        }
         if let Some(ref mut terminal) = self.terminal {
             terminal.draw(f)?;
         }
@@ -219,14 +841,17 @@ impl TerminalManager {
     }
 
     /// Get the current terminal size
+    ///
+    /// Headless mode and outputs that can't report a size use 80x24.
     pub fn size(&self) -> io::Result<ratatui::layout::Rect> {
-        if let Some(ref terminal) = self.terminal {
-            let size = terminal.size()?;
-            Ok(ratatui::layout::Rect::new(0, 0, size.width, size.height))
-        } else {
-            // Return a default size for headless mode
-            Ok(ratatui::layout::Rect::new(0, 0, 80, 24))
-        }
+        let size = self
+            .terminal
+            .as_ref()
+            .and_then(|terminal| terminal.size().ok());
+        Ok(match size {
+            Some(size) => Rect::new(0, 0, size.width, size.height),
+            None => Rect::new(0, 0, 80, 24),
+        })
     }
 
     /// Clear the terminal
@@ -238,7 +863,60 @@ impl TerminalManager {
     }
 }
 
-impl Drop for TerminalManager {
+/// Count the rows up to the last one with anything in it
+fn content_height(buffer: &Buffer) -> u16 {
+    let width = usize::from(buffer.area.width);
+    if width == 0 {
+        return 0;
+    }
+    let blank = Cell::default();
+    buffer
+        .content
+        .chunks(width)
+        .rposition(|row| row.iter().any(|cell| *cell != blank))
+        .map_or(0, |last| last as u16 + 1)
+}
+
+/// Split text into rows no wider than `width` columns
+///
+/// Every line of the text starts a new row, and long lines wrap.
+fn wrap_rows(text: &str, width: u16) -> Vec<String> {
+    let width = usize::from(width.max(1));
+    let text = text.strip_suffix('\n').unwrap_or(text);
+
+    let mut rows = Vec::new();
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let mut row = String::new();
+        let mut row_width = 0;
+        for c in line.chars() {
+            let char_width = Span::raw(c.to_string()).width();
+            if row_width + char_width > width && !row.is_empty() {
+                rows.push(std::mem::take(&mut row));
+                row_width = 0;
+            }
+            row.push(c);
+            row_width += char_width;
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+/// Turn on mouse reporting for the given mode
+///
+/// crossterm's mouse capture reports all motion, so cell motion switches
+/// any-event tracking (mode 1003) back off, leaving button-event tracking.
+fn enable_mouse(out: &mut impl Write, mode: MouseMode) -> io::Result<()> {
+    execute!(out, crossterm::event::EnableMouseCapture)?;
+    if mode == MouseMode::CellMotion {
+        out.write_all(b"\x1b[?1003l")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+impl<B: ManagedBackend> Drop for TerminalManager<B> {
     fn drop(&mut self) {
         let _ = self.cleanup();
     }
@@ -247,6 +925,7 @@ impl Drop for TerminalManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ratatui::backend::TestBackend;
 
     #[test]
     fn test_terminal_config_default() {
@@ -255,6 +934,8 @@ mod tests {
         assert_eq!(config.mouse_mode, MouseMode::None);
         assert!(!config.bracketed_paste);
         assert!(!config.focus_reporting);
+        assert_eq!(config.inline_height, None);
+        assert!(config.raw_mode);
         assert!(!config.headless);
     }
 
@@ -266,10 +947,18 @@ mod tests {
         };
 
         let manager = TerminalManager::new(config).unwrap();
-        assert!(manager.terminal().is_none());
+        let terminal: Option<&Terminal<CrosstermBackend<Stdout>>> = manager.terminal();
+        assert!(terminal.is_none());
         assert!(!manager.is_released());
     }
 
+    #[test]
+    fn test_raw_mode_builder() {
+        let config = TerminalConfig::default().with_raw_mode(false);
+        assert!(!config.raw_mode);
+        assert!(config.with_raw_mode(true).raw_mode);
+    }
+
     #[test]
     fn test_terminal_manager_release_restore() {
         let config = TerminalConfig {
@@ -360,6 +1049,81 @@ mod tests {
         // Should not panic
     }
 
+    #[test]
+    fn test_apply_updates_config() {
+        let config = TerminalConfig {
+            headless: true,
+            alt_screen: false,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::new(config).unwrap();
+
+        manager.apply(&TerminalControlMsg::EnterAltScreen).unwrap();
+        manager
+            .apply(&TerminalControlMsg::EnableMouseAllMotion)
+            .unwrap();
+        manager
+            .apply(&TerminalControlMsg::EnableBracketedPaste)
+            .unwrap();
+        manager
+            .apply(&TerminalControlMsg::EnableFocusChange)
+            .unwrap();
+        assert!(manager.config().alt_screen);
+        assert_eq!(manager.config().mouse_mode, MouseMode::AllMotion);
+        assert!(manager.config().bracketed_paste);
+        assert!(manager.config().focus_reporting);
+
+        manager.apply(&TerminalControlMsg::ExitAltScreen).unwrap();
+        manager
+            .apply(&TerminalControlMsg::EnableMouseCellMotion)
+            .unwrap();
+        manager
+            .apply(&TerminalControlMsg::DisableBracketedPaste)
+            .unwrap();
+        manager
+            .apply(&TerminalControlMsg::DisableFocusChange)
+            .unwrap();
+        assert!(!manager.config().alt_screen);
+        assert_eq!(manager.config().mouse_mode, MouseMode::CellMotion);
+        assert!(!manager.config().bracketed_paste);
+        assert!(!manager.config().focus_reporting);
+
+        manager.apply(&TerminalControlMsg::DisableMouse).unwrap();
+        assert_eq!(manager.config().mouse_mode, MouseMode::None);
+    }
+
+    #[test]
+    fn test_apply_output_only_commands_headless() {
+        let config = TerminalConfig {
+            headless: true,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::new(config).unwrap();
+
+        for msg in [
+            TerminalControlMsg::HideCursor,
+            TerminalControlMsg::ShowCursor,
+            TerminalControlMsg::SetWindowTitle("title".to_string()),
+            TerminalControlMsg::ClearScreen,
+            TerminalControlMsg::ClearLine,
+        ] {
+            assert!(manager.apply(&msg).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_enable_mouse_cell_motion_turns_off_any_motion() {
+        let mut out = Vec::new();
+        enable_mouse(&mut out, MouseMode::CellMotion).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("\x1b[?1002h"));
+        assert!(written.ends_with("\x1b[?1003l"));
+
+        let mut out = Vec::new();
+        enable_mouse(&mut out, MouseMode::AllMotion).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("?1003l"));
+    }
+
     #[test]
     fn test_terminal_config_variations() {
         let configs = vec![
@@ -368,6 +1132,8 @@ mod tests {
                 mouse_mode: MouseMode::CellMotion,
                 bracketed_paste: true,
                 focus_reporting: true,
+                inline_height: None,
+                raw_mode: true,
                 headless: true,
             },
             TerminalConfig {
@@ -375,6 +1141,8 @@ mod tests {
                 mouse_mode: MouseMode::AllMotion,
                 bracketed_paste: false,
                 focus_reporting: false,
+                inline_height: Some(5),
+                raw_mode: false,
                 headless: true,
             },
         ];
@@ -384,4 +1152,200 @@ mod tests {
             assert!(manager.terminal().is_none()); // All are headless
         }
     }
+
+    /// A writer whose output can be inspected after the manager owns it
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    #[test]
+    fn test_with_writer_sends_escapes_to_writer() {
+        let out = SharedBuffer::default();
+        let config = TerminalConfig {
+            raw_mode: false,
+            bracketed_paste: true,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::with_writer(out.clone(), config).unwrap();
+        assert!(out.contents().contains("\x1b[?1049h"));
+        assert!(out.contents().contains("\x1b[?2004h"));
+
+        manager
+            .draw(|frame| {
+                frame.render_widget(ratatui::widgets::Paragraph::new("hello"), frame.area())
+            })
+            .unwrap();
+        assert!(out.contents().contains("hello"));
+
+        manager.cleanup().unwrap();
+        assert!(out.contents().contains("\x1b[?1049l"));
+    }
+
+    #[test]
+    fn test_inline_cleanup_disables_input_reporting() {
+        let out = SharedBuffer::default();
+        let config = TerminalConfig {
+            alt_screen: false,
+            mouse_mode: MouseMode::CellMotion,
+            bracketed_paste: true,
+            focus_reporting: true,
+            inline_height: Some(3),
+            raw_mode: false,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::with_writer(out.clone(), config).unwrap();
+        manager.cleanup().unwrap();
+
+        let written = out.contents();
+        assert!(written.contains("\x1b[?1004l"), "focus reporting left on");
+        assert!(written.contains("\x1b[?2004l"), "bracketed paste left on");
+        assert!(written.contains("\x1b[?1002l"), "mouse capture left on");
+    }
+
+    #[test]
+    fn test_with_backend_draws_to_backend() {
+        let config = TerminalConfig {
+            raw_mode: false,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::with_backend(TestBackend::new(20, 3), config).unwrap();
+        assert_eq!(manager.size().unwrap(), Rect::new(0, 0, 20, 3));
+
+        // Control commands have nowhere to go but still update the config
+        manager.apply(&TerminalControlMsg::ExitAltScreen).unwrap();
+        assert!(!manager.config().alt_screen);
+
+        manager
+            .draw(|frame| {
+                frame.render_widget(ratatui::widgets::Paragraph::new("hello"), frame.area())
+            })
+            .unwrap();
+
+        let backend = manager
+            .terminal()
+            .unwrap()
+            .backend()
+            .downcast_ref::<TestBackend>()
+            .unwrap();
+        let first_line: String = (0..5)
+            .map(|x| backend.buffer()[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(first_line, "hello");
+    }
+
+    fn row_text(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, y)].symbol().to_string())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    fn inline_manager(max_height: u16) -> TerminalManager<DynBackend> {
+        let config = TerminalConfig {
+            alt_screen: false,
+            inline_height: Some(max_height),
+            raw_mode: false,
+            ..Default::default()
+        };
+        TerminalManager::with_backend(TestBackend::new(20, 10), config).unwrap()
+    }
+
+    fn draw_lines(manager: &mut TerminalManager<DynBackend>, lines: &[&str]) {
+        let text = lines.join("\n");
+        manager
+            .draw(|frame| frame.render_widget(ratatui::widgets::Paragraph::new(text), frame.area()))
+            .unwrap();
+    }
+
+    fn screen(manager: &TerminalManager<DynBackend>) -> Buffer {
+        let backend = manager.terminal().unwrap().backend();
+        backend
+            .downcast_ref::<TestBackend>()
+            .unwrap()
+            .buffer()
+            .clone()
+    }
+
+    #[test]
+    fn test_inline_viewport_follows_content_height() {
+        let mut manager = inline_manager(5);
+        assert!(manager.is_inline());
+
+        draw_lines(&mut manager, &["one", "two"]);
+        let area = manager.terminal_mut().unwrap().get_frame().area();
+        assert_eq!(area.height, 2);
+        assert_eq!(row_text(&screen(&manager), area.y + 1), "two");
+
+        // Grows up to the maximum
+        draw_lines(&mut manager, &["1", "2", "3", "4", "5", "6"]);
+        let area = manager.terminal_mut().unwrap().get_frame().area();
+        assert_eq!(area.height, 5);
+        assert_eq!(row_text(&screen(&manager), area.y + 4), "5");
+
+        // Shrinking clears the rows it gave up
+        draw_lines(&mut manager, &["only"]);
+        let area = manager.terminal_mut().unwrap().get_frame().area();
+        assert_eq!(area.height, 1);
+        let buffer = screen(&manager);
+        assert_eq!(row_text(&buffer, area.y), "only");
+        assert_eq!(row_text(&buffer, area.y + 1), "");
+    }
+
+    #[test]
+    fn test_print_line_goes_above_inline_viewport() {
+        let mut manager = inline_manager(3);
+        draw_lines(&mut manager, &["live"]);
+
+        manager
+            .apply(&TerminalControlMsg::PrintLine("first".to_string()))
+            .unwrap();
+        manager.print_line("second\nthird").unwrap();
+        draw_lines(&mut manager, &["live"]);
+
+        let area = manager.terminal_mut().unwrap().get_frame().area();
+        let buffer = screen(&manager);
+        assert_eq!(area.y, 3);
+        assert_eq!(row_text(&buffer, 0), "first");
+        assert_eq!(row_text(&buffer, 1), "second");
+        assert_eq!(row_text(&buffer, 2), "third");
+        assert_eq!(row_text(&buffer, 3), "live");
+    }
+
+    #[test]
+    fn test_print_line_ignored_without_inline_viewport() {
+        let config = TerminalConfig {
+            raw_mode: false,
+            ..Default::default()
+        };
+        let mut manager = TerminalManager::with_backend(TestBackend::new(20, 5), config).unwrap();
+        manager.print_line("dropped").unwrap();
+        assert_eq!(row_text(&screen(&manager), 0), "");
+    }
+
+    #[test]
+    fn test_wrap_rows() {
+        assert_eq!(wrap_rows("hello", 10), vec!["hello"]);
+        assert_eq!(wrap_rows("hello\n", 10), vec!["hello"]);
+        assert_eq!(wrap_rows("", 10), vec![""]);
+        assert_eq!(wrap_rows("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(wrap_rows("a\r\nb", 3), vec!["a", "b"]);
+        // Wide characters don't get split across rows
+        assert_eq!(wrap_rows("日本語", 4), vec!["日本", "語"]);
+    }
 }
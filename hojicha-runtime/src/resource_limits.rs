@@ -14,6 +14,10 @@ use tokio::sync::Semaphore;
 pub struct ResourceLimits {
     /// Maximum number of concurrent async tasks (default: 1000)
     pub max_concurrent_tasks: usize,
+    /// Maximum number of synchronous commands running on blocking threads
+    /// at once (default: 64); further ones wait for a free slot. At least one
+    /// always may, so blocking commands can't wait forever.
+    pub max_blocking_tasks: usize,
     /// Maximum recursion depth for commands (default: 100)
     pub max_recursion_depth: usize,
     /// Warning threshold for concurrent tasks (default: 80% of max)
@@ -29,6 +33,7 @@ impl Default for ResourceLimits {
         let max_tasks = 1000;
         Self {
             max_concurrent_tasks: max_tasks,
+            max_blocking_tasks: 64,
             max_recursion_depth: 100,
             task_warning_threshold: (max_tasks as f64 * 0.8) as usize,
             log_warnings: true,
@@ -45,6 +50,12 @@ impl ResourceLimits {
         self
     }
     
+    /// Set how many blocking commands may run at once
+    pub fn with_max_blocking_tasks(mut self, max: usize) -> Self {
+        self.max_blocking_tasks = max;
+        self
+    }
+    
     /// Set the recursion depth limit
     pub fn with_max_recursion(mut self, depth: usize) -> Self {
         self.max_recursion_depth = depth;
@@ -55,6 +66,7 @@ impl ResourceLimits {
     pub fn unlimited() -> Self {
         Self {
             max_concurrent_tasks: usize::MAX,
+            max_blocking_tasks: usize::MAX,
             max_recursion_depth: usize::MAX,
             task_warning_threshold: usize::MAX,
             log_warnings: false,
@@ -71,6 +83,8 @@ pub struct ResourceMonitor {
     total_rejected: Arc<AtomicUsize>,
     peak_concurrent: Arc<AtomicUsize>,
     task_semaphore: Arc<Semaphore>,
+    active_blocking: Arc<AtomicUsize>,
+    blocking_semaphore: Arc<Semaphore>,
     start_time: Instant,
 }
 
@@ -82,7 +96,13 @@ impl ResourceMonitor {
     
     /// Create a new resource monitor with custom limits
     pub fn with_limits(limits: ResourceLimits) -> Self {
-        let semaphore = Arc::new(Semaphore::new(limits.max_concurrent_tasks));
+        // Semaphores can't hold more than MAX_PERMITS, which unlimited() exceeds
+        let semaphore = Arc::new(Semaphore::new(
+            limits.max_concurrent_tasks.min(Semaphore::MAX_PERMITS),
+        ));
+        let blocking_semaphore = Arc::new(Semaphore::new(
+            limits.max_blocking_tasks.clamp(1, Semaphore::MAX_PERMITS),
+        ));
         
         Self {
             limits,
@@ -91,6 +111,8 @@ impl ResourceMonitor {
             total_rejected: Arc::new(AtomicUsize::new(0)),
             peak_concurrent: Arc::new(AtomicUsize::new(0)),
             task_semaphore: semaphore,
+            active_blocking: Arc::new(AtomicUsize::new(0)),
+            blocking_semaphore,
             start_time: Instant::now(),
         }
     }
@@ -165,6 +187,24 @@ impl ResourceMonitor {
         }
     }
     
+    /// Wait for a permit to run a command on a blocking thread
+    ///
+    /// Unlike async tasks, blocking commands are never rejected: they wait
+    /// until one of the `max_blocking_tasks` slots is free.
+    pub async fn acquire_blocking_permit(&self) -> Result<TaskPermit, ResourceExhausted> {
+        let permit = self
+            .blocking_semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ResourceExhausted::BlockingLimit(self.limits.max_blocking_tasks))?;
+        self.active_blocking.fetch_add(1, Ordering::SeqCst);
+        Ok(TaskPermit {
+            _permit: permit,
+            active_tasks: self.active_blocking.clone(),
+        })
+    }
+    
     /// Get current resource statistics
     pub fn stats(&self) -> ResourceStats {
         ResourceStats {
@@ -173,6 +213,8 @@ impl ResourceMonitor {
             total_rejected: self.total_rejected.load(Ordering::SeqCst),
             peak_concurrent: self.peak_concurrent.load(Ordering::SeqCst),
             max_concurrent_tasks: self.limits.max_concurrent_tasks,
+            active_blocking_tasks: self.active_blocking.load(Ordering::SeqCst),
+            max_blocking_tasks: self.limits.max_blocking_tasks,
             uptime: self.start_time.elapsed(),
         }
     }
@@ -200,7 +242,7 @@ impl ResourceMonitor {
     }
 }
 
-/// A permit to spawn an async task
+/// A permit to spawn an async task or run a blocking command
 pub struct TaskPermit {
     _permit: tokio::sync::OwnedSemaphorePermit,
     active_tasks: Arc<AtomicUsize>,
@@ -218,6 +260,10 @@ pub enum ResourceExhausted {
     #[error("Async task limit exceeded (limit: {0})")]
     TaskLimit(usize),
     
+    /// The blocking command pool was shut down
+    #[error("Blocking task pool is closed (limit: {0})")]
+    BlockingLimit(usize),
+    
     #[error("Recursion depth limit exceeded (limit: {0})")]
     RecursionDepth(usize),
     
@@ -238,6 +284,10 @@ pub struct ResourceStats {
     pub peak_concurrent: usize,
     /// Configured maximum concurrent tasks
     pub max_concurrent_tasks: usize,
+    /// Blocking commands currently running
+    pub active_blocking_tasks: usize,
+    /// Configured maximum concurrent blocking commands
+    pub max_blocking_tasks: usize,
     /// Time since monitor started
     pub uptime: Duration,
 }
@@ -249,6 +299,7 @@ impl ResourceStats {
             "Resource Stats:\n\
              - Active tasks: {}/{} ({}%)\n\
              - Peak concurrent: {}\n\
+             - Blocking tasks: {}/{}\n\
              - Total spawned: {}\n\
              - Total rejected: {}\n\
              - Uptime: {:?}",
@@ -256,6 +307,8 @@ impl ResourceStats {
             self.max_concurrent_tasks,
             (self.active_tasks as f64 / self.max_concurrent_tasks as f64 * 100.0) as u32,
             self.peak_concurrent,
+            self.active_blocking_tasks,
+            self.max_blocking_tasks,
             self.total_spawned,
             self.total_rejected,
             self.uptime
@@ -293,6 +346,48 @@ mod tests {
         assert!(permit4.is_ok());
     }
     
+    #[tokio::test]
+    async fn test_blocking_permits_wait_for_a_free_slot() {
+        let monitor = Arc::new(ResourceMonitor::with_limits(
+            ResourceLimits::default().with_max_blocking_tasks(1)
+        ));
+        
+        let permit = monitor.acquire_blocking_permit().await.unwrap();
+        assert_eq!(monitor.stats().active_blocking_tasks, 1);
+        
+        // The second one waits rather than failing
+        let waiting = {
+            let monitor = monitor.clone();
+            tokio::spawn(async move { monitor.acquire_blocking_permit().await.is_ok() })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+        
+        drop(permit);
+        assert!(waiting.await.unwrap());
+        assert_eq!(monitor.stats().active_blocking_tasks, 0);
+    }
+    
+    #[test]
+    fn test_unlimited_does_not_overflow_semaphores() {
+        let monitor = ResourceMonitor::with_limits(ResourceLimits::unlimited());
+        assert_eq!(monitor.stats().max_blocking_tasks, usize::MAX);
+    }
+    
+    #[tokio::test]
+    async fn test_blocking_limit_is_at_least_one() {
+        let monitor = ResourceMonitor::with_limits(ResourceLimits {
+            max_blocking_tasks: 0,
+            ..ResourceLimits::default()
+        });
+        let permit = tokio::time::timeout(
+            Duration::from_millis(100),
+            monitor.acquire_blocking_permit(),
+        )
+        .await;
+        assert!(matches!(permit, Ok(Ok(_))));
+    }
+    
     #[test]
     fn test_recursion_limits() {
         let monitor = ResourceMonitor::with_limits(
@@ -10,7 +10,15 @@ use hojicha_core::event::Event;
 /// Detect the priority of an event without unsafe operations
 pub fn detect_priority<M: Message>(event: &Event<M>) -> Priority {
     // Use helper methods for cleaner priority detection
-    if event.is_quit() || event.is_key() || event.is_suspend() || event.is_resume() {
+    if event.is_quit() || event.is_suspend() || event.is_resume() {
+        Priority::High
+    } else if event.is_key()
+        || event.is_mouse()
+        || event.is_paste()
+        || event.is_focus()
+        || event.is_blur()
+    {
+        // Input shares one level so it is handled in the order it was typed
         Priority::High
     } else if event.is_resize() || event.is_tick() {
         Priority::Low  
@@ -47,8 +55,12 @@ pub enum EventKind {
     Suspend,
     /// Process resumption event
     Resume,
+    /// Interrupt signal event
+    Interrupt,
     /// Process execution event
     ExecProcess,
+    /// Terminal control event
+    TerminalControl,
 }
 
 impl EventKind {
@@ -59,12 +71,14 @@ impl EventKind {
             EventKind::Key => Priority::High,
             EventKind::Suspend => Priority::High,
             EventKind::Resume => Priority::High,
+            EventKind::Interrupt => Priority::High,
+            EventKind::Mouse => Priority::High,
+            EventKind::Paste => Priority::High,
+            EventKind::Focus => Priority::High,
+            EventKind::Blur => Priority::High,
 
-            EventKind::Mouse => Priority::Normal,
             EventKind::User => Priority::Normal,
-            EventKind::Paste => Priority::Normal,
-            EventKind::Focus => Priority::Normal,
-            EventKind::Blur => Priority::Normal,
+            EventKind::TerminalControl => Priority::Normal,
             EventKind::ExecProcess => Priority::Normal,
 
             EventKind::Resize => Priority::Low,
@@ -85,6 +99,8 @@ impl EventKind {
         else if event.is_blur() { EventKind::Blur }
         else if event.is_suspend() { EventKind::Suspend }
         else if event.is_resume() { EventKind::Resume }
+        else if event.is_interrupt() { EventKind::Interrupt }
+        else if matches!(event, Event::TerminalControl(_)) { EventKind::TerminalControl }
         else { EventKind::ExecProcess }
     }
 }
@@ -0,0 +1,276 @@
+//! OS signal handling for the program
+//!
+//! When `ProgramOptions::install_signal_handler` is set (the default), the
+//! program listens for signals while it runs and turns them into events:
+//!
+//! - **SIGTERM / SIGHUP**: `Event::Quit`, so the loop exits and the terminal
+//!   is restored
+//! - **SIGINT**: `Event::Interrupt` for the model to handle. In raw mode Ctrl+C
+//!   is delivered as a key event, so this mostly matters in headless programs
+//!   or when another process sends the signal
+//! - **SIGWINCH**: `Event::Resize`, when crossterm isn't reading the terminal
+//!   (headless or custom IO) and can't report resizes itself
+//!
+//! On non-Unix platforms only Ctrl+C is handled.
+//!
+//! Once the last handler is dropped, SIGTERM, SIGHUP and SIGINT get back the
+//! disposition they had before, so they terminate the process again after the
+//! program has run.
+
+use hojicha_core::event::Event;
+use std::io;
+use std::sync::mpsc;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+/// Queries the terminal size when a SIGWINCH arrives
+pub type SizeQuery = Box<dyn Fn() -> io::Result<(u16, u16)> + Send + Sync>;
+
+/// Forwards OS signals to the program as events
+///
+/// The handlers stop forwarding when this is dropped.
+pub struct SignalHandler {
+    task: JoinHandle<()>,
+    _dispositions: imp::Dispositions,
+}
+
+impl SignalHandler {
+    /// Start forwarding signals to `tx` on the given runtime
+    ///
+    /// Pass a `resize` query to also turn SIGWINCH into `Event::Resize`.
+    /// The handlers are registered before this returns, so signals sent right
+    /// after it can't hit the default action.
+    pub fn install<M>(
+        runtime: &Handle,
+        tx: mpsc::SyncSender<Event<M>>,
+        resize: Option<SizeQuery>,
+    ) -> io::Result<Self>
+    where
+        M: Send + 'static,
+    {
+        let _guard = runtime.enter();
+        let (task, dispositions) = imp::spawn(runtime, tx, resize)?;
+        Ok(Self {
+            task,
+            _dispositions: dispositions,
+        })
+    }
+}
+
+impl Drop for SignalHandler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::SizeQuery;
+    use hojicha_core::event::Event;
+    use std::io;
+    use std::sync::{mpsc, Mutex};
+    use tokio::runtime::Handle;
+    use tokio::signal::unix::{signal, SignalKind};
+    use tokio::task::JoinHandle;
+
+    /// Signals whose default action terminates the process
+    const TERMINATING: [libc::c_int; 3] = [libc::SIGTERM, libc::SIGHUP, libc::SIGINT];
+
+    /// How many handlers are live, and the dispositions of [`TERMINATING`]
+    /// from before the first of them was installed
+    static PREVIOUS: Mutex<(usize, Vec<libc::sigaction>)> = Mutex::new((0, Vec::new()));
+
+    /// Tokio's handlers for [`TERMINATING`]
+    ///
+    /// Tokio only installs them once per process, so they are put back by hand
+    /// after an earlier handler restored the previous dispositions.
+    static TOKIO: Mutex<Vec<libc::sigaction>> = Mutex::new(Vec::new());
+
+    /// Restores the dispositions of [`TERMINATING`] when the last live handler
+    /// is dropped
+    pub(super) struct Dispositions(());
+
+    impl Dispositions {
+        /// Count a new handler, recording the dispositions if it is the first
+        fn acquire() -> io::Result<Self> {
+            let mut previous = PREVIOUS.lock().unwrap();
+            if previous.0 == 0 {
+                previous.1 = dispositions()?;
+            }
+            previous.0 += 1;
+            Ok(Self(()))
+        }
+
+        /// Install Tokio's handlers, which have been registered by now
+        fn install_tokio_handlers(&self) -> io::Result<()> {
+            let mut tokio = TOKIO.lock().unwrap();
+            if tokio.is_empty() {
+                *tokio = dispositions()?;
+            }
+            for (&signal, action) in TERMINATING.iter().zip(tokio.iter()) {
+                set_disposition(signal, action)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Dispositions {
+        fn drop(&mut self) {
+            let mut previous = PREVIOUS.lock().unwrap();
+            previous.0 -= 1;
+            if previous.0 > 0 {
+                return;
+            }
+            for (&signal, action) in TERMINATING.iter().zip(previous.1.iter()) {
+                if let Err(e) = set_disposition(signal, action) {
+                    log::warn!("Failed to restore the handler of signal {}: {}", signal, e);
+                }
+            }
+        }
+    }
+
+    /// Get the current dispositions of [`TERMINATING`]
+    fn dispositions() -> io::Result<Vec<libc::sigaction>> {
+        TERMINATING
+            .iter()
+            .map(|&signal| {
+                // SAFETY: sigaction only writes to the zeroed struct we own
+                unsafe {
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    if libc::sigaction(signal, std::ptr::null(), &mut action) == 0 {
+                        Ok(action)
+                    } else {
+                        Err(io::Error::last_os_error())
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn set_disposition(signal: libc::c_int, action: &libc::sigaction) -> io::Result<()> {
+        // SAFETY: the action was read by sigaction, so it is a valid disposition
+        if unsafe { libc::sigaction(signal, action, std::ptr::null_mut()) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub(super) fn spawn<M>(
+        runtime: &Handle,
+        tx: mpsc::SyncSender<Event<M>>,
+        resize: Option<SizeQuery>,
+    ) -> io::Result<(JoinHandle<()>, Dispositions)>
+    where
+        M: Send + 'static,
+    {
+        let dispositions = Dispositions::acquire()?;
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut hangup = signal(SignalKind::hangup())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut window_change = match resize {
+            Some(_) => Some(signal(SignalKind::window_change())?),
+            None => None,
+        };
+        dispositions.install_tokio_handlers()?;
+
+        let task = runtime.spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = terminate.recv() => {
+                        log::info!("Received SIGTERM, quitting");
+                        Event::Quit
+                    }
+                    _ = hangup.recv() => {
+                        log::info!("Received SIGHUP, quitting");
+                        Event::Quit
+                    }
+                    _ = interrupt.recv() => Event::Interrupt,
+                    Some(_) = async {
+                        match window_change.as_mut() {
+                            Some(stream) => stream.recv().await,
+                            None => std::future::pending().await,
+                        }
+                    } => {
+                        let query = resize.as_ref().expect("resize query is set with SIGWINCH");
+                        match query() {
+                            Ok((width, height)) => Event::Resize { width, height },
+                            Err(e) => {
+                                log::debug!("Ignoring SIGWINCH, can't read terminal size: {}", e);
+                                continue;
+                            }
+                        }
+                    }
+                };
+
+                if tx.send(event).is_err() {
+                    break; // Program has shut down
+                }
+            }
+        });
+        Ok((task, dispositions))
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use super::SizeQuery;
+    use hojicha_core::event::Event;
+    use std::io;
+    use std::sync::mpsc;
+    use tokio::runtime::Handle;
+    use tokio::task::JoinHandle;
+
+    /// Nothing to restore outside Unix
+    pub(super) struct Dispositions;
+
+    pub(super) fn spawn<M>(
+        runtime: &Handle,
+        tx: mpsc::SyncSender<Event<M>>,
+        _resize: Option<SizeQuery>,
+    ) -> io::Result<(JoinHandle<()>, Dispositions)>
+    where
+        M: Send + 'static,
+    {
+        let task = runtime.spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if tx.send(Event::Interrupt).is_err() {
+                    break; // Program has shut down
+                }
+            }
+        });
+        Ok((task, Dispositions))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_sigwinch_sends_resize() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel::<Event<()>>(10);
+        let _handler = SignalHandler::install(
+            runtime.handle(),
+            tx,
+            Some(Box::new(|| Ok((120, 40)))),
+        )
+        .unwrap();
+
+        // SIGWINCH is ignored by default, so this can't disturb other tests
+        unsafe {
+            libc::raise(libc::SIGWINCH);
+        }
+
+        let event = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(
+            event,
+            Event::Resize {
+                width: 120,
+                height: 40
+            }
+        );
+    }
+}
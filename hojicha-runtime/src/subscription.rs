@@ -27,7 +27,21 @@
 //!
 //! Subscriptions are automatically cancelled when dropped, ensuring no resource
 //! leaks from forgotten stream subscriptions.
+//!
+//! # Declarative Subscriptions
+//!
+//! Models can also list their subscriptions in
+//! [`Model::subscriptions`](hojicha_core::core::Model::subscriptions). The
+//! program keeps those in a [`SubscriptionSet`] and starts or cancels them
+//! after every update, so no handle has to be kept around.
 
+use crate::program::CommandExecutor;
+use futures::{Stream, StreamExt};
+use hojicha_core::core::Message;
+use hojicha_core::event::Event;
+use hojicha_core::subscriptions::Subs;
+use std::collections::HashMap;
+use std::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
@@ -71,6 +85,126 @@ impl Drop for Subscription {
     }
 }
 
+/// Forward every item of `stream` to the program as `Event::User`
+///
+/// The task stops when the stream ends, the subscription is cancelled or the
+/// program has shut down.
+pub(crate) fn spawn_stream<M, S>(
+    executor: &CommandExecutor<M>,
+    stream: S,
+    sender: mpsc::SyncSender<Event<M>>,
+) -> Subscription
+where
+    M: Message + Clone,
+    S: Stream<Item = M> + Send + 'static,
+{
+    let cancel_token = CancellationToken::new();
+    let cancel_clone = cancel_token.clone();
+    let room = executor.message_room().clone();
+
+    let handle = executor.spawn(async move {
+        let forward = async {
+            tokio::pin!(stream);
+            while let Some(msg) = stream.next().await {
+                // Wait for room in the channel without holding up a worker
+                if room.send(&sender, Event::User(msg), None).await.is_err() {
+                    break; // Program has shut down
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = cancel_clone.cancelled() => {} // Subscription was cancelled
+            _ = forward => {} // Stream completed
+        }
+    });
+
+    Subscription::new(handle, cancel_token)
+}
+
+/// The running subscriptions declared by a model, by key
+///
+/// [`sync`](Self::sync) compares a fresh [`Subs`] with what is running:
+/// new keys are started, keys that disappeared are cancelled and keys that
+/// stay keep their stream. A stream that ended on its own isn't restarted
+/// while its key stays in the set.
+pub struct SubscriptionSet {
+    active: HashMap<String, Subscription>,
+}
+
+impl SubscriptionSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self {
+            active: HashMap::new(),
+        }
+    }
+
+    /// Start and cancel subscriptions so the set matches `subs`
+    pub(crate) fn sync<M: Message + Clone>(
+        &mut self,
+        subs: Subs<M>,
+        executor: &CommandExecutor<M>,
+        sender: &mpsc::SyncSender<Event<M>>,
+    ) {
+        let mut next = HashMap::with_capacity(subs.len());
+        for sub in subs {
+            if next.contains_key(sub.key()) {
+                log::warn!("Duplicate subscription key {:?} ignored", sub.key());
+                continue;
+            }
+
+            let key = sub.key().to_string();
+            let subscription = match self.active.remove(&key) {
+                Some(running) => running,
+                None => {
+                    log::debug!("Starting subscription {:?}", key);
+                    spawn_stream(executor, sub.into_stream(), sender.clone())
+                }
+            };
+            next.insert(key, subscription);
+        }
+
+        // Whatever is left wasn't declared again; dropping it cancels it
+        for key in self.active.keys() {
+            log::debug!("Cancelling subscription {:?}", key);
+        }
+        self.active = next;
+    }
+
+    /// Whether a subscription with this key is running
+    pub fn contains(&self, key: &str) -> bool {
+        self.active.contains_key(key)
+    }
+
+    /// Number of subscriptions in the set
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Cancel every subscription
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+}
+
+impl Default for SubscriptionSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for SubscriptionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.active.keys()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +233,22 @@ mod tests {
         assert!(!subscription.is_active());
     }
 
+    #[test]
+    fn test_cancel_stops_a_subscription_waiting_for_room() {
+        let executor = CommandExecutor::<u32>::new().unwrap();
+        let (tx, rx) = mpsc::sync_channel(1);
+        let subscription = spawn_stream(&executor, futures::stream::iter(0..3), tx);
+
+        // The first message fills the channel and the second waits for room
+        std::thread::sleep(Duration::from_millis(50));
+        subscription.cancel();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(subscription.is_finished());
+
+        assert_eq!(rx.try_recv().ok(), Some(Event::User(0)));
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_subscription_drop_cancels() {
         let token = CancellationToken::new();
@@ -121,4 +271,42 @@ mod tests {
         tokio::time::sleep(Duration::from_millis(50)).await;
         assert!(token.is_cancelled());
     }
+
+    #[test]
+    fn test_subscription_set_diffs_by_key() {
+        use hojicha_core::subscriptions::Sub;
+
+        let executor = CommandExecutor::<u32>::new().unwrap();
+        let (tx, _rx) = mpsc::sync_channel(16);
+        let pending = || futures::stream::pending::<u32>();
+        let mut set = SubscriptionSet::new();
+
+        set.sync(
+            Subs::none()
+                .with(Sub::stream("a", pending))
+                .with(Sub::stream("b", pending)),
+            &executor,
+            &tx,
+        );
+        assert_eq!(set.len(), 2);
+        let a = set.active["a"].cancel_token.clone();
+        let b = set.active["b"].cancel_token.clone();
+
+        set.sync(
+            Subs::none()
+                .with(Sub::stream("a", pending))
+                .with(Sub::stream("c", pending))
+                .with(Sub::stream("c", pending)),
+            &executor,
+            &tx,
+        );
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("a") && set.contains("c"));
+        assert!(!a.is_cancelled(), "kept subscriptions keep running");
+        assert!(b.is_cancelled(), "removed subscriptions are cancelled");
+
+        set.clear();
+        assert!(set.is_empty());
+        assert!(a.is_cancelled());
+    }
 }
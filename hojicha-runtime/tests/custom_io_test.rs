@@ -0,0 +1,233 @@
+//! Integration tests for custom input streams, output writers and backends
+//!
+//! Input tests pipe the same escape sequences a terminal would send.
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::{
+    Event, Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::widgets::Paragraph;
+use ratatui::{layout::Rect, Frame};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Records typed characters and quits shortly after seeing 'q'
+#[derive(Default)]
+struct TypingModel {
+    typed: Arc<Mutex<String>>,
+    frames: Arc<AtomicUsize>,
+}
+
+impl Model for TypingModel {
+    type Message = ();
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        match event {
+            Event::Key(key) => {
+                if let Key::Char(c) = key.key {
+                    self.typed.lock().unwrap().push(c);
+                }
+                if key.key == Key::Char('q') {
+                    // Give the program time to draw a frame first
+                    return commands::tick(Duration::from_millis(100), || ());
+                }
+                Cmd::none()
+            }
+            Event::User(()) => commands::quit(),
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+        frame.render_widget(Paragraph::new("hello"), area);
+    }
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_input_string_and_output_writer() {
+    let model = TypingModel::default();
+    let (typed, frames) = (model.typed.clone(), model.frames.clone());
+    let out = SharedBuffer::default();
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_output(Box::new(out.clone()))
+        .with_input_string("hi\x1b[Aq");
+
+    let program = Program::with_options(model, options).unwrap();
+    program.run_with_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(*typed.lock().unwrap(), "hiq");
+    assert!(frames.load(Ordering::SeqCst) > 0);
+    let written = String::from_utf8_lossy(&out.0.lock().unwrap()).into_owned();
+    assert!(written.contains("\x1b[?1049h"), "alt screen not entered");
+    assert!(written.contains("hello"), "frame not drawn: {written:?}");
+    assert!(written.contains("\x1b[?1049l"), "alt screen not left");
+}
+
+#[test]
+fn test_input_is_read_when_headless() {
+    let model = TypingModel::default();
+    let (typed, frames) = (model.typed.clone(), model.frames.clone());
+    let options = ProgramOptions::default()
+        .headless()
+        .without_signal_handler()
+        .with_input_string("abq");
+
+    let program = Program::with_options(model, options).unwrap();
+    program.run_with_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(*typed.lock().unwrap(), "abq");
+    assert_eq!(frames.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_program_draws_to_test_backend() {
+    let model = TypingModel::default();
+    let (typed, frames) = (model.typed.clone(), model.frames.clone());
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("q");
+
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    program.run_with_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(*typed.lock().unwrap(), "q");
+    assert!(frames.load(Ordering::SeqCst) > 0);
+}
+
+/// Records every input event
+#[derive(Default)]
+struct RecordingModel {
+    events: Arc<Mutex<Vec<Event<()>>>>,
+}
+
+impl Model for RecordingModel {
+    type Message = ();
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if matches!(
+            event,
+            Event::Key(_) | Event::Mouse(_) | Event::Paste(_) | Event::Focus | Event::Blur
+        ) {
+            self.events.lock().unwrap().push(event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+/// Hands out chunks of input with a pause before each one
+struct SlowReader {
+    chunks: std::collections::VecDeque<Vec<u8>>,
+    delay: Duration,
+}
+
+impl io::Read for SlowReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(chunk) = self.chunks.pop_front() else {
+            return Ok(0);
+        };
+        std::thread::sleep(self.delay);
+        buf[..chunk.len()].copy_from_slice(&chunk);
+        Ok(chunk.len())
+    }
+}
+
+/// Run until `expected` input events have arrived
+fn run_recording(options: ProgramOptions, expected: usize) -> Vec<Event<()>> {
+    let model = RecordingModel::default();
+    let events = model.events.clone();
+    let options = options.headless().without_signal_handler();
+    let program = Program::with_options(model, options).unwrap();
+    program
+        .run_until(move |model: &RecordingModel| model.events.lock().unwrap().len() >= expected)
+        .unwrap();
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+fn key_event(key: Key, modifiers: KeyModifiers) -> Event<()> {
+    Event::Key(KeyEvent::new(key, modifiers))
+}
+
+#[test]
+fn test_escape_sequences_are_decoded() {
+    let events = run_recording(
+        ProgramOptions::default()
+            .with_input_string("\x1b[I\x1b[1;5A\x1b[<0;3;2M\x1b[200~a\rb\x1b[201~\x1bOP"),
+        5,
+    );
+
+    // All input shares a priority, so it arrives in the order it was typed
+    let expected = vec![
+        Event::Focus,
+        key_event(Key::Up, KeyModifiers::CONTROL),
+        Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            2,
+            1,
+            KeyModifiers::NONE,
+        )),
+        Event::Paste("a\nb".to_string()),
+        key_event(Key::F(1), KeyModifiers::NONE),
+    ];
+    assert_eq!(events, expected);
+}
+
+#[test]
+fn test_lone_escape_resolves_after_timeout() {
+    let reader = SlowReader {
+        chunks: vec![b"\x1b".to_vec(), b"x".to_vec()].into(),
+        delay: Duration::from_millis(200),
+    };
+    let events = run_recording(
+        ProgramOptions::default()
+            .with_input(Box::new(reader))
+            .with_esc_timeout(Duration::from_millis(20)),
+        2,
+    );
+
+    assert_eq!(
+        events,
+        vec![
+            key_event(Key::Esc, KeyModifiers::NONE),
+            key_event(Key::Char('x'), KeyModifiers::NONE),
+        ]
+    );
+}
+
+#[test]
+fn test_split_sequence_waits_within_timeout() {
+    let reader = SlowReader {
+        chunks: vec![b"\x1b[1;".to_vec(), b"5A".to_vec()].into(),
+        delay: Duration::from_millis(20),
+    };
+    let events = run_recording(
+        ProgramOptions::default()
+            .with_input(Box::new(reader))
+            .with_esc_timeout(Duration::from_secs(2)),
+        1,
+    );
+
+    assert_eq!(events, vec![key_event(Key::Up, KeyModifiers::CONTROL)]);
+}
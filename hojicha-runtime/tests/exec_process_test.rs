@@ -0,0 +1,90 @@
+//! Integration tests for running external processes from commands
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::{layout::Rect, Frame};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Msg {
+    Exited(Option<i32>),
+    After,
+}
+
+/// Records every user message it receives
+struct ExecModel {
+    init: Option<Cmd<Msg>>,
+    received: Arc<Mutex<Vec<Msg>>>,
+}
+
+impl Model for ExecModel {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        self.init.take().unwrap_or_else(Cmd::none)
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        // The runtime's internal wake-up event must never reach the model
+        assert!(!matches!(event, Event::ExecProcess));
+        if let Event::User(msg) = event {
+            self.received.lock().unwrap().push(msg);
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+fn run_exec(cmd: Cmd<Msg>, expected: usize) -> Vec<Msg> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let model = ExecModel {
+        init: Some(cmd),
+        received: received.clone(),
+    };
+
+    let options = ProgramOptions::default().headless();
+    let program = Program::with_options(model, options).unwrap();
+    program
+        .run_until(move |model: &ExecModel| model.received.lock().unwrap().len() >= expected)
+        .unwrap();
+
+    let messages = received.lock().unwrap().clone();
+    messages
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_delivers_exit_code() {
+    let messages = run_exec(commands::exec("true", Vec::<String>::new(), Msg::Exited), 1);
+    assert_eq!(messages, vec![Msg::Exited(Some(0))]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_command_runs_through_shell() {
+    let messages = run_exec(commands::exec_command("exit 3", Msg::Exited), 1);
+    assert_eq!(messages, vec![Msg::Exited(Some(3))]);
+}
+
+#[test]
+fn test_exec_missing_program_reports_none() {
+    let messages = run_exec(
+        commands::exec("hojicha-no-such-program", Vec::<String>::new(), Msg::Exited),
+        1,
+    );
+    assert_eq!(messages, vec![Msg::Exited(None)]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_in_sequence_runs_before_next_command() {
+    let cmd = commands::sequence(vec![
+        commands::exec_command("exit 1", Msg::Exited),
+        commands::custom(|| Some(Msg::After)),
+    ]);
+    let messages = run_exec(cmd, 2);
+    assert_eq!(messages, vec![Msg::Exited(Some(1)), Msg::After]);
+}
@@ -0,0 +1,146 @@
+//! Integration tests for running a FallibleModel and routing command errors
+
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::error::{Error, Result};
+use hojicha_core::event::Event;
+use hojicha_core::fallible::FallibleModel;
+use hojicha_runtime::program::error_handler::EventErrorHandler;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::{layout::Rect, Frame};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Msg {
+    Parse(&'static str),
+    Failed(String),
+}
+
+/// Loads a config that doesn't exist and parses some input
+struct Loader {
+    input: &'static str,
+    errors: Arc<Mutex<Vec<String>>>,
+}
+
+impl Loader {
+    fn record(&self, error: String) -> Cmd<Msg> {
+        let mut errors = self.errors.lock().unwrap();
+        errors.push(error);
+        if errors.len() == 2 {
+            Cmd::quit()
+        } else {
+            Cmd::none()
+        }
+    }
+}
+
+impl Model for Loader {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        let input = self.input;
+        Cmd::batch(vec![
+            Cmd::fallible(|| Err(io::Error::new(io::ErrorKind::NotFound, "no config").into()))
+                .labeled("load config"),
+            Cmd::new(move || Some(Msg::Parse(input))),
+        ])
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        match event {
+            Event::User(Msg::Failed(error)) => self.record(format!("message: {}", error)),
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+impl FallibleModel for Loader {
+    fn try_update(&mut self, event: Event<Self::Message>) -> Result<Cmd<Self::Message>> {
+        match event {
+            Event::User(Msg::Parse(input)) => {
+                input
+                    .parse::<u32>()
+                    .map_err(|e| Error::Model(e.to_string()))?;
+                Ok(Cmd::none())
+            }
+            event => Ok(self.update(event)),
+        }
+    }
+
+    fn handle_error(&mut self, error: Error) -> Cmd<Self::Message> {
+        let label = error.command_label().unwrap_or("update").to_string();
+        self.record(format!("{}: {}", label, error))
+    }
+}
+
+fn program(input: &'static str, errors: &Arc<Mutex<Vec<String>>>) -> Program<Loader> {
+    let model = Loader {
+        input,
+        errors: Arc::clone(errors),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("");
+    Program::with_backend(model, TestBackend::new(20, 5), options).unwrap()
+}
+
+fn sorted(errors: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+    let mut errors = errors.lock().unwrap().clone();
+    errors.sort();
+    errors
+}
+
+#[test]
+fn test_update_and_command_errors_reach_handle_error() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    program("twelve", &errors)
+        .with_fallible_model()
+        .run_with_timeout(Duration::from_secs(2))
+        .unwrap();
+
+    assert_eq!(
+        sorted(&errors),
+        vec![
+            "load config: load config failed: I/O error: no config".to_string(),
+            "update: Model error: invalid digit found in string".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_error_handler_turns_command_errors_into_messages() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let handler = EventErrorHandler::new(|error: Error| {
+        Msg::Failed(error.command_label().unwrap_or_default().to_string())
+    });
+    // The custom handler wins no matter which builder runs first
+    program("twelve", &errors)
+        .with_error_handler(handler)
+        .with_fallible_model()
+        .run_with_timeout(Duration::from_secs(2))
+        .unwrap();
+
+    assert_eq!(
+        sorted(&errors),
+        vec![
+            "message: load config".to_string(),
+            "update: Model error: invalid digit found in string".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_plain_model_only_sees_update() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    // Without with_fallible_model the parse error and the command error are
+    // never seen by the model, so the program runs until the timeout
+    program("twelve", &errors)
+        .run_with_timeout(Duration::from_millis(200))
+        .unwrap();
+
+    assert!(errors.lock().unwrap().is_empty());
+}
@@ -0,0 +1,156 @@
+//! Integration tests for inline rendering and printing above the viewport
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::{Backend, ClearType, TestBackend, WindowSize};
+use ratatui::buffer::Cell;
+use ratatui::layout::{Position, Size};
+use ratatui::widgets::Paragraph;
+use ratatui::{layout::Rect, Frame};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A `TestBackend` that can still be inspected after the program owns it
+#[derive(Clone)]
+struct SharedBackend(Arc<Mutex<TestBackend>>);
+
+impl Backend for SharedBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.0.lock().unwrap().draw(content)
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.0.lock().unwrap().append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.0.lock().unwrap().get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.0.lock().unwrap().set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.0.lock().unwrap().clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.0.lock().unwrap().size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.0.lock().unwrap().window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl SharedBackend {
+    fn rows(&self) -> Vec<String> {
+        let backend = self.0.lock().unwrap();
+        let buffer = backend.buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+enum Msg {
+    Step,
+    Finish,
+}
+
+/// Prints a line per step and shows its progress in the live region
+struct StepsModel {
+    step: usize,
+}
+
+impl Model for StepsModel {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        commands::tick(Duration::from_millis(20), || Msg::Step)
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        match event {
+            Event::User(Msg::Step) => {
+                self.step += 1;
+                let next = if self.step < 2 {
+                    commands::tick(Duration::from_millis(20), || Msg::Step)
+                } else {
+                    commands::tick(Duration::from_millis(50), || Msg::Finish)
+                };
+                commands::sequence(vec![
+                    commands::println(format!("finished step {}", self.step)),
+                    next,
+                ])
+            }
+            Event::User(Msg::Finish) => commands::quit(),
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        let text = format!("progress {}/2\n[{}]", self.step, "#".repeat(self.step));
+        frame.render_widget(Paragraph::new(text), area);
+    }
+}
+
+#[test]
+fn test_inline_program_prints_above_and_leaves_final_frame() {
+    let backend = SharedBackend(Arc::new(Mutex::new(TestBackend::new(30, 10))));
+    let options = ProgramOptions::default()
+        .with_inline(4)
+        .with_input_string("")
+        .without_signal_handler();
+
+    let program = Program::with_backend(StepsModel { step: 0 }, backend.clone(), options).unwrap();
+    program.println("starting");
+    program.run_with_timeout(Duration::from_secs(5)).unwrap();
+
+    let rows = backend.rows();
+    assert_eq!(
+        &rows[..5],
+        &[
+            "starting",
+            "finished step 1",
+            "finished step 2",
+            "progress 2/2",
+            "[##]",
+        ]
+    );
+    assert!(rows[5..].iter().all(|row| row.is_empty()), "{rows:?}");
+
+    // The cursor is left on the line after the final frame
+    let cursor = backend.0.lock().unwrap().get_cursor_position().unwrap();
+    assert_eq!(cursor.y, 5);
+}
@@ -0,0 +1,155 @@
+//! Integration tests for the middleware stack around update
+
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::{Event, Key};
+use hojicha_runtime::middleware::{Context, KeyMap, Middleware, Recorder};
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::{layout::Rect, Frame};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Msg {
+    Start,
+    Work,
+    Done,
+}
+
+/// Records what reaches update
+struct Worker {
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+impl Model for Worker {
+    type Message = Msg;
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        let cmd = match &event {
+            Event::User(Msg::Start) => Cmd::new(|| Some(Msg::Work)),
+            Event::User(Msg::Done) => Cmd::quit(),
+            Event::User(_) | Event::Key(_) => Cmd::none(),
+            _ => return Cmd::none(),
+        };
+        self.seen.lock().unwrap().push(format!("{:?}", event));
+        cmd
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+/// Starts work on `s` and follows every command with `Done`
+struct StartKey;
+
+impl Middleware<Worker> for StartKey {
+    fn on_event(&mut self, event: Event<Msg>, ctx: &mut Context<'_, Worker>) -> Option<Event<Msg>> {
+        if event.is_key_press(Key::Char('s')) {
+            ctx.inject(Event::User(Msg::Start));
+            return None;
+        }
+        Some(event)
+    }
+
+    fn on_command(&mut self, cmd: Cmd<Msg>, _ctx: &mut Context<'_, Worker>) -> Cmd<Msg> {
+        if cmd.is_noop() || cmd.is_quit() {
+            return cmd;
+        }
+        Cmd::sequence(vec![cmd, Cmd::new(|| Some(Msg::Done))])
+    }
+}
+
+#[test]
+fn test_middleware_remaps_injects_and_wraps_commands() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let model = Worker {
+        seen: Arc::clone(&seen),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("js");
+    let recorder = Recorder::new();
+    let recording = recorder.recording();
+
+    Program::with_backend(model, TestBackend::new(20, 5), options)
+        .unwrap()
+        .with_middleware(recorder)
+        .with_middleware(KeyMap::new().bind_key(Key::Char('j'), Key::Down))
+        .with_middleware(StartKey)
+        .run_with_timeout(Duration::from_secs(2))
+        .unwrap();
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 4, "{:?}", seen);
+    assert!(seen[0].contains("Down"), "{:?}", seen);
+    assert_eq!(seen[1..], ["User(Start)", "User(Work)", "User(Done)"]);
+
+    let recorded: Vec<Msg> = recording.take().into_iter().map(|(_, msg)| msg).collect();
+    assert_eq!(recorded, vec![Msg::Start, Msg::Work, Msg::Done]);
+}
+
+#[test]
+fn test_filter_runs_in_the_stack() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let model = Worker {
+        seen: Arc::clone(&seen),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("xs");
+
+    // The filter drops keys before StartKey ever sees `s`
+    Program::with_backend(model, TestBackend::new(20, 5), options)
+        .unwrap()
+        .with_filter(|_, event| (!matches!(event, Event::Key(_))).then_some(event))
+        .with_middleware(StartKey)
+        .run_with_timeout(Duration::from_millis(200))
+        .unwrap();
+
+    assert!(seen.lock().unwrap().is_empty());
+}
+
+/// Panics on `x`, and on the first command that does something
+struct Fragile {
+    panicked: bool,
+}
+
+impl Middleware<Worker> for Fragile {
+    fn on_event(&mut self, event: Event<Msg>, _ctx: &mut Context<'_, Worker>) -> Option<Event<Msg>> {
+        if event.is_key_press(Key::Char('x')) {
+            panic!("middleware panicked on x");
+        }
+        Some(event)
+    }
+
+    fn on_command(&mut self, cmd: Cmd<Msg>, _ctx: &mut Context<'_, Worker>) -> Cmd<Msg> {
+        if !cmd.is_noop() && !self.panicked {
+            self.panicked = true;
+            panic!("middleware panicked on a command");
+        }
+        cmd
+    }
+}
+
+#[test]
+fn test_panics_in_middleware_are_recovered() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let model = Worker {
+        seen: Arc::clone(&seen),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("xsj");
+
+    // `x` is dropped, and the command for `Start` is lost to the second panic
+    Program::with_backend(model, TestBackend::new(20, 5), options)
+        .unwrap()
+        .with_middleware(StartKey)
+        .with_middleware(Fragile { panicked: false })
+        .run_with_timeout(Duration::from_millis(200))
+        .unwrap();
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 2, "{:?}", seen);
+    assert!(seen[0].contains("'j'"), "{:?}", seen);
+    assert_eq!(seen[1], "User(Start)");
+}
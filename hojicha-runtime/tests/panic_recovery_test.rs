@@ -2,7 +2,7 @@
 
 use hojicha_core::core::{Cmd, Model};
 use hojicha_core::event::Event;
-use hojicha_runtime::panic_recovery::{PanicRecoveryStrategy, safe_init, safe_update};
+use hojicha_runtime::panic_recovery::{PanicRecovery, PanicRecoveryStrategy, safe_init, safe_update};
 use hojicha_runtime::{Program, ProgramOptions};
 use ratatui::{Frame, layout::Rect};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -150,4 +150,81 @@ fn test_panic_recovery_quit_strategy() {
     
     // Program should exit immediately due to panic in init
     assert!(result.is_ok());
-}
\ No newline at end of file
+}
+
+/// Panics on every update until it is replaced by a fresh copy
+struct Fragile {
+    broken: bool,
+    inits: Arc<AtomicUsize>,
+    updates: Arc<AtomicUsize>,
+}
+
+impl Model for Fragile {
+    type Message = TestMsg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        self.inits.fetch_add(1, Ordering::SeqCst);
+        Cmd::new(|| Some(TestMsg::Recovered))
+    }
+
+    fn update(&mut self, _event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if self.broken {
+            panic!("Fragile model broke");
+        }
+        self.updates.fetch_add(1, Ordering::SeqCst);
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+#[test]
+fn test_reset_strategy_rebuilds_model() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let updates = Arc::new(AtomicUsize::new(0));
+    let model = Fragile {
+        broken: true,
+        inits: inits.clone(),
+        updates: updates.clone(),
+    };
+
+    let options = ProgramOptions::new()
+        .headless()
+        .without_signal_handler()
+        .with_panic_recovery(PanicRecoveryStrategy::Reset);
+    let (fresh_inits, fresh_updates) = (inits.clone(), updates.clone());
+    let program = Program::with_options(model, options)
+        .unwrap()
+        .with_reset(move || Fragile {
+            broken: false,
+            inits: fresh_inits.clone(),
+            updates: fresh_updates.clone(),
+        });
+    program.run_with_timeout(Duration::from_millis(200)).unwrap();
+
+    // The broken model panics on its init message, the fresh one handles its own
+    assert_eq!(inits.load(Ordering::SeqCst), 2);
+    assert!(updates.load(Ordering::SeqCst) >= 1);
+}
+
+#[test]
+fn test_panic_report_has_location() {
+    let mut model = PanickyModel {
+        panic_in_init: false,
+        panic_in_update: true,
+        panic_in_view: false,
+        update_count: Arc::new(AtomicUsize::new(0)),
+        view_count: Arc::new(AtomicUsize::new(0)),
+        recovered: Arc::new(AtomicBool::new(false)),
+    };
+
+    let mut recovery = PanicRecovery::new(PanicRecoveryStrategy::ShowError);
+    let cmd = recovery.update(&mut model, Event::Tick);
+    assert!(cmd.is_noop());
+
+    let report = recovery.error().expect("error screen should be shown");
+    assert_eq!(report.message, "Intentional panic in update!");
+    let location = report.location.as_deref().expect("panic location");
+    assert!(location.starts_with("hojicha-runtime/tests/panic_recovery_test.rs:"));
+    assert!(report.to_string().contains(location));
+}
@@ -0,0 +1,122 @@
+//! Integration tests for ProgramHandle used after run has taken the program
+
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions, SendError};
+use ratatui::backend::TestBackend;
+use ratatui::{layout::Rect, Frame};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Msg {
+    Number(u32),
+}
+
+/// Records every number it receives
+struct Collector {
+    numbers: Arc<Mutex<Vec<u32>>>,
+}
+
+impl Model for Collector {
+    type Message = Msg;
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if let Event::User(Msg::Number(n)) = event {
+            self.numbers.lock().unwrap().push(n);
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+fn program(numbers: &Arc<Mutex<Vec<u32>>>) -> Program<Collector> {
+    let model = Collector {
+        numbers: Arc::clone(numbers),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("")
+        .with_message_capacity(4);
+    Program::with_backend(model, TestBackend::new(20, 5), options).unwrap()
+}
+
+fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !condition() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_handle_sends_from_tokio_task_and_quits() {
+    let numbers = Arc::new(Mutex::new(Vec::new()));
+    let mut program = program(&numbers);
+    let handle = program.handle();
+    assert!(!handle.is_running());
+    assert_eq!(handle.queue_capacity(), 1000);
+
+    let runner = thread::spawn(move || program.run());
+    wait_until(|| handle.is_running());
+    assert!(handle.is_running());
+
+    // Many more messages than the channel holds, sent without blocking
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let sender = handle.clone();
+    runtime.block_on(async move {
+        for n in 0..50 {
+            sender.send(Msg::Number(n)).await.unwrap();
+        }
+    });
+    wait_until(|| numbers.lock().unwrap().len() == 50);
+    handle.quit();
+
+    runner.join().unwrap().unwrap();
+    assert_eq!(*numbers.lock().unwrap(), (0..50).collect::<Vec<_>>());
+    assert!(!handle.is_running());
+
+    // The program is gone, so the message comes back
+    assert_eq!(
+        handle.try_send(Msg::Number(99)),
+        Err(SendError::Closed(Msg::Number(99)))
+    );
+}
+
+#[test]
+fn test_handle_kill_stops_the_loop() {
+    let numbers = Arc::new(Mutex::new(Vec::new()));
+    let mut program = program(&numbers);
+    let handle = program.handle();
+
+    let runner = thread::spawn(move || program.run());
+    wait_until(|| handle.is_running());
+
+    handle.kill();
+    runner.join().unwrap().unwrap();
+    assert!(!handle.is_running());
+}
+
+#[test]
+fn test_handle_quit_gets_through_a_full_channel() {
+    let numbers = Arc::new(Mutex::new(Vec::new()));
+    let mut program = program(&numbers);
+    let handle = program.handle();
+
+    for n in 0..4 {
+        handle.try_send(Msg::Number(n)).unwrap();
+    }
+    assert!(matches!(
+        handle.try_send(Msg::Number(4)),
+        Err(SendError::Full(_))
+    ));
+    handle.quit();
+
+    let runner = thread::spawn(move || program.run());
+    runner.join().unwrap().unwrap();
+    assert!(!handle.is_running());
+}
@@ -0,0 +1,157 @@
+//! Integration tests for redrawing only when something changed
+
+use hojicha_core::commands::{self, TerminalControlMsg};
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::{layout::Rect, Frame};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+enum Msg {
+    Poke,
+}
+
+/// Counts frames, running `init` once at startup
+struct FrameCounter {
+    init: Option<Cmd<Msg>>,
+    frames: Arc<AtomicUsize>,
+}
+
+impl FrameCounter {
+    fn new(init: Cmd<Msg>) -> (Self, Arc<AtomicUsize>) {
+        let frames = Arc::new(AtomicUsize::new(0));
+        let model = Self {
+            init: Some(init),
+            frames: Arc::clone(&frames),
+        };
+        (model, frames)
+    }
+}
+
+impl Model for FrameCounter {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        self.init.take().unwrap_or_else(Cmd::none)
+    }
+
+    fn update(&mut self, _event: Event<Self::Message>) -> Cmd<Self::Message> {
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Options for a program that only redraws when something changed
+fn options() -> ProgramOptions {
+    ProgramOptions::default()
+        .with_fps(50)
+        .without_signal_handler()
+        .with_input_string("")
+}
+
+/// Run for `duration` and return how many frames were drawn
+fn count_frames(init: Cmd<Msg>, duration: Duration) -> usize {
+    let (model, frames) = FrameCounter::new(init);
+    let options = options();
+
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    program.run_with_timeout(duration).unwrap();
+    frames.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_idle_program_draws_only_the_first_frame() {
+    // Idle ticks arrive meanwhile, but they don't change anything
+    let frames = count_frames(Cmd::none(), Duration::from_millis(600));
+    assert_eq!(frames, 1);
+}
+
+#[test]
+fn test_updates_and_invalidate_redraw() {
+    let poke = commands::tick(Duration::from_millis(100), || Msg::Poke);
+    let frames = count_frames(poke, Duration::from_millis(300));
+    assert_eq!(frames, 2, "first frame plus one for the update");
+
+    // Nothing reaches update here, the command alone asks for a frame
+    let (model, frames) = FrameCounter::new(Cmd::none());
+    let mut program = Program::with_backend(model, TestBackend::new(20, 5), options()).unwrap();
+    let sender = program.init_async_bridge();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        let invalidate = Event::TerminalControl(TerminalControlMsg::Invalidate);
+        sender.send(invalidate).unwrap();
+    });
+    program
+        .run_with_timeout(Duration::from_millis(300))
+        .unwrap();
+    let frames = frames.load(Ordering::SeqCst);
+    assert_eq!(frames, 2, "first frame plus one for the invalidate");
+}
+
+#[test]
+fn test_animation_draws_at_the_fps_cap() {
+    let frames = count_frames(
+        commands::animate(Duration::from_millis(300)),
+        Duration::from_millis(600),
+    );
+
+    // 50 fps for 300ms is about 15 frames, and nothing is drawn afterwards
+    assert!((8..=17).contains(&frames), "drew {frames} frames");
+}
+
+/// Counts its ticks and shows the count, like a clock animating on `Tick`
+#[derive(Default)]
+struct TickCounter {
+    ticks: usize,
+    drawn: Arc<AtomicUsize>,
+    /// Ask for a redraw after each tick
+    invalidate: bool,
+}
+
+impl Model for TickCounter {
+    type Message = Msg;
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if let Event::Tick = event {
+            self.ticks += 1;
+            if self.invalidate {
+                return commands::invalidate();
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {
+        self.drawn.store(self.ticks, Ordering::SeqCst);
+    }
+}
+
+/// Run a `TickCounter` and return the tick count its last frame showed
+fn last_drawn_tick(options: ProgramOptions, invalidate: bool) -> usize {
+    let model = TickCounter {
+        invalidate,
+        ..TickCounter::default()
+    };
+    let drawn = Arc::clone(&model.drawn);
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    program.run_with_timeout(Duration::from_millis(300)).unwrap();
+    drawn.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_ticks_redraw_models_that_change_on_them() {
+    // Ticks are skipped unless their update returns a command. The first
+    // frame is drawn after the first tick either way.
+    assert_eq!(last_drawn_tick(options(), false), 1);
+    assert!(last_drawn_tick(options(), true) > 1);
+
+    let redrawing = options().with_tick_redraws();
+    assert!(last_drawn_tick(redrawing, false) > 1, "tick changes were never drawn");
+}
@@ -0,0 +1,262 @@
+//! Integration tests for running programs on the caller's Tokio runtime
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{PriorityConfig, Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::widgets::Paragraph;
+use ratatui::{layout::Rect, Frame};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+enum Msg {
+    Fetched(String),
+    Ping,
+}
+
+/// Records which thread ran its async command, then quits
+#[derive(Default)]
+struct FetchModel {
+    fetched_on: Arc<Mutex<Option<String>>>,
+    frames: Arc<AtomicUsize>,
+}
+
+impl Model for FetchModel {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        commands::spawn(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let thread = std::thread::current()
+                .name()
+                .unwrap_or_default()
+                .to_string();
+            Some(Msg::Fetched(thread))
+        })
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        match event {
+            Event::User(Msg::Fetched(thread)) => {
+                *self.fetched_on.lock().unwrap() = Some(thread);
+                commands::quit()
+            }
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, frame: &mut Frame, area: Rect) {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+        frame.render_widget(Paragraph::new("fetching"), area);
+    }
+}
+
+#[test]
+fn test_run_async_spawns_commands_on_the_callers_runtime() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("app-runtime")
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let model = FetchModel::default();
+    let fetched_on = model.fetched_on.clone();
+    let options = ProgramOptions::default()
+        .headless()
+        .without_signal_handler();
+
+    runtime.block_on(async {
+        let program = Program::with_options(model, options).unwrap();
+        program.run_async().await.unwrap();
+    });
+
+    assert_eq!(fetched_on.lock().unwrap().as_deref(), Some("app-runtime"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_async_renders_and_can_be_spawned() {
+    let model = FetchModel::default();
+    let (fetched_on, frames) = (model.fetched_on.clone(), model.frames.clone());
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("");
+
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), tokio::spawn(program.run_async()))
+        .await
+        .expect("program didn't quit")
+        .unwrap()
+        .unwrap();
+
+    assert!(fetched_on.lock().unwrap().is_some());
+    assert!(frames.load(Ordering::SeqCst) > 0);
+}
+
+/// Pings itself with zero-delay ticks and quits after the last one
+struct PingModel {
+    remaining: usize,
+}
+
+impl Model for PingModel {
+    type Message = Msg;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        commands::tick(Duration::ZERO, || Msg::Ping)
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        match event {
+            Event::User(Msg::Ping) if self.remaining <= 1 => commands::quit(),
+            Event::User(Msg::Ping) => {
+                self.remaining -= 1;
+                commands::tick(Duration::ZERO, || Msg::Ping)
+            }
+            _ => Cmd::none(),
+        }
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+/// Run a program that bounces ten messages and return how long it took
+fn time_pings(options: ProgramOptions, run_async: bool) -> Duration {
+    let model = PingModel { remaining: 10 };
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+
+    let start = Instant::now();
+    if run_async {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(program.run_async())
+            .unwrap();
+    } else {
+        program.run().unwrap();
+    }
+    start.elapsed()
+}
+
+#[test]
+fn test_messages_wake_the_loop_immediately() {
+    // Each message used to wait for the 250ms input poll to time out
+    for run_async in [false, true] {
+        let options = ProgramOptions::default()
+            .without_signal_handler()
+            .with_input_string("");
+        let elapsed = time_pings(options, run_async);
+        assert!(
+            elapsed < Duration::from_secs(1),
+            "ten messages took {elapsed:?} (async: {run_async})"
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_program_can_be_dropped_on_the_runtime_it_was_created_on() {
+    // The program shares the caller's runtime instead of creating one that
+    // couldn't be dropped from an async context
+    let options = ProgramOptions::default()
+        .headless()
+        .without_signal_handler();
+    let program = Program::with_options(FetchModel::default(), options).unwrap();
+    drop(program);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_message_bridge_stops_with_the_program() {
+    let options = ProgramOptions::default()
+        .headless()
+        .without_signal_handler();
+    let mut program = Program::with_options(FetchModel::default(), options).unwrap();
+    let sender = program.init_async_bridge();
+
+    tokio::time::timeout(Duration::from_secs(5), program.run_async())
+        .await
+        .expect("program didn't quit")
+        .unwrap();
+
+    // The bridge owned the receiving end, so the channel closes once it stops
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while sender.try_send(Event::Tick).is_ok() {
+        assert!(Instant::now() < deadline, "message bridge is still running");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(matches!(
+        sender.try_send(Event::Tick),
+        Err(std::sync::mpsc::TrySendError::Disconnected(_))
+    ));
+}
+
+/// Sends a burst of numbered messages and quits once it has seen them all
+struct BurstModel {
+    received: Arc<Mutex<Vec<usize>>>,
+}
+
+/// More messages than the priority queue holds
+const BURST: usize = 200;
+
+impl Model for BurstModel {
+    type Message = usize;
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        commands::batch(
+            (0..BURST)
+                .map(|i| commands::spawn(async move { Some(i) }))
+                .collect(),
+        )
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if let Event::User(i) = event {
+            let mut received = self.received.lock().unwrap();
+            received.push(i);
+            if received.len() == BURST {
+                return commands::quit();
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+#[test]
+fn test_a_full_queue_loses_no_messages() {
+    for run_async in [false, true] {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let model = BurstModel {
+            received: received.clone(),
+        };
+        let options = ProgramOptions::default()
+            .headless()
+            .without_signal_handler();
+        let program = Program::with_options(model, options)
+            .unwrap()
+            .with_priority_config(PriorityConfig {
+                max_queue_size: 4,
+                ..PriorityConfig::default()
+            });
+
+        if run_async {
+            tokio::runtime::Runtime::new().unwrap().block_on(async {
+                tokio::time::timeout(Duration::from_secs(5), program.run_async())
+                    .await
+                    .expect("program didn't get every message")
+                    .unwrap();
+            });
+        } else {
+            program.run_with_timeout(Duration::from_secs(5)).unwrap();
+        }
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort_unstable();
+        assert_eq!(
+            received,
+            (0..BURST).collect::<Vec<_>>(),
+            "async: {run_async}"
+        );
+    }
+}
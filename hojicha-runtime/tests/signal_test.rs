@@ -0,0 +1,112 @@
+//! Integration tests for OS signal handling
+//!
+//! Signals go to the whole test process, so every program in this file would
+//! see them. The tests take a lock to run one at a time.
+#![cfg(unix)]
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::{layout::Rect, Frame};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+static SIGNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sends a signal to the test process from init and records what it sees
+struct SignalModel {
+    signal: libc::c_int,
+    quit_on_interrupt: bool,
+    seen: Arc<Mutex<Vec<Event<()>>>>,
+}
+
+impl Model for SignalModel {
+    type Message = ();
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        let signal = self.signal;
+        commands::custom(move || {
+            unsafe {
+                libc::kill(libc::getpid(), signal);
+            }
+            None
+        })
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        let interrupted = event.is_interrupt();
+        self.seen.lock().unwrap().push(event);
+        if interrupted && self.quit_on_interrupt {
+            commands::quit()
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+/// Run a headless program that signals itself, returning how long it ran
+fn run_with_signal(signal: libc::c_int, seen: Arc<Mutex<Vec<Event<()>>>>) -> Duration {
+    let _lock = SIGNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let model = SignalModel {
+        signal,
+        quit_on_interrupt: true,
+        seen,
+    };
+
+    let start = Instant::now();
+    let program = Program::with_options(model, ProgramOptions::default().headless()).unwrap();
+    program.run_with_timeout(Duration::from_secs(10)).unwrap();
+    start.elapsed()
+}
+
+#[test]
+fn test_sigterm_quits_gracefully() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let elapsed = run_with_signal(libc::SIGTERM, seen.clone());
+    assert!(elapsed < Duration::from_secs(5), "program ran for {elapsed:?}");
+    assert!(!seen.lock().unwrap().iter().any(|e| e.is_interrupt()));
+}
+
+#[test]
+fn test_sighup_quits_gracefully() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let elapsed = run_with_signal(libc::SIGHUP, seen);
+    assert!(elapsed < Duration::from_secs(5), "program ran for {elapsed:?}");
+}
+
+#[test]
+fn test_sigint_is_delivered_to_model() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let elapsed = run_with_signal(libc::SIGINT, seen.clone());
+    assert!(elapsed < Duration::from_secs(5), "program ran for {elapsed:?}");
+    assert!(seen.lock().unwrap().iter().any(|e| e.is_interrupt()));
+}
+
+/// Whether `signal` has its default disposition
+fn is_default(signal: libc::c_int) -> bool {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        assert_eq!(libc::sigaction(signal, std::ptr::null(), &mut action), 0);
+        action.sa_sigaction == libc::SIG_DFL
+    }
+}
+
+#[test]
+fn test_signals_terminate_again_after_the_program() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    run_with_signal(libc::SIGHUP, seen.clone());
+    let lock = SIGNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(is_default(libc::SIGTERM));
+    assert!(is_default(libc::SIGHUP));
+    assert!(is_default(libc::SIGINT));
+    drop(lock);
+
+    // A later program handles them again
+    let elapsed = run_with_signal(libc::SIGHUP, seen);
+    assert!(elapsed < Duration::from_secs(5), "program ran for {elapsed:?}");
+    let _lock = SIGNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(is_default(libc::SIGHUP));
+}
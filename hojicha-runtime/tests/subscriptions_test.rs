@@ -0,0 +1,83 @@
+//! Integration tests for subscriptions declared by the model
+
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_core::subscriptions::{Sub, Subs};
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::backend::TestBackend;
+use ratatui::{layout::Rect, Frame};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+enum Msg {
+    Spin,
+}
+
+/// Shows a spinner for a few frames, then hides it
+struct Spinner {
+    visible: bool,
+    frames_left: usize,
+    spins: Arc<AtomicUsize>,
+}
+
+impl Model for Spinner {
+    type Message = Msg;
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        if let Event::User(Msg::Spin) = event {
+            self.spins.fetch_add(1, Ordering::SeqCst);
+            self.frames_left = self.frames_left.saturating_sub(1);
+            self.visible = self.frames_left > 0;
+        }
+        Cmd::none()
+    }
+
+    fn subscriptions(&self) -> Subs<Self::Message> {
+        if self.visible {
+            Sub::every("spinner", Duration::from_millis(20), |_| Msg::Spin).into()
+        } else {
+            Subs::none()
+        }
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+#[test]
+fn test_subscription_runs_only_while_declared() {
+    let spins = Arc::new(AtomicUsize::new(0));
+    let model = Spinner {
+        visible: true,
+        frames_left: 3,
+        spins: Arc::clone(&spins),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("");
+
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    program.run_with_timeout(Duration::from_millis(400)).unwrap();
+
+    // Three spins at 20ms each, then the timer is cancelled
+    assert_eq!(spins.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_hidden_spinner_never_subscribes() {
+    let spins = Arc::new(AtomicUsize::new(0));
+    let model = Spinner {
+        visible: false,
+        frames_left: 3,
+        spins: Arc::clone(&spins),
+    };
+    let options = ProgramOptions::default()
+        .without_signal_handler()
+        .with_input_string("");
+
+    let program = Program::with_backend(model, TestBackend::new(20, 5), options).unwrap();
+    program.run_with_timeout(Duration::from_millis(200)).unwrap();
+
+    assert_eq!(spins.load(Ordering::SeqCst), 0);
+}
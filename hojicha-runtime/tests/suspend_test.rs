@@ -0,0 +1,115 @@
+//! Integration tests for suspending and resuming the program
+
+use hojicha_core::commands;
+use hojicha_core::core::{Cmd, Model};
+use hojicha_core::event::Event;
+use hojicha_runtime::{Program, ProgramOptions};
+use ratatui::{layout::Rect, Frame};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Seen {
+    Suspend,
+    Resume,
+    After,
+}
+
+/// How many times the program tried to stop the process
+static STOPS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_stop() {
+    STOPS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Records suspend-related events and optionally vetoes the suspend
+struct SuspendModel {
+    init: Option<Cmd<()>>,
+    veto: Option<fn() -> Cmd<()>>,
+    seen: Arc<Mutex<Vec<Seen>>>,
+}
+
+impl Model for SuspendModel {
+    type Message = ();
+
+    fn init(&mut self) -> Cmd<Self::Message> {
+        self.init.take().unwrap_or_else(Cmd::none)
+    }
+
+    fn update(&mut self, event: Event<Self::Message>) -> Cmd<Self::Message> {
+        let mut seen = self.seen.lock().unwrap();
+        match event {
+            Event::Suspend => {
+                seen.push(Seen::Suspend);
+                if let Some(veto) = self.veto {
+                    return veto();
+                }
+            }
+            Event::Resume => seen.push(Seen::Resume),
+            Event::User(()) => seen.push(Seen::After),
+            _ => {}
+        }
+        Cmd::none()
+    }
+
+    fn view(&self, _frame: &mut Frame, _area: Rect) {}
+}
+
+fn run_suspend(
+    init: Cmd<()>,
+    veto: Option<fn() -> Cmd<()>>,
+    options: ProgramOptions,
+    expected: usize,
+) -> Vec<Seen> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let model = SuspendModel {
+        init: Some(init),
+        veto,
+        seen: seen.clone(),
+    };
+
+    let program = Program::with_options(model, options.headless())
+        .unwrap()
+        .with_stop_process(count_stop);
+    program
+        .run_until(move |model: &SuspendModel| model.seen.lock().unwrap().len() >= expected)
+        .unwrap();
+
+    let events = seen.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn test_model_can_veto_suspend() {
+    let init = commands::sequence(vec![commands::suspend(), commands::custom(|| Some(()))]);
+    let events = run_suspend(
+        init,
+        Some(commands::cancel_suspend),
+        ProgramOptions::default(),
+        2,
+    );
+    assert_eq!(events, vec![Seen::Suspend, Seen::After]);
+}
+
+#[test]
+fn test_veto_inside_batch_or_sequence_counts() {
+    let vetoes: [fn() -> Cmd<()>; 2] = [
+        || commands::batch(vec![commands::custom(|| None), commands::cancel_suspend()]),
+        || commands::sequence(vec![commands::cancel_suspend(), commands::custom(|| None)]),
+    ];
+    for veto in vetoes {
+        let init = commands::sequence(vec![commands::suspend(), commands::custom(|| Some(()))]);
+        let events = run_suspend(init, Some(veto), ProgramOptions::default(), 2);
+        assert_eq!(events, vec![Seen::Suspend, Seen::After]);
+    }
+}
+
+#[test]
+fn test_ctrl_z_suspends_and_resumes() {
+    let before = STOPS.load(Ordering::SeqCst);
+    let options = ProgramOptions::default().with_input_string("\x1a");
+    let events = run_suspend(Cmd::none(), None, options, 2);
+
+    assert_eq!(events, vec![Seen::Suspend, Seen::Resume]);
+    assert!(STOPS.load(Ordering::SeqCst) > before);
+}
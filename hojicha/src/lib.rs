@@ -7,6 +7,13 @@
 //! - `hojicha-runtime`: Event loop and async runtime (Program)
 //! - `hojicha-pearls`: UI components and styling (optional)
 //!
+//! ## Features
+//!
+//! - `pearls` (default): the components of `hojicha-pearls`
+//! - `http`, `websocket`, `watch`: the network and file watching backends of
+//!   [`async_helpers`]
+//! - `full`: all of the above
+//!
 //! ## Quick Start
 //!
 //! ```no_run